@@ -86,6 +86,7 @@ See the `custom_collector` example.
 */
 
 use downcast_rs;
+use query::{Scorer, Weight};
 use DocId;
 use Result;
 use Score;
@@ -153,6 +154,31 @@ pub trait Collector: Sync {
     /// Combines the fruit associated to the collection of each segments
     /// into one fruit.
     fn merge_fruits(&self, segment_fruits: Vec<Self::Fruit>) -> Result<Self::Fruit>;
+
+    /// Collects the documents of the given segment matching the `Weight`,
+    /// and returns the associated fruit.
+    ///
+    /// By default, all of the documents that are not deleted are pushed
+    /// to the `SegmentCollector` returned by `.for_segment(...)`.
+    fn collect_segment(
+        &self,
+        weight: &Weight,
+        segment_local_id: SegmentLocalId,
+        reader: &SegmentReader,
+    ) -> Result<Self::Fruit> {
+        let mut scorer = weight.scorer(reader)?;
+        let mut segment_collector = self.for_segment(segment_local_id, reader)?;
+        if let Some(delete_bitset) = reader.delete_bitset() {
+            scorer.for_each(&mut |doc, score| {
+                if !delete_bitset.is_deleted(doc) {
+                    segment_collector.collect(doc, score);
+                }
+            });
+        } else {
+            scorer.for_each(&mut |doc, score| segment_collector.collect(doc, score));
+        }
+        Ok(segment_collector.harvest())
+    }
 }
 
 /// The `SegmentCollector` is the trait in charge of defining the
@@ -151,6 +151,19 @@ impl<T: PartialOrd + Clone> TopSegmentCollector<T> {
         self.heap.len() >= self.limit
     }
 
+    /// Returns the smallest feature of the top K documents,
+    /// or `None` if less than K documents have been collected.
+    ///
+    /// Only the documents with a strictly greater feature
+    /// can enter the top K.
+    pub(crate) fn threshold(&self) -> Option<T> {
+        if self.at_capacity() {
+            self.heap.peek().map(|head| head.feature.clone())
+        } else {
+            None
+        }
+    }
+
     /// Collects a document scored by the given feature
     ///
     /// It collects documents until it has reached the max capacity. Once it reaches capacity, it
@@ -216,6 +229,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_top_collector_threshold() {
+        let mut top_collector = TopSegmentCollector::new(0, 2);
+        assert_eq!(top_collector.threshold(), None);
+        top_collector.collect(1, 0.8);
+        assert_eq!(top_collector.threshold(), None);
+        top_collector.collect(3, 0.2);
+        assert_eq!(top_collector.threshold(), Some(0.2));
+        top_collector.collect(5, 0.3);
+        assert_eq!(top_collector.threshold(), Some(0.3));
+        top_collector.collect(7, 0.1);
+        assert_eq!(top_collector.threshold(), Some(0.3));
+    }
+
     #[test]
     #[should_panic]
     fn test_top_0() {
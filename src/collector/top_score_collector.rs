@@ -4,7 +4,9 @@ use collector::top_collector::TopSegmentCollector;
 use collector::SegmentCollector;
 use collector::TopDocsByField;
 use fastfield::FastValue;
+use query::Weight;
 use schema::Field;
+use std::f32;
 use DocAddress;
 use DocId;
 use Result;
@@ -108,11 +110,47 @@ impl Collector for TopDocs {
     fn merge_fruits(&self, child_fruits: Vec<Vec<(Score, DocAddress)>>) -> Result<Self::Fruit> {
         self.0.merge_fruits(child_fruits)
     }
+
+    /// Collects the top K documents of the segment, feeding the
+    /// threshold of the `TopScoreSegmentCollector` back to the `Weight`,
+    /// so that documents that cannot make it to the top K are skipped.
+    fn collect_segment(
+        &self,
+        weight: &Weight,
+        segment_local_id: SegmentLocalId,
+        reader: &SegmentReader,
+    ) -> Result<Self::Fruit> {
+        let mut segment_collector = self.for_segment(segment_local_id, reader)?;
+        {
+            let delete_bitset_opt = reader.delete_bitset();
+            let mut callback = |doc: DocId, score: Score| {
+                if let Some(delete_bitset) = delete_bitset_opt {
+                    if delete_bitset.is_deleted(doc) {
+                        return segment_collector.threshold();
+                    }
+                }
+                segment_collector.collect(doc, score);
+                segment_collector.threshold()
+            };
+            weight.for_each_pruning(f32::NEG_INFINITY, reader, &mut callback)?;
+        }
+        Ok(segment_collector.harvest())
+    }
 }
 
 /// Segment Collector associated to `TopDocs`.
 pub struct TopScoreSegmentCollector(TopSegmentCollector<Score>);
 
+impl TopScoreSegmentCollector {
+    /// Returns the score a document must strictly exceed to enter
+    /// the top K documents collected so far.
+    ///
+    /// This is `-inf` until K documents have been collected.
+    pub fn threshold(&self) -> Score {
+        self.0.threshold().unwrap_or(f32::NEG_INFINITY)
+    }
+}
+
 impl SegmentCollector for TopScoreSegmentCollector {
     type Fruit = Vec<(Score, DocAddress)>;
 
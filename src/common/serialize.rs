@@ -102,6 +102,19 @@ impl FixedSize for i64 {
     const SIZE_IN_BYTES: usize = 8;
 }
 
+impl BinarySerializable for f32 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_f32::<Endianness>(*self)
+    }
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<Self> {
+        reader.read_f32::<Endianness>()
+    }
+}
+
+impl FixedSize for f32 {
+    const SIZE_IN_BYTES: usize = 4;
+}
+
 impl BinarySerializable for u8 {
     fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         writer.write_u8(*self)
@@ -159,6 +172,14 @@ pub mod test {
         fixed_size_test::<u8>();
     }
 
+    #[test]
+    fn test_serialize_f32() {
+        fixed_size_test::<f32>();
+        let mut buffer = Vec::new();
+        1.5f32.serialize(&mut buffer).unwrap();
+        assert_eq!(f32::deserialize(&mut &buffer[..]).unwrap(), 1.5f32);
+    }
+
     #[test]
     fn test_serialize_u32() {
         fixed_size_test::<u32>();
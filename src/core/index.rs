@@ -16,6 +16,7 @@ use indexer::index_writer::open_index_writer;
 use indexer::index_writer::HEAP_SIZE_MIN;
 use indexer::segment_updater::save_new_metas;
 use num_cpus;
+use query::SimilarityManager;
 use reader::IndexReader;
 use reader::IndexReaderBuilder;
 use schema::Field;
@@ -31,18 +32,24 @@ use tokenizer::BoxedTokenizer;
 use tokenizer::TokenizerManager;
 use IndexWriter;
 use Result;
+use INDEX_FORMAT_VERSION;
 
 fn load_metas(directory: &Directory) -> Result<IndexMeta> {
     let meta_data = directory.atomic_read(&META_FILEPATH)?;
     let meta_string = String::from_utf8_lossy(&meta_data);
-    serde_json::from_str(&meta_string)
-        .map_err(|e| {
-            DataCorruption::new(
-                META_FILEPATH.clone(),
-                format!("Meta file cannot be deserialized. {:?}.", e),
-            )
-        })
-        .map_err(From::from)
+    let metas: IndexMeta = serde_json::from_str(&meta_string).map_err(|e| {
+        DataCorruption::new(
+            META_FILEPATH.clone(),
+            format!("Meta file cannot be deserialized. {:?}.", e),
+        )
+    })?;
+    if metas.index_format_version != INDEX_FORMAT_VERSION {
+        return Err(TantivyError::IncompatibleIndex(
+            metas.index_format_version,
+            INDEX_FORMAT_VERSION,
+        ));
+    }
+    Ok(metas)
 }
 
 /// Search Index
@@ -52,6 +59,7 @@ pub struct Index {
     schema: Schema,
     executor: Arc<Executor>,
     tokenizers: TokenizerManager,
+    similarities: SimilarityManager,
 }
 
 impl Index {
@@ -158,6 +166,7 @@ impl Index {
             directory,
             schema,
             tokenizers: TokenizerManager::default(),
+            similarities: SimilarityManager::default(),
             executor: Arc::new(Executor::single_thread()),
         };
         Ok(index)
@@ -168,6 +177,23 @@ impl Index {
         &self.tokenizers
     }
 
+    /// Accessor for the similarity manager.
+    ///
+    /// The similarities registered are the ones the fields of the schema may refer to by name.
+    pub fn similarities(&self) -> &SimilarityManager {
+        &self.similarities
+    }
+
+    /// Replaces the tokenizer manager, and with it the tokenizers registered.
+    pub(crate) fn set_tokenizers(&mut self, tokenizers: TokenizerManager) {
+        self.tokenizers = tokenizers;
+    }
+
+    /// Replaces the similarity manager, and with it the similarities registered.
+    pub(crate) fn set_similarities(&mut self, similarities: SimilarityManager) {
+        self.similarities = similarities;
+    }
+
     /// Helper to access the tokenizer associated to a specific field.
     pub fn tokenizer_for_field(&self, field: Field) -> Result<Box<BoxedTokenizer>> {
         let field_entry = self.schema.get_field_entry(field);
@@ -353,15 +379,18 @@ impl fmt::Debug for Index {
 
 #[cfg(test)]
 mod tests {
-    use directory::RAMDirectory;
+    use core::META_FILEPATH;
+    use directory::{Directory, RAMDirectory};
     use schema::Field;
     use schema::{Schema, INDEXED, TEXT};
+    use serde_json;
     use std::thread;
     use std::time::Duration;
     use Index;
     use IndexReader;
     use IndexWriter;
     use ReloadPolicy;
+    use INDEX_FORMAT_VERSION;
 
     #[test]
     fn test_indexer_for_field() {
@@ -422,6 +451,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn open_should_refuse_incompatible_index_format() {
+        let mut directory = RAMDirectory::create();
+        let index = Index::create(directory.clone(), throw_away_schema()).unwrap();
+        let mut metas = index.load_metas().unwrap();
+        metas.index_format_version = 0;
+        // Indexes written before the format was versioned have no version in `meta.json`.
+        let meta_string = serde_json::to_string(&metas)
+            .unwrap()
+            .replace(r#","index_format_version":0"#, "");
+        assert!(!meta_string.contains("index_format_version"));
+        directory
+            .atomic_write(&META_FILEPATH, meta_string.as_bytes())
+            .unwrap();
+        let err = Index::open(directory).err().unwrap();
+        assert_eq!(
+            format!("{:?}", err),
+            format!("IncompatibleIndex(0, {})", INDEX_FORMAT_VERSION)
+        );
+    }
+
     fn throw_away_schema() -> Schema {
         let mut schema_builder = Schema::builder();
         let _ = schema_builder.add_u64_field("num_likes", INDEXED);
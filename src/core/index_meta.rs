@@ -3,6 +3,7 @@ use schema::Schema;
 use serde_json;
 use std::fmt;
 use Opstamp;
+use INDEX_FORMAT_VERSION;
 
 /// Meta information about the `Index`.
 ///
@@ -11,6 +12,7 @@ use Opstamp;
 /// * the searchable segments,
 /// * the index `docstamp`
 /// * the schema
+/// * the version of the format of the index files
 ///
 #[derive(Clone, Serialize, Deserialize)]
 pub struct IndexMeta {
@@ -19,6 +21,10 @@ pub struct IndexMeta {
     pub opstamp: Opstamp,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payload: Option<String>,
+    /// Version of the format of the index files, `0` for the indexes
+    /// written before the format was versioned.
+    #[serde(default)]
+    pub index_format_version: u32,
 }
 
 impl IndexMeta {
@@ -28,6 +34,7 @@ impl IndexMeta {
             schema,
             opstamp: 0u64,
             payload: None,
+            index_format_version: INDEX_FORMAT_VERSION,
         }
     }
 }
@@ -49,6 +56,7 @@ mod tests {
     use super::IndexMeta;
     use schema::{Schema, TEXT};
     use serde_json;
+    use INDEX_FORMAT_VERSION;
 
     #[test]
     fn test_serialize_metas() {
@@ -62,8 +70,12 @@ mod tests {
             schema,
             opstamp: 0u64,
             payload: None,
+            index_format_version: INDEX_FORMAT_VERSION,
         };
         let json = serde_json::ser::to_string(&index_metas).expect("serialization failed");
-        assert_eq!(json, r#"{"segments":[],"schema":[{"name":"text","type":"text","options":{"indexing":{"record":"position","tokenizer":"default"},"stored":false}}],"opstamp":0}"#);
+        assert_eq!(
+            json,
+            r#"{"segments":[],"schema":[{"name":"text","type":"text","options":{"indexing":{"record":"position","tokenizer":"default"},"stored":false}}],"opstamp":0,"index_format_version":1}"#
+        );
     }
 }
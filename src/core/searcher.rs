@@ -1,15 +1,17 @@
 use collector::Collector;
-use collector::SegmentCollector;
 use core::Executor;
 use core::InvertedIndexReader;
 use core::SegmentReader;
+use error::TantivyError;
+use query::Explanation;
+use query::FilterCache;
 use query::Query;
-use query::Scorer;
-use query::Weight;
+use query::Similarity;
 use schema::Document;
 use schema::Schema;
-use schema::{Field, Term};
+use schema::{Field, FieldType, Term};
 use space_usage::SearcherSpaceUsage;
+use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 use store::StoreReader;
@@ -18,26 +20,6 @@ use DocAddress;
 use Index;
 use Result;
 
-fn collect_segment<C: Collector>(
-    collector: &C,
-    weight: &Weight,
-    segment_ord: u32,
-    segment_reader: &SegmentReader,
-) -> Result<C::Fruit> {
-    let mut scorer = weight.scorer(segment_reader)?;
-    let mut segment_collector = collector.for_segment(segment_ord as u32, segment_reader)?;
-    if let Some(delete_bitset) = segment_reader.delete_bitset() {
-        scorer.for_each(&mut |doc, score| {
-            if !delete_bitset.is_deleted(doc) {
-                segment_collector.collect(doc, score);
-            }
-        });
-    } else {
-        scorer.for_each(&mut |doc, score| segment_collector.collect(doc, score));
-    }
-    Ok(segment_collector.harvest())
-}
-
 /// Holds a list of `SegmentReader`s ready for search.
 ///
 /// It guarantees that the `Segment` will not be removed before
@@ -48,6 +30,8 @@ pub struct Searcher {
     index: Index,
     segment_readers: Vec<SegmentReader>,
     store_readers: Vec<StoreReader>,
+    filter_cache: Arc<FilterCache>,
+    similarities: HashMap<Field, Arc<Similarity>>,
 }
 
 impl Searcher {
@@ -56,6 +40,7 @@ impl Searcher {
         schema: Schema,
         index: Index,
         segment_readers: Vec<SegmentReader>,
+        filter_cache: Arc<FilterCache>,
     ) -> Searcher {
         let store_readers = segment_readers
             .iter()
@@ -66,6 +51,8 @@ impl Searcher {
             index,
             segment_readers,
             store_readers,
+            filter_cache,
+            similarities: HashMap::new(),
         }
     }
 
@@ -74,6 +61,55 @@ impl Searcher {
         &self.index
     }
 
+    /// Returns the `FilterCache` shared by the searchers of the `IndexReader`.
+    pub fn filter_cache(&self) -> &Arc<FilterCache> {
+        &self.filter_cache
+    }
+
+    /// Returns the `Similarity` used to score the terms of a field.
+    ///
+    /// This is the similarity set on this searcher for the field if any, or else the
+    /// similarity of the field in the schema, looked up by name in the
+    /// [`SimilarityManager`](./query/struct.SimilarityManager.html) of the index.
+    pub fn similarity(&self, field: Field) -> Result<Arc<Similarity>> {
+        if let Some(similarity) = self.similarities.get(&field) {
+            return Ok(similarity.clone());
+        }
+        let similarity_name = match *self.schema.get_field_entry(field).field_type() {
+            FieldType::Str(ref text_options) => text_options
+                .get_indexing_options()
+                .map(|text_indexing_options| text_indexing_options.similarity())
+                .unwrap_or("default"),
+            _ => "default",
+        };
+        self.index
+            .similarities()
+            .get(similarity_name)
+            .ok_or_else(|| {
+                TantivyError::SchemaError(format!(
+                    "No similarity registered under the name {:?}",
+                    similarity_name
+                ))
+            })
+    }
+
+    /// Returns a `Searcher` over the same segments, scoring the terms of a field
+    /// with the given `Similarity` instead of the similarity of the field in the schema.
+    ///
+    /// The `Searcher`s of an `IndexReader` are pooled, so the override is not set on
+    /// this `Searcher`: it only applies to the searches done with the returned one.
+    pub fn with_similarity(&self, field: Field, similarity: Arc<Similarity>) -> Searcher {
+        let mut searcher = Searcher::new(
+            self.schema.clone(),
+            self.index.clone(),
+            self.segment_readers.clone(),
+            self.filter_cache.clone(),
+        );
+        searcher.similarities = self.similarities.clone();
+        searcher.similarities.insert(field, similarity);
+        searcher
+    }
+
     /// Fetches a document from tantivy's store given a `DocAddress`.
     ///
     /// The searcher uses the segment ordinal to route the
@@ -118,6 +154,16 @@ impl Searcher {
         &self.segment_readers[segment_ord as usize]
     }
 
+    /// Returns an `Explanation` for the score of the document at `doc_address`
+    /// for the given query.
+    ///
+    /// Returns an error if the document does not match the query.
+    pub fn explain(&self, query: &Query, doc_address: DocAddress) -> Result<Explanation> {
+        let DocAddress(segment_ord, doc_id) = doc_address;
+        let weight = query.weight(self, true)?;
+        weight.explain(self.segment_reader(segment_ord), doc_id)
+    }
+
     /// Runs a query on the segment readers wrapped by the searcher.
     ///
     /// Search works as follows :
@@ -160,12 +206,7 @@ impl Searcher {
         let segment_readers = self.segment_readers();
         let fruits = executor.map(
             |(segment_ord, segment_reader)| {
-                collect_segment(
-                    collector,
-                    weight.as_ref(),
-                    segment_ord as u32,
-                    segment_reader,
-                )
+                collector.collect_segment(weight.as_ref(), segment_ord as u32, segment_reader)
             },
             segment_readers.iter().enumerate(),
         )?;
@@ -26,12 +26,16 @@ pub enum SegmentComponent {
     STORE,
     /// Bitset describing which document of the segment is deleted.
     DELETE,
+    /// Dense vectors of the documents, and the HNSW graph
+    /// used to search for their nearest neighbours.
+    /// It only exists if the schema has vector fields.
+    VECTORS,
 }
 
 impl SegmentComponent {
     /// Iterates through the components.
     pub fn iterator() -> slice::Iter<'static, SegmentComponent> {
-        static SEGMENT_COMPONENTS: [SegmentComponent; 8] = [
+        static SEGMENT_COMPONENTS: [SegmentComponent; 9] = [
             SegmentComponent::POSTINGS,
             SegmentComponent::POSITIONS,
             SegmentComponent::POSITIONSSKIP,
@@ -40,6 +44,7 @@ impl SegmentComponent {
             SegmentComponent::TERMS,
             SegmentComponent::STORE,
             SegmentComponent::DELETE,
+            SegmentComponent::VECTORS,
         ];
         SEGMENT_COMPONENTS.iter()
     }
@@ -5,6 +5,7 @@ use core::Segment;
 use core::SegmentComponent;
 use core::SegmentId;
 use directory::ReadOnlySource;
+use error::{DataCorruption, TantivyError};
 use fastfield::DeleteBitSet;
 use fastfield::FacetReader;
 use fastfield::FastFieldReaders;
@@ -19,7 +20,9 @@ use std::sync::Arc;
 use std::sync::RwLock;
 use store::StoreReader;
 use termdict::TermDictionary;
+use vector::{has_vector_fields, VectorReader};
 use DocId;
+use Opstamp;
 use Result;
 
 /// Entry point to access all of the datastructures of the `Segment`
@@ -38,6 +41,7 @@ use Result;
 #[derive(Clone)]
 pub struct SegmentReader {
     inv_idx_reader_cache: Arc<RwLock<HashMap<Field, Arc<InvertedIndexReader>>>>,
+    vector_reader_cache: Arc<RwLock<HashMap<Field, Arc<VectorReader>>>>,
 
     segment_id: SegmentId,
     max_doc: DocId,
@@ -49,9 +53,11 @@ pub struct SegmentReader {
     positions_idx_composite: CompositeFile,
     fast_fields_readers: Arc<FastFieldReaders>,
     fieldnorms_composite: CompositeFile,
+    vectors_composite: CompositeFile,
 
     store_source: ReadOnlySource,
     delete_bitset_opt: Option<DeleteBitSet>,
+    delete_opstamp: Option<Opstamp>,
     schema: Schema,
 }
 
@@ -181,6 +187,14 @@ impl SegmentReader {
         let fieldnorms_data = segment.open_read(SegmentComponent::FIELDNORMS)?;
         let fieldnorms_composite = CompositeFile::open(&fieldnorms_data)?;
 
+        // the vectors file only exists for the segments of schemas with vector fields.
+        let vectors_composite = if has_vector_fields(&schema) {
+            let vectors_data = segment.open_read(SegmentComponent::VECTORS)?;
+            CompositeFile::open(&vectors_data)?
+        } else {
+            CompositeFile::empty()
+        };
+
         let delete_bitset_opt = if segment.meta().has_deletes() {
             let delete_data = segment.open_read(SegmentComponent::DELETE)?;
             Some(DeleteBitSet::open(delete_data))
@@ -190,15 +204,18 @@ impl SegmentReader {
 
         Ok(SegmentReader {
             inv_idx_reader_cache: Arc::new(RwLock::new(HashMap::new())),
+            vector_reader_cache: Arc::new(RwLock::new(HashMap::new())),
             max_doc: segment.meta().max_doc(),
             num_docs: segment.meta().num_docs(),
             termdict_composite,
             postings_composite,
             fast_fields_readers: fast_field_readers,
             fieldnorms_composite,
+            vectors_composite,
             segment_id: segment.id(),
             store_source,
             delete_bitset_opt,
+            delete_opstamp: segment.meta().delete_opstamp(),
             positions_composite,
             positions_idx_composite,
             schema,
@@ -276,11 +293,68 @@ impl SegmentReader {
         inv_idx_reader
     }
 
+    /// Returns the reader of the vectors of a field.
+    ///
+    /// Returns `None` if the field is not a vector field.
+    ///
+    /// The vectors and their HNSW graph are decoded in memory
+    /// the first time the reader of a field is requested.
+    ///
+    /// Returns an error if the field is not a vector field,
+    /// or if its vectors cannot be read.
+    pub fn vector_reader(&self, field: Field) -> Result<Arc<VectorReader>> {
+        let field_entry = self.schema.get_field_entry(field);
+        let dim = match *field_entry.field_type() {
+            FieldType::Vector(dim) => dim,
+            _ => {
+                return Err(TantivyError::SchemaError(format!(
+                    "Field {:?} is not a vector field",
+                    field_entry.name()
+                )));
+            }
+        };
+        if let Some(vector_reader) = self
+            .vector_reader_cache
+            .read()
+            .expect("Lock poisoned. This should never happen")
+            .get(&field)
+        {
+            return Ok(Arc::clone(vector_reader));
+        }
+        let vector_reader = Arc::new(
+            if let Some(vectors_source) = self.vectors_composite.open_read(field) {
+                VectorReader::open(&vectors_source).map_err(|err| {
+                    DataCorruption::comment_only(format!(
+                        "Failed to read the vectors of field {:?}: {:?}",
+                        field_entry.name(),
+                        err
+                    ))
+                })?
+            } else {
+                VectorReader::empty(dim)
+            },
+        );
+        self.vector_reader_cache
+            .write()
+            .expect("Vector reader cache lock poisoned. This should never happen.")
+            .insert(field, Arc::clone(&vector_reader));
+        Ok(vector_reader)
+    }
+
     /// Returns the segment id
     pub fn segment_id(&self) -> SegmentId {
         self.segment_id
     }
 
+    /// Returns the opstamp of the last delete operation
+    /// applied to the segment, if any.
+    ///
+    /// Together with the segment id, it identifies the set of
+    /// documents that are alive in the segment.
+    pub fn delete_opstamp(&self) -> Option<Opstamp> {
+        self.delete_opstamp
+    }
+
     /// Returns the bitset representing
     /// the documents that have been deleted.
     pub fn delete_bitset(&self) -> Option<&DeleteBitSet> {
@@ -310,6 +384,7 @@ impl SegmentReader {
             self.positions_idx_composite.space_usage(),
             self.fast_fields_readers.space_usage(),
             self.fieldnorms_composite.space_usage(),
+            self.vectors_composite.space_usage(),
             self.get_store_reader().space_usage(),
             self.delete_bitset_opt
                 .as_ref()
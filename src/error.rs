@@ -83,6 +83,10 @@ pub enum TantivyError {
     /// System error. (e.g.: We failed spawning a new thread)
     #[fail(display = "System error.'{}'", _0)]
     SystemError(String),
+    /// The index was written with a version of the format of the index files
+    /// that cannot be read by this version of tantivy.
+    #[fail(display = "Incompatible index format version: {}, expected {}", _0, _1)]
+    IncompatibleIndex(u32, u32),
 }
 
 impl From<DataCorruption> for TantivyError {
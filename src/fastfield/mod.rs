@@ -104,7 +104,9 @@ impl FastValue for i64 {
 
     fn fast_field_cardinality(field_type: &FieldType) -> Option<Cardinality> {
         match *field_type {
-            FieldType::I64(ref integer_options) => integer_options.get_fastfield_cardinality(),
+            FieldType::I64(ref integer_options) | FieldType::Date(ref integer_options) => {
+                integer_options.get_fastfield_cardinality()
+            }
             _ => None,
         }
     }
@@ -118,7 +120,8 @@ fn value_to_u64(value: &Value) -> u64 {
     match *value {
         Value::U64(ref val) => *val,
         Value::I64(ref val) => common::i64_to_u64(*val),
-        _ => panic!("Expected a u64/i64 field, got {:?} ", value),
+        Value::Date(ref datetime) => common::i64_to_u64(datetime.timestamp()),
+        _ => panic!("Expected a u64/i64/date field, got {:?} ", value),
     }
 }
 
@@ -59,6 +59,16 @@ impl<Item: FastValue> MultiValueIntFastFieldReader<Item> {
     pub fn total_num_vals(&self) -> u64 {
         self.idx_reader.max_value()
     }
+
+    /// Returns a lower bound of the values of all of the documents.
+    pub fn min_value(&self) -> Item {
+        self.vals_reader.min_value()
+    }
+
+    /// Returns an upper bound of the values of all of the documents.
+    pub fn max_value(&self) -> Item {
+        self.vals_reader.max_value()
+    }
 }
 
 #[cfg(test)]
@@ -30,7 +30,7 @@ fn type_and_cardinality(field_type: &FieldType) -> Option<(FastType, Cardinality
         FieldType::U64(options) => options
             .get_fastfield_cardinality()
             .map(|cardinality| (FastType::U64, cardinality)),
-        FieldType::I64(options) => options
+        FieldType::I64(options) | FieldType::Date(options) => options
             .get_fastfield_cardinality()
             .map(|cardinality| (FastType::I64, cardinality)),
         FieldType::HierarchicalFacet => Some((FastType::U64, Cardinality::MultiValues)),
@@ -148,7 +148,9 @@ impl FastFieldReaders {
 
     /// Returns the `i64` fast field reader reader associated to `field`.
     ///
-    /// If `field` is not a i64 fast field, this method returns `None`.
+    /// Date fast fields are read as their `i64` timestamp, in seconds.
+    ///
+    /// If `field` is not a i64 or date fast field, this method returns `None`.
     pub fn i64(&self, field: Field) -> Option<FastFieldReader<i64>> {
         self.fast_field_i64.get(&field).cloned()
     }
@@ -177,7 +179,7 @@ impl FastFieldReaders {
 
     /// Returns a `i64s` multi-valued fast field reader reader associated to `field`.
     ///
-    /// If `field` is not a i64 multi-valued fast field, this method returns `None`.
+    /// If `field` is not a i64 or date multi-valued fast field, this method returns `None`.
     pub fn i64s(&self, field: Field) -> Option<MultiValueIntFastFieldReader<i64>> {
         self.fast_field_i64s.get(&field).cloned()
     }
@@ -1,7 +1,9 @@
 use DocId;
 
 use super::fieldnorm_to_id;
+use super::FieldNormReader;
 use super::FieldNormsSerializer;
+use directory::ReadOnlySource;
 use schema::Field;
 use schema::Schema;
 use std::io;
@@ -74,6 +76,20 @@ impl FieldNormsWriter {
         fieldnorm_buffer[doc as usize] = fieldnorm_to_id(fieldnorm);
     }
 
+    /// Returns a `FieldNormReader` over a copy of the fieldnorm values
+    /// seen so far for the given field.
+    ///
+    /// Returns `None` if the field does not have field norms.
+    pub(crate) fn fieldnorm_reader(&self, field: Field) -> Option<FieldNormReader> {
+        if !self.fields.contains(&field) {
+            return None;
+        }
+        let fieldnorm_values = self.fieldnorms_buffer[field.0 as usize].clone();
+        Some(FieldNormReader::open(ReadOnlySource::from(
+            fieldnorm_values,
+        )))
+    }
+
     /// Serialize the seen fieldnorm values to the serializer for all fields.
     pub fn serialize(&self, fieldnorms_serializer: &mut FieldNormsSerializer) -> io::Result<()> {
         for &field in self.fields.iter() {
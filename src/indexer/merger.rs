@@ -2,6 +2,7 @@ use common::MAX_DOC_LIMIT;
 use core::Segment;
 use core::SegmentReader;
 use core::SerializableSegment;
+use directory::ReadOnlySource;
 use docset::DocSet;
 use fastfield::BytesFastFieldReader;
 use fastfield::DeleteBitSet;
@@ -23,6 +24,7 @@ use std::collections::HashMap;
 use store::StoreWriter;
 use termdict::TermMerger;
 use termdict::TermOrdinal;
+use vector::VectorsSerializer;
 use DocId;
 use Result;
 use TantivyError;
@@ -168,19 +170,40 @@ impl IndexMerger {
         })
     }
 
-    fn write_fieldnorms(&self, fieldnorms_serializer: &mut FieldNormsSerializer) -> Result<()> {
-        let fields = FieldNormsWriter::fields_with_fieldnorm(&self.schema);
+    /// Returns the fieldnorm ids of the documents of the merged segment for the given field.
+    fn merged_fieldnorms(&self, field: Field) -> Vec<u8> {
         let mut fieldnorms_data = Vec::with_capacity(self.max_doc as usize);
-        for field in fields {
-            fieldnorms_data.clear();
-            for reader in &self.readers {
-                let fieldnorms_reader = reader.get_fieldnorms_reader(field);
-                for doc_id in reader.doc_ids_alive() {
-                    let fieldnorm_id = fieldnorms_reader.fieldnorm_id(doc_id);
-                    fieldnorms_data.push(fieldnorm_id);
-                }
+        for reader in &self.readers {
+            let fieldnorms_reader = reader.get_fieldnorms_reader(field);
+            for doc_id in reader.doc_ids_alive() {
+                let fieldnorm_id = fieldnorms_reader.fieldnorm_id(doc_id);
+                fieldnorms_data.push(fieldnorm_id);
             }
-            fieldnorms_serializer.serialize_field(field, &fieldnorms_data[..])?;
+        }
+        fieldnorms_data
+    }
+
+    /// Returns the fieldnorm ids of the documents of the merged segment for all of
+    /// the fields with fieldnorms.
+    ///
+    /// They are computed once, as they are both written and used to
+    /// compute the block maxima of the postings.
+    fn merged_fieldnorms_per_field(&self) -> HashMap<Field, ReadOnlySource> {
+        FieldNormsWriter::fields_with_fieldnorm(&self.schema)
+            .into_iter()
+            .map(|field| (field, ReadOnlySource::from(self.merged_fieldnorms(field))))
+            .collect()
+    }
+
+    fn write_fieldnorms(
+        &self,
+        fieldnorms_serializer: &mut FieldNormsSerializer,
+        merged_fieldnorms: &HashMap<Field, ReadOnlySource>,
+    ) -> Result<()> {
+        let fields = FieldNormsWriter::fields_with_fieldnorm(&self.schema);
+        for field in fields {
+            let fieldnorms_data = merged_fieldnorms[&field].as_slice();
+            fieldnorms_serializer.serialize_field(field, fieldnorms_data)?;
         }
         Ok(())
     }
@@ -224,6 +247,9 @@ impl IndexMerger {
                 FieldType::Bytes => {
                     self.write_bytes_fast_field(field, fast_field_serializer)?;
                 }
+                FieldType::Vector(_) => {
+                    // Vectors are not fast fields.
+                }
             }
         }
         Ok(())
@@ -491,6 +517,7 @@ impl IndexMerger {
         &self,
         indexed_field: Field,
         field_type: &FieldType,
+        fieldnorms_data: ReadOnlySource,
         serializer: &mut InvertedIndexSerializer,
     ) -> Result<Option<TermOrdinalMapping>> {
         let mut positions_buffer: Vec<u32> = Vec::with_capacity(1_000);
@@ -550,7 +577,9 @@ impl IndexMerger {
         // - Segment 2's doc ids become  [seg0.max_doc + seg1.max_doc,
         //                                seg0.max_doc + seg1.max_doc + seg2.max_doc]
         // ...
-        let mut field_serializer = serializer.new_field(indexed_field, total_num_tokens)?;
+        let fieldnorm_reader = FieldNormReader::open(fieldnorms_data);
+        let mut field_serializer =
+            serializer.new_field(indexed_field, total_num_tokens, Some(fieldnorm_reader))?;
 
         let field_entry = self.schema.get_field_entry(indexed_field);
 
@@ -646,6 +675,7 @@ impl IndexMerger {
     fn write_postings(
         &self,
         serializer: &mut InvertedIndexSerializer,
+        merged_fieldnorms: &HashMap<Field, ReadOnlySource>,
     ) -> Result<HashMap<Field, TermOrdinalMapping>> {
         let mut term_ordinal_mappings = HashMap::new();
         for (field_ord, field_entry) in self.schema.fields().iter().enumerate() {
@@ -654,6 +684,7 @@ impl IndexMerger {
                 if let Some(term_ordinal_mapping) = self.write_postings_for_field(
                     indexed_field,
                     field_entry.field_type(),
+                    merged_fieldnorms[&indexed_field].clone(),
                     serializer,
                 )? {
                     term_ordinal_mappings.insert(indexed_field, term_ordinal_mapping);
@@ -663,6 +694,35 @@ impl IndexMerger {
         Ok(term_ordinal_mappings)
     }
 
+    /// Writes the vectors of the alive documents, in the order of the merged
+    /// segment, rebuilding their HNSW graph.
+    fn write_vectors(&self, vectors_serializer: &mut VectorsSerializer) -> Result<()> {
+        for (field_id, field_entry) in self.schema.fields().iter().enumerate() {
+            let dim = match *field_entry.field_type() {
+                FieldType::Vector(dim) => dim,
+                _ => continue,
+            };
+            let field = Field(field_id as u32);
+            let mut doc_ids = Vec::new();
+            let mut values = Vec::new();
+            let mut doc_offset: DocId = 0;
+            for reader in &self.readers {
+                let vector_reader = reader.vector_reader(field)?;
+                if vector_reader.num_vectors() > 0 {
+                    for (new_doc, doc) in (doc_offset..).zip(reader.doc_ids_alive()) {
+                        if let Some(vector) = vector_reader.vector(doc) {
+                            doc_ids.push(new_doc);
+                            values.extend_from_slice(vector);
+                        }
+                    }
+                }
+                doc_offset += reader.num_docs();
+            }
+            vectors_serializer.serialize_field(field, dim, &doc_ids, &values)?;
+        }
+        Ok(())
+    }
+
     fn write_storable_fields(&self, store_writer: &mut StoreWriter) -> Result<()> {
         for reader in &self.readers {
             let store_reader = reader.get_store_reader();
@@ -681,10 +741,15 @@ impl IndexMerger {
 
 impl SerializableSegment for IndexMerger {
     fn write(&self, mut serializer: SegmentSerializer) -> Result<u32> {
-        let term_ord_mappings = self.write_postings(serializer.get_postings_serializer())?;
-        self.write_fieldnorms(serializer.get_fieldnorms_serializer())?;
+        let merged_fieldnorms = self.merged_fieldnorms_per_field();
+        let term_ord_mappings =
+            self.write_postings(serializer.get_postings_serializer(), &merged_fieldnorms)?;
+        self.write_fieldnorms(serializer.get_fieldnorms_serializer(), &merged_fieldnorms)?;
         self.write_fast_fields(serializer.get_fast_field_serializer(), term_ord_mappings)?;
         self.write_storable_fields(serializer.get_store_writer())?;
+        if let Some(vectors_serializer) = serializer.get_vectors_serializer() {
+            self.write_vectors(vectors_serializer)?;
+        }
         serializer.close()?;
         Ok(self.max_doc)
     }
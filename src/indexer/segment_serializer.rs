@@ -6,6 +6,7 @@ use fastfield::FastFieldSerializer;
 use fieldnorm::FieldNormsSerializer;
 use postings::InvertedIndexSerializer;
 use store::StoreWriter;
+use vector::{has_vector_fields, VectorsSerializer};
 
 /// Segment serializer is in charge of laying out on disk
 /// the data accumulated and sorted by the `SegmentWriter`.
@@ -14,6 +15,7 @@ pub struct SegmentSerializer {
     fast_field_serializer: FastFieldSerializer,
     fieldnorms_serializer: FieldNormsSerializer,
     postings_serializer: InvertedIndexSerializer,
+    vectors_serializer: Option<VectorsSerializer>,
 }
 
 impl SegmentSerializer {
@@ -27,12 +29,21 @@ impl SegmentSerializer {
         let fieldnorms_write = segment.open_write(SegmentComponent::FIELDNORMS)?;
         let fieldnorms_serializer = FieldNormsSerializer::from_write(fieldnorms_write)?;
 
+        // the vectors file is only created if the schema has vector fields.
+        let vectors_serializer = if has_vector_fields(&segment.schema()) {
+            let vectors_write = segment.open_write(SegmentComponent::VECTORS)?;
+            Some(VectorsSerializer::from_write(vectors_write)?)
+        } else {
+            None
+        };
+
         let postings_serializer = InvertedIndexSerializer::open(segment)?;
         Ok(SegmentSerializer {
             store_writer: StoreWriter::new(store_write),
             fast_field_serializer,
             fieldnorms_serializer,
             postings_serializer,
+            vectors_serializer,
         })
     }
 
@@ -51,6 +62,13 @@ impl SegmentSerializer {
         &mut self.fieldnorms_serializer
     }
 
+    /// Accessor to the vectors serializer.
+    ///
+    /// Returns `None` if the schema has no vector fields.
+    pub fn get_vectors_serializer(&mut self) -> Option<&mut VectorsSerializer> {
+        self.vectors_serializer.as_mut()
+    }
+
     /// Accessor to the `StoreWriter`.
     pub fn get_store_writer(&mut self) -> &mut StoreWriter {
         &mut self.store_writer
@@ -62,6 +80,9 @@ impl SegmentSerializer {
         self.postings_serializer.close()?;
         self.store_writer.close()?;
         self.fieldnorms_serializer.close()?;
+        if let Some(vectors_serializer) = self.vectors_serializer {
+            vectors_serializer.close()?;
+        }
         Ok(())
     }
 }
@@ -38,6 +38,7 @@ use std::thread;
 use std::thread::JoinHandle;
 use Opstamp;
 use Result;
+use INDEX_FORMAT_VERSION;
 
 /// Save the index meta file.
 /// This operation is atomic :
@@ -55,6 +56,7 @@ pub fn save_new_metas(schema: Schema, directory: &mut Directory) -> Result<()> {
             schema,
             opstamp: 0u64,
             payload: None,
+            index_format_version: INDEX_FORMAT_VERSION,
         },
         directory,
     )
@@ -259,6 +261,7 @@ impl SegmentUpdater {
                 schema: index.schema(),
                 opstamp,
                 payload: commit_message,
+                index_format_version: INDEX_FORMAT_VERSION,
             };
             save_metas(&index_meta, directory.box_clone().borrow_mut())
                 .expect("Could not save metas.");
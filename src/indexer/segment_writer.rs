@@ -15,6 +15,7 @@ use std::str;
 use tokenizer::BoxedTokenizer;
 use tokenizer::FacetTokenizer;
 use tokenizer::{TokenStream, Tokenizer};
+use vector::VectorsWriter;
 use DocId;
 use Opstamp;
 use Result;
@@ -30,6 +31,7 @@ pub struct SegmentWriter {
     segment_serializer: SegmentSerializer,
     fast_field_writers: FastFieldsWriter,
     fieldnorms_writer: FieldNormsWriter,
+    vectors_writer: VectorsWriter,
     doc_opstamps: Vec<Opstamp>,
     tokenizers: Vec<Option<Box<BoxedTokenizer>>>,
 }
@@ -70,6 +72,7 @@ impl SegmentWriter {
             max_doc: 0,
             multifield_postings,
             fieldnorms_writer: FieldNormsWriter::for_schema(schema),
+            vectors_writer: VectorsWriter::for_schema(schema),
             segment_serializer,
             fast_field_writers: FastFieldsWriter::from_schema(schema),
             doc_opstamps: Vec::with_capacity(1_000),
@@ -87,13 +90,14 @@ impl SegmentWriter {
             &self.multifield_postings,
             &self.fast_field_writers,
             &self.fieldnorms_writer,
+            &self.vectors_writer,
             self.segment_serializer,
         )?;
         Ok(self.doc_opstamps)
     }
 
     pub fn mem_usage(&self) -> usize {
-        self.multifield_postings.mem_usage()
+        self.multifield_postings.mem_usage() + self.vectors_writer.mem_usage()
     }
 
     /// Indexes a new document
@@ -105,6 +109,7 @@ impl SegmentWriter {
         self.doc_opstamps.push(add_operation.opstamp);
 
         self.fast_field_writers.add_document(&doc);
+        self.vectors_writer.add_document(doc_id, &doc);
 
         for (field, field_values) in doc.get_sorted_field_values() {
             let field_options = schema.get_field_entry(field);
@@ -195,8 +200,9 @@ impl SegmentWriter {
                         }
                     }
                 }
-                FieldType::Bytes => {
-                    // Do nothing. Bytes only supports fast fields.
+                FieldType::Bytes | FieldType::Vector(_) => {
+                    // Do nothing. Bytes only supports fast fields,
+                    // and vectors are handled by the `VectorsWriter`.
                 }
             }
         }
@@ -233,11 +239,16 @@ fn write(
     multifield_postings: &MultiFieldPostingsWriter,
     fast_field_writers: &FastFieldsWriter,
     fieldnorms_writer: &FieldNormsWriter,
+    vectors_writer: &VectorsWriter,
     mut serializer: SegmentSerializer,
 ) -> Result<()> {
-    let term_ord_map = multifield_postings.serialize(serializer.get_postings_serializer())?;
+    let term_ord_map =
+        multifield_postings.serialize(serializer.get_postings_serializer(), fieldnorms_writer)?;
     fast_field_writers.serialize(serializer.get_fast_field_serializer(), &term_ord_map)?;
     fieldnorms_writer.serialize(serializer.get_fieldnorms_serializer())?;
+    if let Some(vectors_serializer) = serializer.get_vectors_serializer() {
+        vectors_writer.serialize(vectors_serializer)?;
+    }
     serializer.close()?;
     Ok(())
 }
@@ -249,6 +260,7 @@ impl SerializableSegment for SegmentWriter {
             &self.multifield_postings,
             &self.fast_field_writers,
             &self.fieldnorms_writer,
+            &self.vectors_writer,
             serializer,
         )?;
         Ok(max_doc)
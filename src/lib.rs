@@ -208,6 +208,7 @@ pub mod collector;
 pub mod directory;
 pub mod fastfield;
 pub mod fieldnorm;
+pub mod percolator;
 pub(crate) mod positions;
 pub mod postings;
 pub mod query;
@@ -215,6 +216,7 @@ pub mod schema;
 pub mod space_usage;
 pub mod store;
 pub mod termdict;
+pub mod vector;
 
 mod reader;
 
@@ -241,6 +243,12 @@ pub fn version() -> &'static str {
     env!("CARGO_PKG_VERSION")
 }
 
+/// Version of the format of the index files.
+///
+/// It is written in the `meta.json` file of the index.
+/// Indexes written with another version of the format cannot be opened.
+pub const INDEX_FORMAT_VERSION: u32 = 1;
+
 /// Defines tantivy's merging strategy
 pub mod merge_policy {
     pub use indexer::DefaultMergePolicy;
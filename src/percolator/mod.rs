@@ -0,0 +1,422 @@
+//! Matches documents against a set of registered queries.
+//!
+//! Searching runs a query against many documents. A `Percolator` does the reverse:
+//! it stores many queries, and finds which of them match an incoming document.
+//!
+//! The document is indexed into a throwaway single-document segment held in RAM,
+//! against which the candidate queries are run.
+//! Queries whose matching documents necessarily contain one of their
+//! [`query_terms`](../query/trait.Query.html#method.query_terms), like term or
+//! phrase queries, are only candidates if the document contains one of these terms.
+//! The other queries, like range queries, are run for every document.
+//!
+//! ```rust
+//! #[macro_use]
+//! extern crate tantivy;
+//! use tantivy::percolator::Percolator;
+//! use tantivy::query::QueryParser;
+//! use tantivy::schema::{Schema, TEXT};
+//! use tantivy::{Index, Result};
+//!
+//! # fn main() { example().unwrap(); }
+//! fn example() -> Result<()> {
+//!     let mut schema_builder = Schema::builder();
+//!     let body = schema_builder.add_text_field("body", TEXT);
+//!     let schema = schema_builder.build();
+//!
+//!     let index = Index::create_in_ram(schema.clone());
+//!     let query_parser = QueryParser::for_index(&index, vec![body]);
+//!     let mut percolator = Percolator::new(schema);
+//!     percolator.register(1, query_parser.parse_query("+release +tantivy")?);
+//!     percolator.register(2, query_parser.parse_query("\"code review\"")?);
+//!     percolator.register(3, query_parser.parse_query("tantivy -lucene")?);
+//!
+//!     let matching_ids = percolator.percolate(doc!(
+//!         body => "The release of tantivy is ready for code review."
+//!     ))?;
+//!     assert_eq!(matching_ids, vec![1, 2, 3]);
+//!     Ok(())
+//! }
+//! ```
+
+use core::SegmentMeta;
+use indexer::operation::AddOperation;
+use indexer::SegmentWriter;
+use query::{
+    BooleanQuery, BoostQuery, CachedQuery, ConstScoreQuery, DisjunctionMaxQuery, FilterCache,
+    FunctionScoreQuery, MultiPhraseQuery, Occur, PhrasePrefixQuery, PhraseQuery, Query,
+    SimilarityManager, TermQuery, TermSetQuery,
+};
+use schema::{Document, Field, Schema, Term};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use tokenizer::TokenizerManager;
+use Index;
+use Result;
+use Searcher;
+use SegmentReader;
+
+/// Number of bits of the initial size of the term hash table of the segment writer.
+/// The table grows if the document has many terms.
+const TABLE_BITS: usize = 10;
+
+/// Stores queries, identified by a `u64`, and finds which of them match a document.
+///
+/// The queries must be built against the schema of the percolator.
+pub struct Percolator {
+    schema: Schema,
+    tokenizers: TokenizerManager,
+    similarities: SimilarityManager,
+    queries: BTreeMap<u64, Box<Query>>,
+    // ids of the pre-filtered queries, by query term.
+    query_ids_by_term: HashMap<Term, BTreeSet<u64>>,
+    // ids of the queries run for every document.
+    unfiltered_query_ids: BTreeSet<u64>,
+}
+
+impl Percolator {
+    /// Creates a percolator without any query.
+    pub fn new(schema: Schema) -> Percolator {
+        Percolator {
+            schema,
+            tokenizers: TokenizerManager::default(),
+            similarities: SimilarityManager::default(),
+            queries: BTreeMap::new(),
+            query_ids_by_term: HashMap::new(),
+            unfiltered_query_ids: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the schema of the documents.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Accessor for the tokenizer manager used to index the documents.
+    pub fn tokenizers(&self) -> &TokenizerManager {
+        &self.tokenizers
+    }
+
+    /// Accessor for the similarity manager used to run the queries.
+    pub fn similarities(&self) -> &SimilarityManager {
+        &self.similarities
+    }
+
+    /// Registers a query under an id.
+    ///
+    /// Returns the query previously registered under this id, if any.
+    pub fn register(&mut self, id: u64, query: Box<Query>) -> Option<Box<Query>> {
+        let previous_query = self.unregister(id);
+        let mut terms = BTreeSet::new();
+        query.query_terms(&mut terms);
+        if !terms.is_empty() && requires_query_terms(query.as_ref()) {
+            for term in terms {
+                self.query_ids_by_term.entry(term).or_default().insert(id);
+            }
+        } else {
+            self.unfiltered_query_ids.insert(id);
+        }
+        self.queries.insert(id, query);
+        previous_query
+    }
+
+    /// Unregisters the query registered under an id, and returns it.
+    pub fn unregister(&mut self, id: u64) -> Option<Box<Query>> {
+        let query = self.queries.remove(&id)?;
+        if !self.unfiltered_query_ids.remove(&id) {
+            let mut terms = BTreeSet::new();
+            query.query_terms(&mut terms);
+            for term in terms {
+                let is_last_query = if let Some(query_ids) = self.query_ids_by_term.get_mut(&term) {
+                    query_ids.remove(&id);
+                    query_ids.is_empty()
+                } else {
+                    false
+                };
+                if is_last_query {
+                    self.query_ids_by_term.remove(&term);
+                }
+            }
+        }
+        Some(query)
+    }
+
+    /// Returns the query registered under an id, if any.
+    pub fn query(&self, id: u64) -> Option<&Query> {
+        self.queries.get(&id).map(|query| query.as_ref())
+    }
+
+    /// Returns the number of registered queries.
+    pub fn num_queries(&self) -> usize {
+        self.queries.len()
+    }
+
+    /// Returns the ids of the registered queries matching a document, in increasing order.
+    pub fn percolate(&self, document: Document) -> Result<Vec<u64>> {
+        let searcher = self.index_document(document)?;
+        let mut matching_ids = Vec::new();
+        for id in self.candidate_query_ids(searcher.segment_reader(0)) {
+            if self.queries[&id].count(&searcher)? > 0 {
+                matching_ids.push(id);
+            }
+        }
+        Ok(matching_ids)
+    }
+
+    /// Indexes the document into a single-document segment,
+    /// and returns a searcher over this segment.
+    fn index_document(&self, document: Document) -> Result<Searcher> {
+        let mut index = Index::create_in_ram(self.schema.clone());
+        index.set_tokenizers(self.tokenizers.clone());
+        index.set_similarities(self.similarities.clone());
+        let segment = index.new_segment();
+        let mut segment_writer =
+            SegmentWriter::for_segment(TABLE_BITS, segment.clone(), &self.schema)?;
+        segment_writer.add_document(
+            AddOperation {
+                opstamp: 0,
+                document,
+            },
+            &self.schema,
+        )?;
+        segment_writer.finalize()?;
+        let segment = index.segment(SegmentMeta::new(segment.id(), 1));
+        let segment_reader = SegmentReader::open(&segment)?;
+        Ok(Searcher::new(
+            self.schema.clone(),
+            index,
+            vec![segment_reader],
+            Arc::new(FilterCache::with_memory_budget(0)),
+        ))
+    }
+
+    /// Returns the ids of the queries that may match the document of the segment.
+    fn candidate_query_ids(&self, segment_reader: &SegmentReader) -> BTreeSet<u64> {
+        let mut candidate_ids = self.unfiltered_query_ids.clone();
+        for (field_id, field_entry) in self.schema.fields().iter().enumerate() {
+            if !field_entry.is_indexed() {
+                continue;
+            }
+            let field = Field(field_id as u32);
+            let inverted_index = segment_reader.inverted_index(field);
+            let mut term_stream = inverted_index.terms().stream();
+            while term_stream.advance() {
+                let term = Term::from_field_bytes(field, term_stream.key());
+                if let Some(query_ids) = self.query_ids_by_term.get(&term) {
+                    candidate_ids.extend(query_ids);
+                }
+            }
+        }
+        candidate_ids
+    }
+}
+
+/// Returns true iff all of the documents matching the query contain
+/// at least one of its query terms.
+///
+/// The answer is conservative: `false` means the query may match documents
+/// containing none of its query terms.
+fn requires_query_terms(query: &Query) -> bool {
+    if query.is::<TermQuery>()
+        || query.is::<TermSetQuery>()
+        || query.is::<PhraseQuery>()
+        || query.is::<MultiPhraseQuery>()
+        || query.is::<PhrasePrefixQuery>()
+    {
+        true
+    } else if let Some(boost_query) = query.downcast_ref::<BoostQuery>() {
+        requires_query_terms(boost_query.query())
+    } else if let Some(const_score_query) = query.downcast_ref::<ConstScoreQuery>() {
+        requires_query_terms(const_score_query.query())
+    } else if let Some(cached_query) = query.downcast_ref::<CachedQuery>() {
+        requires_query_terms(cached_query.query())
+    } else if let Some(function_score_query) = query.downcast_ref::<FunctionScoreQuery>() {
+        requires_query_terms(function_score_query.query())
+    } else if let Some(disjunction_max_query) = query.downcast_ref::<DisjunctionMaxQuery>() {
+        let disjuncts = disjunction_max_query.disjuncts();
+        !disjuncts.is_empty()
+            && disjuncts
+                .iter()
+                .all(|disjunct| requires_query_terms(disjunct.as_ref()))
+    } else if let Some(boolean_query) = query.downcast_ref::<BooleanQuery>() {
+        let clauses = boolean_query.clauses();
+        let is_required = |occur: Occur| occur == Occur::Must || occur == Occur::Filter;
+        if clauses.iter().any(|clause| is_required(clause.0)) {
+            // one of the required clauses is enough.
+            clauses
+                .iter()
+                .filter(|clause| is_required(clause.0))
+                .any(|clause| requires_query_terms(clause.1.as_ref()))
+        } else {
+            // the document has to match one of the `Should` clauses.
+            let mut should_clauses = clauses
+                .iter()
+                .filter(|clause| clause.0 == Occur::Should)
+                .peekable();
+            should_clauses.peek().is_some()
+                && should_clauses.all(|clause| requires_query_terms(clause.1.as_ref()))
+        }
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{requires_query_terms, Percolator};
+    use query::{
+        AllQuery, BooleanQuery, BoostQuery, Occur, PhrasePrefixQuery, Query, RangeQuery,
+        RegexQuery, TermQuery,
+    };
+    use schema::{
+        Field, IndexRecordOption, Schema, Term, TextFieldIndexing, TextOptions, INDEXED, STRING,
+        TEXT,
+    };
+    use tokenizer::{LowerCaser, SimpleTokenizer, Tokenizer};
+
+    fn term_query(field: Field, text: &str) -> Box<Query> {
+        Box::new(TermQuery::new(
+            Term::from_field_text(field, text),
+            IndexRecordOption::Basic,
+        ))
+    }
+
+    #[test]
+    fn test_requires_query_terms() {
+        let mut schema_builder = Schema::builder();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let year = schema_builder.add_u64_field("year", INDEXED);
+        schema_builder.build();
+        let range_query = || -> Box<Query> { Box::new(RangeQuery::new_u64(year, 2000..2010)) };
+        assert!(requires_query_terms(term_query(body, "a").as_ref()));
+        assert!(!requires_query_terms(range_query().as_ref()));
+        assert!(requires_query_terms(&BoostQuery::new(
+            term_query(body, "a"),
+            2f32
+        )));
+        assert!(!requires_query_terms(&RegexQuery::new(
+            "a.*".to_string(),
+            body
+        )));
+        assert!(requires_query_terms(&PhrasePrefixQuery::new(vec![
+            Term::from_field_text(body, "a"),
+            Term::from_field_text(body, "b"),
+        ])));
+        assert!(requires_query_terms(&BooleanQuery::from(vec![
+            (Occur::Must, term_query(body, "a")),
+            (Occur::Must, range_query()),
+            (Occur::Should, range_query()),
+        ])));
+        assert!(!requires_query_terms(&BooleanQuery::from(vec![
+            (Occur::Should, term_query(body, "a")),
+            (Occur::Should, range_query()),
+        ])));
+        assert!(requires_query_terms(&BooleanQuery::from(vec![
+            (Occur::Should, term_query(body, "a")),
+            (Occur::Should, term_query(body, "b")),
+            (Occur::MustNot, range_query()),
+        ])));
+        assert!(!requires_query_terms(&BooleanQuery::from(vec![
+            (Occur::Must, Box::new(AllQuery) as Box<Query>),
+            (Occur::MustNot, term_query(body, "a")),
+        ])));
+    }
+
+    #[test]
+    fn test_percolator() {
+        let mut schema_builder = Schema::builder();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let year = schema_builder.add_u64_field("year", INDEXED);
+        let mut percolator = Percolator::new(schema_builder.build());
+        percolator.register(1, term_query(body, "tantivy"));
+        percolator.register(
+            2,
+            Box::new(BooleanQuery::from(vec![
+                (Occur::Must, term_query(body, "tantivy")),
+                (Occur::MustNot, term_query(tag, "spam")),
+            ])),
+        );
+        percolator.register(
+            3,
+            Box::new(BooleanQuery::from(vec![
+                (Occur::Must, Box::new(AllQuery) as Box<Query>),
+                (Occur::MustNot, term_query(tag, "spam")),
+            ])),
+        );
+        percolator.register(
+            4,
+            Box::new(BooleanQuery::from(vec![
+                (Occur::Should, term_query(body, "lucene")),
+                (
+                    Occur::Should,
+                    Box::new(RangeQuery::new_u64(year, 2000..2010)),
+                ),
+            ])),
+        );
+        assert_eq!(percolator.num_queries(), 4);
+        assert_eq!(
+            percolator
+                .percolate(doc!(body=>"Tantivy is fast", year=>2019u64))
+                .unwrap(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            percolator
+                .percolate(doc!(body=>"Tantivy is fast", tag=>"spam", year=>2005u64))
+                .unwrap(),
+            vec![1, 4]
+        );
+        assert_eq!(
+            percolator
+                .percolate(doc!(body=>"Lucene is fast", tag=>"spam"))
+                .unwrap(),
+            vec![4]
+        );
+        assert!(percolator.percolate(doc!(tag=>"spam")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_percolator_register_and_unregister() {
+        let mut schema_builder = Schema::builder();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let mut percolator = Percolator::new(schema_builder.build());
+        assert!(percolator.register(1, term_query(body, "rust")).is_none());
+        assert!(percolator.register(2, term_query(body, "rust")).is_none());
+        assert!(percolator.register(1, term_query(body, "java")).is_some());
+        assert_eq!(
+            percolator.percolate(doc!(body=>"rust and java")).unwrap(),
+            vec![1, 2]
+        );
+        assert_eq!(percolator.percolate(doc!(body=>"rust")).unwrap(), vec![2]);
+        assert!(percolator.unregister(2).is_some());
+        assert!(percolator.unregister(2).is_none());
+        assert!(percolator.query(2).is_none());
+        assert!(!percolator
+            .query_ids_by_term
+            .contains_key(&Term::from_field_text(body, "rust")));
+        assert!(percolator.percolate(doc!(body=>"rust")).unwrap().is_empty());
+        assert_eq!(percolator.num_queries(), 1);
+    }
+
+    #[test]
+    fn test_percolator_custom_tokenizer() {
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("lowercase_only")
+                .set_index_option(IndexRecordOption::Basic),
+        );
+        let body = schema_builder.add_text_field("body", text_options);
+        let mut percolator = Percolator::new(schema_builder.build());
+        percolator
+            .tokenizers()
+            .register("lowercase_only", SimpleTokenizer.filter(LowerCaser));
+        percolator.register(1, term_query(body, "tantivy"));
+        assert_eq!(
+            percolator.percolate(doc!(body=>"TANTIVY")).unwrap(),
+            vec![1]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use super::stacker::{Addr, MemoryArena, TermHashMap};
+use fieldnorm::FieldNormsWriter;
 
 use postings::recorder::{
     BufferLender, NothingRecorder, Recorder, TFAndPositionRecorder, TermFrequencyRecorder,
@@ -37,8 +38,8 @@ fn posting_from_field_entry(field_entry: &FieldEntry) -> Box<PostingsWriter> {
         | FieldType::I64(_)
         | FieldType::Date(_)
         | FieldType::HierarchicalFacet => SpecializedPostingsWriter::<NothingRecorder>::new_boxed(),
-        FieldType::Bytes => {
-            // FieldType::Bytes cannot actually be indexed.
+        FieldType::Bytes | FieldType::Vector(_) => {
+            // FieldType::Bytes and FieldType::Vector cannot actually be indexed.
             // TODO fix during the indexer refactoring described in #276
             SpecializedPostingsWriter::<NothingRecorder>::new_boxed()
         }
@@ -118,9 +119,13 @@ impl MultiFieldPostingsWriter {
     /// Serialize the inverted index.
     /// It pushes all term, one field at a time, towards the
     /// postings serializer.
+    ///
+    /// The fieldnorms of all of the documents must have been recorded
+    /// in the `fieldnorms_writer` beforehand.
     pub fn serialize(
         &self,
         serializer: &mut InvertedIndexSerializer,
+        fieldnorms_writer: &FieldNormsWriter,
     ) -> Result<HashMap<Field, HashMap<UnorderedTermId, TermOrdinal>>> {
         let mut term_offsets: Vec<(&[u8], Addr, UnorderedTermId)> =
             self.term_index.iter().collect();
@@ -150,12 +155,15 @@ impl MultiFieldPostingsWriter {
                     unordered_term_mappings.insert(field, mapping);
                 }
                 FieldType::U64(_) | FieldType::I64(_) | FieldType::Date(_) => {}
-                FieldType::Bytes => {}
+                FieldType::Bytes | FieldType::Vector(_) => {}
             }
 
             let postings_writer = &self.per_field_postings_writers[field.0 as usize];
-            let mut field_serializer =
-                serializer.new_field(field, postings_writer.total_num_tokens())?;
+            let mut field_serializer = serializer.new_field(
+                field,
+                postings_writer.total_num_tokens(),
+                fieldnorms_writer.fieldnorm_reader(field),
+            )?;
             postings_writer.serialize(
                 &term_offsets[start..stop],
                 &mut field_serializer,
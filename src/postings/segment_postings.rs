@@ -2,6 +2,7 @@ use common::BitSet;
 use common::HasLen;
 use common::{BinarySerializable, VInt};
 use docset::{DocSet, SkipResult};
+use fieldnorm::FieldNormReader;
 use owned_read::OwnedRead;
 use positions::PositionReader;
 use postings::compression::{compressed_block_size, AlignedBuffer};
@@ -16,6 +17,7 @@ use schema::IndexRecordOption;
 use std::cmp::Ordering;
 use tantivy_fst::Streamer;
 use DocId;
+use Score;
 
 struct PositionComputer {
     // store the amount of position int
@@ -87,7 +89,7 @@ impl SegmentPostings {
     pub fn create_from_docs(docs: &[u32]) -> SegmentPostings {
         let mut buffer = Vec::new();
         {
-            let mut postings_serializer = PostingsSerializer::new(&mut buffer, false, false);
+            let mut postings_serializer = PostingsSerializer::new(&mut buffer, false, false, None);
             for &doc in docs {
                 postings_serializer.write_doc(doc, 1u32);
             }
@@ -125,6 +127,24 @@ impl SegmentPostings {
     }
 }
 
+impl SegmentPostings {
+    /// Returns, for each of the blocks of the postings that have not been
+    /// read yet, the last document of the block and an upper bound of the
+    /// score of its documents.
+    ///
+    /// See `BlockSegmentPostings::block_max_scores`.
+    pub(crate) fn block_max_scores<F>(
+        &self,
+        fieldnorm_reader: &FieldNormReader,
+        score: F,
+    ) -> Vec<(DocId, Score)>
+    where
+        F: Fn(u8, u32) -> Score,
+    {
+        self.block_cursor.block_max_scores(fieldnorm_reader, score)
+    }
+}
+
 impl DocSet for SegmentPostings {
     // goes to the next element.
     // next needs to be called a first time to point to the correct element.
@@ -576,6 +596,68 @@ impl BlockSegmentPostings {
         }
     }
 
+    /// Returns, for each of the blocks that have not been read yet,
+    /// the last document of the block and an upper bound of the score
+    /// of its documents.
+    ///
+    /// `score` computes the score of a document given its fieldnorm id and
+    /// its term frequency, and must be decreasing with the former and
+    /// increasing with the latter.
+    ///
+    /// For the bitpacked blocks, the bound is the score of the
+    /// smallest fieldnorm id and the largest term frequency of the block,
+    /// as recorded in the skip data, so the blocks are not decoded.
+    /// The last, variable int encoded block is decoded and its bound is exact.
+    ///
+    /// The position of the cursor is left unchanged.
+    pub(crate) fn block_max_scores<F>(
+        &self,
+        fieldnorm_reader: &FieldNormReader,
+        score: F,
+    ) -> Vec<(DocId, Score)>
+    where
+        F: Fn(u8, u32) -> Score,
+    {
+        let read_freqs = self.freq_reading_option == FreqReadingOption::ReadFreq;
+        let mut block_max_scores = Vec::with_capacity(self.doc_freq / COMPRESSION_BLOCK_SIZE + 1);
+        let mut skip_reader = self.skip_reader.clone();
+        let mut remaining_data = self.remaining_data.clone();
+        let mut doc_offset = self.doc_offset;
+        while skip_reader.advance() {
+            let term_freq = if read_freqs {
+                skip_reader.block_max_term_freq()
+            } else {
+                1u32
+            };
+            let block_max_score = score(skip_reader.block_min_fieldnorm_id(), term_freq);
+            block_max_scores.push((skip_reader.doc(), block_max_score));
+            remaining_data.advance(skip_reader.total_block_len());
+            doc_offset = skip_reader.doc();
+        }
+        if self.num_vint_docs > 0 {
+            let mut doc_decoder = BlockDecoder::new();
+            let mut freq_decoder = BlockDecoder::with_val(1);
+            let num_compressed_bytes = doc_decoder.uncompress_vint_sorted(
+                remaining_data.as_ref(),
+                doc_offset,
+                self.num_vint_docs,
+            );
+            remaining_data.advance(num_compressed_bytes);
+            if read_freqs {
+                freq_decoder.uncompress_vint_unsorted(remaining_data.as_ref(), self.num_vint_docs);
+            }
+            let block_max_score = (0..self.num_vint_docs)
+                .map(|idx| {
+                    let fieldnorm_id = fieldnorm_reader.fieldnorm_id(doc_decoder.output(idx));
+                    score(fieldnorm_id, freq_decoder.output(idx))
+                })
+                .fold(0f32, Score::max);
+            let last_doc = doc_decoder.output(self.num_vint_docs - 1);
+            block_max_scores.push((last_doc, block_max_score));
+        }
+        block_max_scores
+    }
+
     /// Returns an empty segment postings object
     pub fn empty() -> BlockSegmentPostings {
         BlockSegmentPostings {
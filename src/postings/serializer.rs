@@ -3,6 +3,7 @@ use common::{BinarySerializable, VInt};
 use common::{CompositeWrite, CountingWriter};
 use core::Segment;
 use directory::WritePtr;
+use fieldnorm::FieldNormReader;
 use positions::PositionSerializer;
 use postings::compression::{BlockEncoder, VIntEncoder, COMPRESSION_BLOCK_SIZE};
 use postings::skip::SkipSerializer;
@@ -87,10 +88,16 @@ impl InvertedIndexSerializer {
     /// a given field.
     ///
     /// Loads the indexing options for the given field.
+    ///
+    /// The `fieldnorm_reader` gives the fieldnorms of the documents of the
+    /// segment for this field. They are used to record the smallest fieldnorm of
+    /// each block of postings, which bounds the score of its documents.
+    /// If `None`, a fieldnorm id of `0` is recorded instead.
     pub fn new_field(
         &mut self,
         field: Field,
         total_num_tokens: u64,
+        fieldnorm_reader: Option<FieldNormReader>,
     ) -> io::Result<FieldSerializer> {
         let field_entry: &FieldEntry = self.schema.get_field_entry(field);
         let term_dictionary_write = self.terms_write.for_field(field);
@@ -105,6 +112,7 @@ impl InvertedIndexSerializer {
             postings_write,
             positions_write,
             positionsidx_write,
+            fieldnorm_reader,
         )
     }
 
@@ -136,6 +144,7 @@ impl<'a> FieldSerializer<'a> {
         postings_write: &'a mut CountingWriter<WritePtr>,
         positions_write: &'a mut CountingWriter<WritePtr>,
         positionsidx_write: &'a mut CountingWriter<WritePtr>,
+        fieldnorm_reader: Option<FieldNormReader>,
     ) -> io::Result<FieldSerializer<'a>> {
         let (term_freq_enabled, position_enabled): (bool, bool) = match field_type {
             FieldType::Str(ref text_options) => {
@@ -153,8 +162,12 @@ impl<'a> FieldSerializer<'a> {
         };
         let term_dictionary_builder =
             TermDictionaryBuilder::create(term_dictionary_write, &field_type)?;
-        let postings_serializer =
-            PostingsSerializer::new(postings_write, term_freq_enabled, position_enabled);
+        let postings_serializer = PostingsSerializer::new(
+            postings_write,
+            term_freq_enabled,
+            position_enabled,
+            fieldnorm_reader,
+        );
         let positions_serializer_opt = if position_enabled {
             Some(PositionSerializer::new(positions_write, positionsidx_write))
         } else {
@@ -312,6 +325,8 @@ pub struct PostingsSerializer<W: Write> {
 
     termfreq_enabled: bool,
     termfreq_sum_enabled: bool,
+
+    fieldnorm_reader: Option<FieldNormReader>,
 }
 
 impl<W: Write> PostingsSerializer<W> {
@@ -319,6 +334,7 @@ impl<W: Write> PostingsSerializer<W> {
         write: W,
         termfreq_enabled: bool,
         termfreq_sum_enabled: bool,
+        fieldnorm_reader: Option<FieldNormReader>,
     ) -> PostingsSerializer<W> {
         PostingsSerializer {
             output_write: CountingWriter::wrap(write),
@@ -332,6 +348,7 @@ impl<W: Write> PostingsSerializer<W> {
             last_doc_id_encoded: 0u32,
             termfreq_enabled,
             termfreq_sum_enabled,
+            fieldnorm_reader,
         }
     }
 
@@ -359,6 +376,30 @@ impl<W: Write> PostingsSerializer<W> {
                 self.skip_write.write_total_term_freq(sum_freq);
             }
         }
+        // the block maxima, used to bound the score of the documents of the block.
+        let min_fieldnorm_id = self
+            .fieldnorm_reader
+            .as_ref()
+            .and_then(|fieldnorm_reader| {
+                self.block
+                    .doc_ids()
+                    .iter()
+                    .map(|&doc| fieldnorm_reader.fieldnorm_id(doc))
+                    .min()
+            })
+            .unwrap_or(0u8);
+        self.skip_write
+            .write_block_min_fieldnorm_id(min_fieldnorm_id);
+        if self.termfreq_enabled {
+            let max_term_freq = self
+                .block
+                .term_freqs()
+                .iter()
+                .cloned()
+                .max()
+                .unwrap_or(1u32);
+            self.skip_write.write_block_max_term_freq(max_term_freq);
+        }
         self.block.clear();
     }
 
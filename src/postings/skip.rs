@@ -1,4 +1,4 @@
-use common::BinarySerializable;
+use common::{BinarySerializable, VInt};
 use owned_read::OwnedRead;
 use postings::compression::COMPRESSION_BLOCK_SIZE;
 use schema::IndexRecordOption;
@@ -39,6 +39,20 @@ impl SkipSerializer {
             .expect("Should never fail");
     }
 
+    /// Writes the smallest fieldnorm id of the documents of the block.
+    pub fn write_block_min_fieldnorm_id(&mut self, fieldnorm_id: u8) {
+        self.buffer.push(fieldnorm_id);
+    }
+
+    /// Writes the largest term frequency of the documents of the block.
+    ///
+    /// Should only be called if term frequencies are enabled.
+    pub fn write_block_max_term_freq(&mut self, term_freq: u32) {
+        VInt(u64::from(term_freq))
+            .serialize(&mut self.buffer)
+            .expect("Should never fail");
+    }
+
     pub fn data(&self) -> &[u8] {
         &self.buffer[..]
     }
@@ -49,12 +63,15 @@ impl SkipSerializer {
     }
 }
 
+#[derive(Clone)]
 pub(crate) struct SkipReader {
     doc: DocId,
     owned_read: OwnedRead,
     doc_num_bits: u8,
     tf_num_bits: u8,
     tf_sum: u32,
+    block_min_fieldnorm_id: u8,
+    block_max_term_freq: u32,
     skip_info: IndexRecordOption,
 }
 
@@ -67,6 +84,8 @@ impl SkipReader {
             doc_num_bits: 0u8,
             tf_num_bits: 0u8,
             tf_sum: 0u32,
+            block_min_fieldnorm_id: 0u8,
+            block_max_term_freq: 1u32,
         }
     }
 
@@ -76,6 +95,8 @@ impl SkipReader {
         self.doc_num_bits = 0u8;
         self.tf_num_bits = 0u8;
         self.tf_sum = 0u32;
+        self.block_min_fieldnorm_id = 0u8;
+        self.block_max_term_freq = 1u32;
     }
 
     pub fn total_block_len(&self) -> usize {
@@ -101,6 +122,21 @@ impl SkipReader {
         self.tf_sum
     }
 
+    /// Smallest fieldnorm id of the documents of the block.
+    ///
+    /// Together with `.block_max_term_freq()`, it gives an upper bound
+    /// of the score of the documents of the block.
+    pub fn block_min_fieldnorm_id(&self) -> u8 {
+        self.block_min_fieldnorm_id
+    }
+
+    /// Largest term frequency of the documents of the block.
+    ///
+    /// 1 if term frequencies are not enabled.
+    pub fn block_max_term_freq(&self) -> u32 {
+        self.block_max_term_freq
+    }
+
     pub fn advance(&mut self) -> bool {
         if self.owned_read.as_ref().is_empty() {
             false
@@ -123,6 +159,13 @@ impl SkipReader {
                         u32::deserialize(&mut self.owned_read).expect("Failed reading tf_sum");
                 }
             }
+            self.block_min_fieldnorm_id = self.owned_read.get(0);
+            self.owned_read.advance(1);
+            if self.skip_info.is_termfreq_enabled() {
+                self.block_max_term_freq = VInt::deserialize(&mut self.owned_read)
+                    .expect("Failed reading block_max_term_freq")
+                    .0 as u32;
+            }
             true
         }
     }
@@ -141,8 +184,12 @@ mod tests {
             let mut skip_serializer = SkipSerializer::new();
             skip_serializer.write_doc(1u32, 2u8);
             skip_serializer.write_term_freq(3u8);
+            skip_serializer.write_block_min_fieldnorm_id(7u8);
+            skip_serializer.write_block_max_term_freq(4u32);
             skip_serializer.write_doc(5u32, 5u8);
             skip_serializer.write_term_freq(2u8);
+            skip_serializer.write_block_min_fieldnorm_id(0u8);
+            skip_serializer.write_block_max_term_freq(300u32);
             skip_serializer.data().to_owned()
         };
         let mut skip_reader = SkipReader::new(OwnedRead::new(buf), IndexRecordOption::WithFreqs);
@@ -150,10 +197,14 @@ mod tests {
         assert_eq!(skip_reader.doc(), 1u32);
         assert_eq!(skip_reader.doc_num_bits(), 2u8);
         assert_eq!(skip_reader.tf_num_bits(), 3u8);
+        assert_eq!(skip_reader.block_min_fieldnorm_id(), 7u8);
+        assert_eq!(skip_reader.block_max_term_freq(), 4u32);
         assert!(skip_reader.advance());
         assert_eq!(skip_reader.doc(), 5u32);
         assert_eq!(skip_reader.doc_num_bits(), 5u8);
         assert_eq!(skip_reader.tf_num_bits(), 2u8);
+        assert_eq!(skip_reader.block_min_fieldnorm_id(), 0u8);
+        assert_eq!(skip_reader.block_max_term_freq(), 300u32);
         assert!(!skip_reader.advance());
     }
 
@@ -162,16 +213,21 @@ mod tests {
         let buf = {
             let mut skip_serializer = SkipSerializer::new();
             skip_serializer.write_doc(1u32, 2u8);
+            skip_serializer.write_block_min_fieldnorm_id(3u8);
             skip_serializer.write_doc(5u32, 5u8);
+            skip_serializer.write_block_min_fieldnorm_id(1u8);
             skip_serializer.data().to_owned()
         };
         let mut skip_reader = SkipReader::new(OwnedRead::new(buf), IndexRecordOption::Basic);
         assert!(skip_reader.advance());
         assert_eq!(skip_reader.doc(), 1u32);
         assert_eq!(skip_reader.doc_num_bits(), 2u8);
+        assert_eq!(skip_reader.block_min_fieldnorm_id(), 3u8);
+        assert_eq!(skip_reader.block_max_term_freq(), 1u32);
         assert!(skip_reader.advance());
         assert_eq!(skip_reader.doc(), 5u32);
         assert_eq!(skip_reader.doc_num_bits(), 5u8);
+        assert_eq!(skip_reader.block_min_fieldnorm_id(), 1u8);
         assert!(!skip_reader.advance());
     }
 }
@@ -1,7 +1,7 @@
 use core::Searcher;
 use core::SegmentReader;
 use docset::DocSet;
-use query::{Query, Scorer, Weight};
+use query::{does_not_match, Explanation, Query, Scorer, Weight};
 use DocId;
 use Result;
 use Score;
@@ -29,6 +29,13 @@ impl Weight for AllWeight {
             max_doc: reader.max_doc(),
         }))
     }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        if doc >= reader.max_doc() {
+            return Err(does_not_match(doc));
+        }
+        Ok(Explanation::new("AllQuery", 1f32))
+    }
 }
 
 enum State {
@@ -122,5 +129,4 @@ mod tests {
             assert!(!scorer.advance());
         }
     }
-
 }
@@ -1,20 +1,24 @@
 use common::BitSet;
 use core::SegmentReader;
+use docset::{DocSet, SkipResult};
 use query::BitSetDocSet;
 use query::ConstScorer;
+use query::{does_not_match, Explanation};
 use query::{Scorer, Weight};
 use schema::{Field, IndexRecordOption};
 use tantivy_fst::Automaton;
 use termdict::{TermDictionary, TermStreamer};
+use DocId;
 use Result;
 
-/// A weight struct for Fuzzy Term and Regex Queries
+/// A weight struct for Fuzzy Term, Regex, Prefix and Wildcard Queries
 pub struct AutomatonWeight<A>
 where
     A: Automaton + Send + Sync + 'static,
 {
     field: Field,
     automaton: A,
+    max_expansions: Option<usize>,
 }
 
 impl<A> AutomatonWeight<A>
@@ -23,7 +27,19 @@ where
 {
     /// Create a new AutomationWeight
     pub fn new(field: Field, automaton: A) -> AutomatonWeight<A> {
-        AutomatonWeight { field, automaton }
+        AutomatonWeight {
+            field,
+            automaton,
+            max_expansions: None,
+        }
+    }
+
+    /// Limits the number of terms the automaton may expand to in each segment.
+    ///
+    /// Only the first `max_expansions` matching terms, in the term dictionary order,
+    /// are used to match documents.
+    pub fn set_max_expansions(&mut self, max_expansions: usize) {
+        self.max_expansions = Some(max_expansions);
     }
 
     fn automaton_stream<'a>(&'a self, term_dict: &'a TermDictionary) -> TermStreamer<'a, &'a A> {
@@ -43,7 +59,12 @@ where
         let inverted_index = reader.inverted_index(self.field);
         let term_dict = inverted_index.terms();
         let mut term_stream = self.automaton_stream(term_dict);
+        let mut num_expansions = 0;
         while term_stream.advance() {
+            if self.max_expansions == Some(num_expansions) {
+                break;
+            }
+            num_expansions += 1;
             let term_info = term_stream.value();
             let mut block_segment_postings = inverted_index
                 .read_block_postings_from_terminfo(term_info, IndexRecordOption::Basic);
@@ -56,4 +77,13 @@ where
         let doc_bitset = BitSetDocSet::from(doc_bitset);
         Ok(Box::new(ConstScorer::new(doc_bitset)))
     }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut scorer = self.scorer(reader)?;
+        if scorer.skip_next(doc) == SkipResult::Reached {
+            Ok(Explanation::new("AutomatonWeight", 1f32))
+        } else {
+            Err(does_not_match(doc))
+        }
+    }
 }
@@ -1,6 +1,7 @@
 use common::{BitSet, TinySet};
 use docset::{DocSet, SkipResult};
 use std::cmp::Ordering;
+use std::sync::Arc;
 use DocId;
 
 /// A `BitSetDocSet` makes it possible to iterate through a bitset as if it was a `DocSet`.
@@ -13,7 +14,7 @@ use DocId;
 /// TODO: Consider implementing a `BitTreeSet` in order to advance faster
 /// when the bitset is sparse
 pub struct BitSetDocSet {
-    docs: BitSet,
+    docs: Arc<BitSet>,
     cursor_bucket: u32, //< index associated to the current tiny bitset
     cursor_tinybitset: TinySet,
     doc: u32,
@@ -28,6 +29,12 @@ impl BitSetDocSet {
 
 impl From<BitSet> for BitSetDocSet {
     fn from(docs: BitSet) -> BitSetDocSet {
+        BitSetDocSet::from(Arc::new(docs))
+    }
+}
+
+impl From<Arc<BitSet>> for BitSetDocSet {
+    fn from(docs: Arc<BitSet>) -> BitSetDocSet {
         let first_tiny_bitset = if docs.max_value() == 0 {
             TinySet::empty()
         } else {
@@ -0,0 +1,394 @@
+use docset::{DocSet, SkipResult};
+use query::term_query::TermScorer;
+use query::Scorer;
+use DocId;
+use Score;
+
+/// A `TermScorer` together with the upper bounds of the scores
+/// of the blocks of its postings.
+struct TermScorerWithMaxScore {
+    scorer: TermScorer,
+    // (last doc of the block, max score of the block)
+    block_max_scores: Vec<(DocId, Score)>,
+    // the block containing the last doc the scorer was shallow-seeked to.
+    block_ord: usize,
+    // max score over all of the blocks.
+    max_score: Score,
+}
+
+impl TermScorerWithMaxScore {
+    fn new(scorer: TermScorer) -> TermScorerWithMaxScore {
+        let block_max_scores = scorer.block_max_scores();
+        let max_score = block_max_scores
+            .iter()
+            .map(|&(_, block_max_score)| block_max_score)
+            .fold(0f32, Score::max);
+        TermScorerWithMaxScore {
+            scorer,
+            block_max_scores,
+            block_ord: 0,
+            max_score,
+        }
+    }
+
+    fn doc(&self) -> DocId {
+        self.scorer.doc()
+    }
+
+    /// Moves the block cursor to the block that may contain `target`,
+    /// without decoding any posting.
+    fn shallow_seek(&mut self, target: DocId) {
+        while self.block_ord < self.block_max_scores.len()
+            && self.block_max_scores[self.block_ord].0 < target
+        {
+            self.block_ord += 1;
+        }
+    }
+
+    /// Upper bound of the scores of the documents of the current block.
+    ///
+    /// `0` if the postings end before the target of the last shallow seek.
+    fn block_max_score(&self) -> Score {
+        self.block_max_scores
+            .get(self.block_ord)
+            .map(|&(_, block_max_score)| block_max_score)
+            .unwrap_or(0f32)
+    }
+
+    /// Last document of the current block.
+    fn last_doc_in_block(&self) -> Option<DocId> {
+        self.block_max_scores
+            .get(self.block_ord)
+            .map(|&(last_doc, _)| last_doc)
+    }
+}
+
+fn sort_by_doc(scorers: &mut [TermScorerWithMaxScore]) {
+    scorers.sort_by_key(TermScorerWithMaxScore::doc);
+}
+
+/// Finds the pivot, that is the first document that may have a score
+/// greater than the threshold, judging only by the max score of the terms.
+///
+/// Scorers must be sorted by doc.
+///
+/// Returns `(before_pivot_len, pivot_len, pivot_doc)`, where the scorers
+/// `[..before_pivot_len]` are positioned before or on the pivot doc, and
+/// `[before_pivot_len..pivot_len]` are positioned on the pivot doc.
+fn find_pivot_doc(
+    scorers: &[TermScorerWithMaxScore],
+    threshold: Score,
+) -> Option<(usize, usize, DocId)> {
+    let mut max_score = 0f32;
+    let mut before_pivot_len = 0;
+    while before_pivot_len < scorers.len() {
+        max_score += scorers[before_pivot_len].max_score;
+        if max_score > threshold {
+            let pivot_doc = scorers[before_pivot_len].doc();
+            let pivot_len = before_pivot_len
+                + scorers[before_pivot_len..]
+                    .iter()
+                    .take_while(|scorer| scorer.doc() == pivot_doc)
+                    .count();
+            return Some((before_pivot_len, pivot_len, pivot_doc));
+        }
+        before_pivot_len += 1;
+    }
+    None
+}
+
+/// Advances a scorer, removing it if it is exhausted.
+fn skip_scorer(scorers: &mut Vec<TermScorerWithMaxScore>, ord: usize, target: DocId) {
+    if scorers[ord].scorer.skip_next(target) == SkipResult::End {
+        scorers.swap_remove(ord);
+    }
+}
+
+/// The block max scores are too low for any of the documents until `target`
+/// to beat the threshold: skips the scorer of the pivot with the highest max score.
+fn skip_pivot_scorers(scorers: &mut Vec<TermScorerWithMaxScore>, pivot_len: usize) {
+    let mut target = scorers[..pivot_len]
+        .iter()
+        .filter_map(TermScorerWithMaxScore::last_doc_in_block)
+        .min()
+        .map(|last_doc| last_doc.saturating_add(1))
+        .unwrap_or(DocId::max_value());
+    if let Some(next_scorer) = scorers.get(pivot_len) {
+        target = target.min(next_scorer.doc());
+    }
+    let mut scorer_ord = 0;
+    for ord in 1..pivot_len {
+        if scorers[ord].max_score > scorers[scorer_ord].max_score {
+            scorer_ord = ord;
+        }
+    }
+    skip_scorer(scorers, scorer_ord, target);
+}
+
+/// Skips the scorers `[..before_pivot_len]` to the pivot doc.
+///
+/// Returns false if one of them went beyond it.
+fn align_scorers(
+    scorers: &mut Vec<TermScorerWithMaxScore>,
+    pivot_doc: DocId,
+    before_pivot_len: usize,
+) -> bool {
+    let mut aligned = true;
+    for ord in (0..before_pivot_len).rev() {
+        if scorers[ord].doc() == pivot_doc {
+            continue;
+        }
+        match scorers[ord].scorer.skip_next(pivot_doc) {
+            SkipResult::Reached => {}
+            SkipResult::OverStep => {
+                aligned = false;
+            }
+            SkipResult::End => {
+                scorers.swap_remove(ord);
+                aligned = false;
+            }
+        }
+    }
+    aligned
+}
+
+/// Advances the scorers positioned on the pivot doc, removing the exhausted ones.
+fn advance_pivot_scorers(scorers: &mut Vec<TermScorerWithMaxScore>, pivot_len: usize) {
+    for ord in (0..pivot_len).rev() {
+        if !scorers[ord].scorer.advance() {
+            scorers.swap_remove(ord);
+        }
+    }
+}
+
+/// Pushes the documents matching any of the `TermScorer`s, with
+/// the sum of their scores, to the callback, skipping the documents
+/// that cannot beat the threshold.
+///
+/// The callback returns the new threshold: only the documents with
+/// a score strictly greater than it are pushed afterwards.
+///
+/// This is the Block-Max WAND algorithm, described in
+/// [Faster Top-k Document Retrieval Using Block-Max Indexes](http://engineering.nyu.edu/~suel/papers/bmw.pdf):
+/// the upper bounds of the score of each term, and of each block of its postings,
+/// are used to skip documents, and entire blocks, without scoring them.
+pub(crate) fn block_max_wand(
+    scorers: Vec<TermScorer>,
+    threshold: Score,
+    callback: &mut FnMut(DocId, Score) -> Score,
+) {
+    let mut threshold = threshold;
+    let mut scorers: Vec<TermScorerWithMaxScore> = scorers
+        .into_iter()
+        .map(TermScorerWithMaxScore::new)
+        .filter_map(|mut scorer| {
+            if scorer.scorer.advance() {
+                Some(scorer)
+            } else {
+                None
+            }
+        })
+        .collect();
+    sort_by_doc(&mut scorers);
+    while let Some((before_pivot_len, pivot_len, pivot_doc)) =
+        find_pivot_doc(&scorers[..], threshold)
+    {
+        let block_max_score: Score = scorers[..pivot_len]
+            .iter_mut()
+            .map(|scorer| {
+                scorer.shallow_seek(pivot_doc);
+                scorer.block_max_score()
+            })
+            .sum();
+        if block_max_score <= threshold {
+            skip_pivot_scorers(&mut scorers, pivot_len);
+        } else if align_scorers(&mut scorers, pivot_doc, before_pivot_len) {
+            let score: Score = scorers[..pivot_len]
+                .iter_mut()
+                .map(|scorer| scorer.scorer.score())
+                .sum();
+            if score > threshold {
+                threshold = callback(pivot_doc, score);
+            }
+            advance_pivot_scorers(&mut scorers, pivot_len);
+        }
+        sort_by_doc(&mut scorers);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use collector::tests::TestCollector;
+    use collector::TopDocs;
+    use futures::Future;
+    use query::{BooleanQuery, Occur, Query, TermQuery};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use schema::{Field, IndexRecordOption, Schema, STRING, TEXT};
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use DocId;
+    use Index;
+    use Score;
+    use Term;
+
+    /// Returns the top `limit` documents by brute force.
+    fn exhaustive_top_docs(index: &Index, query: &Query, limit: usize) -> Vec<(Score, DocAddress)> {
+        let searcher = index.reader().unwrap().searcher();
+        let fruit = searcher.search(query, &TestCollector).unwrap();
+        let mut scored_docs: Vec<(Score, DocAddress)> = fruit
+            .scores()
+            .iter()
+            .cloned()
+            .zip(fruit.docs().iter().cloned())
+            .collect();
+        scored_docs.sort_by(|left, right| right.0.partial_cmp(&left.0).unwrap());
+        scored_docs.truncate(limit);
+        scored_docs
+    }
+
+    fn check_top_docs(index: &Index, query: &Query, limit: usize) {
+        let searcher = index.reader().unwrap().searcher();
+        let top_docs = searcher.search(query, &TopDocs::with_limit(limit)).unwrap();
+        let expected = exhaustive_top_docs(index, query, limit);
+        assert_eq!(top_docs.len(), expected.len());
+        for (&(score, _), &(expected_score, _)) in top_docs.iter().zip(expected.iter()) {
+            assert_nearly_equals(score, expected_score);
+        }
+        for &(score, doc_address) in &top_docs {
+            // the document may differ from the expected one in case of ties,
+            // but it must have been given the right score.
+            let explanation = searcher.explain(query, doc_address).unwrap();
+            assert_nearly_equals(score, explanation.value());
+        }
+    }
+
+    fn term_union(terms: &[Term], index_record_option: IndexRecordOption) -> BooleanQuery {
+        BooleanQuery::from(
+            terms
+                .iter()
+                .map(|term| {
+                    let term_query: Box<Query> =
+                        Box::new(TermQuery::new(term.clone(), index_record_option));
+                    (Occur::Should, term_query)
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Creates an index of random documents, with skewed term frequencies
+    /// and document lengths, and rare terms at the end of the alphabet.
+    fn create_index() -> (Index, Field) {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let id = schema_builder.add_text_field("id", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let words = ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"];
+        let mut rng = StdRng::from_seed([7u8; 32]);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 30_000_000).unwrap();
+            for segment in 0..3 {
+                for doc in 0..1_500 {
+                    let num_tokens = rng.gen_range(1, 30);
+                    let body: Vec<&str> = (0..num_tokens)
+                        .map(|_| {
+                            let word_ord = rng.gen_range(0, words.len());
+                            words[rng.gen_range(0, word_ord + 1)]
+                        })
+                        .collect();
+                    let id_value = format!("{}", (doc + segment) % 7);
+                    index_writer.add_document(doc!(text=>body.join(" "), id=>id_value));
+                }
+                index_writer.commit().unwrap();
+            }
+            index_writer.delete_term(Term::from_field_text(id, "3"));
+            index_writer.commit().unwrap();
+        }
+        (index, text)
+    }
+
+    #[test]
+    fn test_block_max_wand() {
+        let (index, text) = create_index();
+        let term = |word: &str| Term::from_field_text(text, word);
+        let queries = vec![
+            term_union(&[term("a"), term("j")], IndexRecordOption::WithFreqs),
+            term_union(
+                &[term("b"), term("h"), term("i")],
+                IndexRecordOption::WithFreqs,
+            ),
+            term_union(
+                &[term("a"), term("c"), term("f"), term("j"), term("missing")],
+                IndexRecordOption::WithFreqs,
+            ),
+            term_union(&[term("d"), term("j")], IndexRecordOption::Basic),
+        ];
+        for query in &queries {
+            for &limit in &[1, 10, 100] {
+                check_top_docs(&index, query, limit);
+            }
+        }
+
+        // and once the segments have been merged.
+        {
+            let segment_ids = index.searchable_segment_ids().unwrap();
+            let mut index_writer = index.writer_with_num_threads(1, 30_000_000).unwrap();
+            index_writer
+                .merge(&segment_ids)
+                .expect("Failed to initiate merge")
+                .wait()
+                .expect("Merging failed");
+            index_writer.wait_merging_threads().unwrap();
+        }
+        let reader = index.reader().unwrap();
+        reader.reload().unwrap();
+        assert_eq!(reader.searcher().segment_readers().len(), 1);
+        for query in &queries {
+            check_top_docs(&index, query, 10);
+        }
+    }
+
+    #[test]
+    fn test_block_max_wand_threshold() {
+        let (index, text) = create_index();
+        let query = term_union(
+            &[
+                Term::from_field_text(text, "b"),
+                Term::from_field_text(text, "i"),
+                Term::from_field_text(text, "j"),
+            ],
+            IndexRecordOption::WithFreqs,
+        );
+        let searcher = index.reader().unwrap().searcher();
+        let weight = query.weight(&searcher, true).unwrap();
+        for segment_reader in searcher.segment_readers() {
+            let mut scored_docs: Vec<(DocId, Score)> = Vec::new();
+            weight
+                .scorer(segment_reader)
+                .unwrap()
+                .for_each(&mut |doc, score| scored_docs.push((doc, score)));
+            let mut scores: Vec<Score> = scored_docs.iter().map(|&(_, score)| score).collect();
+            scores.sort_by(|left, right| right.partial_cmp(left).unwrap());
+            // a threshold between two distinct scores, to be robust to rounding.
+            let threshold_ord = (20..scores.len())
+                .find(|&ord| scores[ord] < scores[ord - 1] - 0.01)
+                .unwrap();
+            let threshold = (scores[threshold_ord] + scores[threshold_ord - 1]) / 2f32;
+            let expected: Vec<DocId> = scored_docs
+                .iter()
+                .filter(|&&(_, score)| score > threshold)
+                .map(|&(doc, _)| doc)
+                .collect();
+            let mut pruned_docs: Vec<DocId> = Vec::new();
+            weight
+                .for_each_pruning(threshold, segment_reader, &mut |doc, score| {
+                    assert!(score > threshold);
+                    pruned_docs.push(doc);
+                    threshold
+                })
+                .unwrap();
+            assert_eq!(pruned_docs, expected);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use super::boolean_weight::BooleanWeight;
+use query::cached_query::CachedWeight;
 use query::Occur;
 use query::Query;
 use query::TermQuery;
@@ -15,28 +16,37 @@ use Searcher;
 /// those which
 /// * match all of the sub queries associated with the
 /// `Must` occurence
+/// * match all of the sub queries associated with the `Filter` occurence, which are not scored.
 /// * match none of the sub queries associated with the
 /// `MustNot` occurence.
 /// * match at least one of the subqueries that is not
 /// a `MustNot` occurence.
+/// * match at least `minimum_should_match` of the `Should` sub queries, if set.
 #[derive(Debug)]
 pub struct BooleanQuery {
     subqueries: Vec<(Occur, Box<Query>)>,
+    minimum_should_match: usize,
 }
 
 impl Clone for BooleanQuery {
     fn clone(&self) -> Self {
-        self.subqueries
+        let mut boolean_query: BooleanQuery = self
+            .subqueries
             .iter()
             .map(|(occur, subquery)| (*occur, subquery.box_clone()))
             .collect::<Vec<_>>()
-            .into()
+            .into();
+        boolean_query.minimum_should_match = self.minimum_should_match;
+        boolean_query
     }
 }
 
 impl From<Vec<(Occur, Box<Query>)>> for BooleanQuery {
     fn from(subqueries: Vec<(Occur, Box<Query>)>) -> BooleanQuery {
-        BooleanQuery { subqueries }
+        BooleanQuery {
+            subqueries,
+            minimum_should_match: 0,
+        }
     }
 }
 
@@ -46,10 +56,23 @@ impl Query for BooleanQuery {
             .subqueries
             .iter()
             .map(|&(ref occur, ref subquery)| {
-                Ok((*occur, subquery.weight(searcher, scoring_enabled)?))
+                let is_filter = *occur == Occur::Filter || *occur == Occur::MustNot;
+                if is_filter {
+                    if let Some(cache_key) = subquery.cache_key() {
+                        let cached_weight =
+                            CachedWeight::new(subquery.as_ref(), cache_key, searcher)?;
+                        return Ok((*occur, Box::new(cached_weight) as Box<Weight>));
+                    }
+                }
+                let subquery_scoring_enabled = scoring_enabled && *occur != Occur::Filter;
+                Ok((*occur, subquery.weight(searcher, subquery_scoring_enabled)?))
             })
             .collect::<Result<_>>()?;
-        Ok(Box::new(BooleanWeight::new(sub_weights, scoring_enabled)))
+        Ok(Box::new(BooleanWeight::new(
+            sub_weights,
+            self.minimum_should_match,
+            scoring_enabled,
+        )))
     }
 
     fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
@@ -74,6 +97,24 @@ impl BooleanQuery {
         BooleanQuery::from(occur_term_queries)
     }
 
+    /// Requires the matched documents to match at least `minimum_should_match`
+    /// of the `Should` clauses, e.g. "at least 3 of these 5 optional clauses".
+    ///
+    /// The `Should` clauses then become required as a whole, even in the
+    /// presence of `Must` clauses. No document matches if `minimum_should_match`
+    /// exceeds the number of `Should` clauses.
+    ///
+    /// Defaults to `0`, i.e. the `Should` clauses are only required
+    /// if there is no `Must` clause, in which case one of them has to match.
+    pub fn set_minimum_should_match(&mut self, minimum_should_match: usize) {
+        self.minimum_should_match = minimum_should_match;
+    }
+
+    /// Returns the minimum number of `Should` clauses a document has to match.
+    pub fn minimum_should_match(&self) -> usize {
+        self.minimum_should_match
+    }
+
     /// Deconstructed view of the clauses making up this query.
     pub fn clauses(&self) -> &[(Occur, Box<Query>)] {
         &self.subqueries[..]
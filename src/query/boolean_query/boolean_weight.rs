@@ -1,16 +1,23 @@
 use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use query::block_max_wand::block_max_wand;
 use query::intersect_scorers;
 use query::score_combiner::{DoNothingCombiner, ScoreCombiner, SumWithCoordsCombiner};
 use query::term_query::TermScorer;
+use query::ConstScorer;
 use query::EmptyScorer;
 use query::Exclude;
+use query::MinShouldMatchScorer;
 use query::Occur;
 use query::RequiredOptionalScorer;
 use query::Scorer;
 use query::Union;
 use query::Weight;
+use query::{does_not_match, Explanation};
 use std::collections::HashMap;
+use DocId;
 use Result;
+use Score;
 
 fn scorer_union<TScoreCombiner>(scorers: Vec<Box<Scorer>>) -> Box<Scorer>
 where
@@ -37,19 +44,54 @@ where
     scorer
 }
 
+/// Wraps the scorer of a `Filter` clause, so that
+/// it does not contribute to the score.
+fn filter_scorer(scorer: Box<Scorer>) -> Box<Scorer> {
+    let mut const_scorer = ConstScorer::new(scorer);
+    const_scorer.set_score(0f32);
+    Box::new(const_scorer)
+}
+
 pub struct BooleanWeight {
     weights: Vec<(Occur, Box<Weight>)>,
+    minimum_should_match: usize,
     scoring_enabled: bool,
 }
 
 impl BooleanWeight {
-    pub fn new(weights: Vec<(Occur, Box<Weight>)>, scoring_enabled: bool) -> BooleanWeight {
+    pub fn new(
+        weights: Vec<(Occur, Box<Weight>)>,
+        minimum_should_match: usize,
+        scoring_enabled: bool,
+    ) -> BooleanWeight {
         BooleanWeight {
             weights,
+            minimum_should_match,
             scoring_enabled,
         }
     }
 
+    fn num_should_clauses(&self) -> usize {
+        self.weights
+            .iter()
+            .filter(|&&(occur, _)| occur == Occur::Should)
+            .count()
+    }
+
+    fn should_scorer<TScoreCombiner: ScoreCombiner>(
+        &self,
+        scorers: Vec<Box<Scorer>>,
+    ) -> Box<Scorer> {
+        if self.minimum_should_match > 1 {
+            Box::new(MinShouldMatchScorer::<_, TScoreCombiner>::new(
+                scorers,
+                self.minimum_should_match,
+            ))
+        } else {
+            scorer_union::<TScoreCombiner>(scorers)
+        }
+    }
+
     fn complex_scorer<TScoreCombiner: ScoreCombiner>(
         &self,
         reader: &SegmentReader,
@@ -65,19 +107,28 @@ impl BooleanWeight {
 
         let should_scorer_opt: Option<Box<Scorer>> = per_occur_scorers
             .remove(&Occur::Should)
-            .map(scorer_union::<TScoreCombiner>);
+            .map(|scorers| self.should_scorer::<TScoreCombiner>(scorers));
 
         let exclude_scorer_opt: Option<Box<Scorer>> = per_occur_scorers
             .remove(&Occur::MustNot)
             .map(scorer_union::<TScoreCombiner>);
 
-        let must_scorer_opt: Option<Box<Scorer>> = per_occur_scorers
-            .remove(&Occur::Must)
-            .map(intersect_scorers);
+        let mut must_scorers: Vec<Box<Scorer>> =
+            per_occur_scorers.remove(&Occur::Must).unwrap_or_default();
+        if let Some(filter_scorers) = per_occur_scorers.remove(&Occur::Filter) {
+            must_scorers.extend(filter_scorers.into_iter().map(filter_scorer));
+        }
+        let must_scorer_opt: Option<Box<Scorer>> = if must_scorers.is_empty() {
+            None
+        } else {
+            Some(intersect_scorers(must_scorers))
+        };
 
         let positive_scorer: Box<Scorer> = match (should_scorer_opt, must_scorer_opt) {
             (Some(should_scorer), Some(must_scorer)) => {
-                if self.scoring_enabled {
+                if self.minimum_should_match > 0 {
+                    intersect_scorers(vec![must_scorer, should_scorer])
+                } else if self.scoring_enabled {
                     Box::new(RequiredOptionalScorer::<_, _, TScoreCombiner>::new(
                         must_scorer,
                         should_scorer,
@@ -103,14 +154,14 @@ impl BooleanWeight {
 
 impl Weight for BooleanWeight {
     fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
-        if self.weights.is_empty() {
+        if self.weights.is_empty() || self.minimum_should_match > self.num_should_clauses() {
             Ok(Box::new(EmptyScorer))
         } else if self.weights.len() == 1 {
             let &(occur, ref weight) = &self.weights[0];
-            if occur == Occur::MustNot {
-                Ok(Box::new(EmptyScorer))
-            } else {
-                weight.scorer(reader)
+            match occur {
+                Occur::MustNot => Ok(Box::new(EmptyScorer)),
+                Occur::Filter => Ok(filter_scorer(weight.scorer(reader)?)),
+                _ => weight.scorer(reader),
             }
         } else if self.scoring_enabled {
             self.complex_scorer::<SumWithCoordsCombiner>(reader)
@@ -118,4 +169,63 @@ impl Weight for BooleanWeight {
             self.complex_scorer::<DoNothingCombiner>(reader)
         }
     }
+
+    fn for_each_pruning(
+        &self,
+        threshold: Score,
+        reader: &SegmentReader,
+        callback: &mut FnMut(DocId, Score) -> Score,
+    ) -> Result<()> {
+        let is_disjunction = self.weights.len() > 1
+            && self.minimum_should_match <= 1
+            && self
+                .weights
+                .iter()
+                .all(|&(occur, _)| occur == Occur::Should);
+        if !self.scoring_enabled || !is_disjunction {
+            let mut scorer = self.scorer(reader)?;
+            scorer.for_each_pruning(threshold, callback);
+            return Ok(());
+        }
+        let scorers = self
+            .weights
+            .iter()
+            .map(|(_, weight)| weight.scorer(reader))
+            .collect::<Result<Vec<Box<Scorer>>>>()?;
+        if scorers.iter().all(|scorer| scorer.is::<TermScorer>()) {
+            let scorers: Vec<TermScorer> = scorers
+                .into_iter()
+                .map(|scorer| *(scorer.downcast::<TermScorer>().map_err(|_| ()).unwrap()))
+                .collect();
+            block_max_wand(scorers, threshold, callback);
+        } else {
+            let mut scorer = scorer_union::<SumWithCoordsCombiner>(scorers);
+            scorer.for_each_pruning(threshold, callback);
+        }
+        Ok(())
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut scorer = self.scorer(reader)?;
+        if scorer.skip_next(doc) != SkipResult::Reached {
+            return Err(does_not_match(doc));
+        }
+        if !self.scoring_enabled {
+            return Ok(Explanation::new(
+                "BooleanQuery with scoring disabled",
+                scorer.score(),
+            ));
+        }
+        let mut explanation =
+            Explanation::new("BooleanQuery, sum of the matching clauses:", scorer.score());
+        for &(occur, ref subweight) in &self.weights {
+            if occur == Occur::MustNot || occur == Occur::Filter {
+                continue;
+            }
+            if let Ok(child_explanation) = subweight.explain(reader, doc) {
+                explanation.add_detail(child_explanation);
+            }
+        }
+        Ok(explanation)
+    }
 }
@@ -17,9 +17,14 @@ mod tests {
     use query::RequiredOptionalScorer;
     use query::Scorer;
     use query::TermQuery;
+    use query::Weight;
     use schema::*;
+    use tests::assert_nearly_equals;
+    use DocAddress;
     use DocId;
     use Index;
+    use Result;
+    use Searcher;
 
     fn aux_test_helper() -> (Index, Field) {
         let mut schema_builder = Schema::builder();
@@ -176,6 +181,157 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_boolean_query_minimum_should_match() {
+        let (index, text_field) = aux_test_helper();
+        let make_term_query = |text: &str| {
+            let term_query = TermQuery::new(
+                Term::from_field_text(text_field, text),
+                IndexRecordOption::WithFreqs,
+            );
+            let query: Box<Query> = Box::new(term_query);
+            query
+        };
+        let reader = index.reader().unwrap();
+        let search = |boolean_query: &Query| {
+            let fruit = reader
+                .searcher()
+                .search(boolean_query, &TestCollector)
+                .unwrap();
+            let docs = fruit.docs().iter().map(|doc| doc.1).collect::<Vec<DocId>>();
+            (docs, fruit.scores().to_vec())
+        };
+        let make_boolean_query = |clauses: &[(Occur, &str)], minimum_should_match: usize| {
+            let mut boolean_query = BooleanQuery::from(
+                clauses
+                    .iter()
+                    .map(|&(occur, text)| (occur, make_term_query(text)))
+                    .collect::<Vec<_>>(),
+            );
+            boolean_query.set_minimum_should_match(minimum_should_match);
+            boolean_query
+        };
+        let should_abd = [
+            (Occur::Should, "a"),
+            (Occur::Should, "b"),
+            (Occur::Should, "d"),
+        ];
+        assert_eq!(
+            search(&make_boolean_query(&should_abd, 1)).0,
+            vec![0, 1, 2, 3, 4]
+        );
+        assert_eq!(search(&make_boolean_query(&should_abd, 2)).0, vec![0, 3]);
+        assert_eq!(search(&make_boolean_query(&should_abd, 3)).0, vec![3]);
+        assert!(search(&make_boolean_query(&should_abd, 4)).0.is_empty());
+        {
+            // The score is the sum of the scores of the matching clauses.
+            let (docs, scores) = search(&make_boolean_query(&should_abd, 2));
+            let (_, union_scores) = search(&make_boolean_query(&should_abd, 0));
+            assert_eq!(docs, vec![0, 3]);
+            assert_nearly_equals(scores[0], union_scores[0]);
+            assert_nearly_equals(scores[1], union_scores[3]);
+        }
+        {
+            // With a required clause, the optional clauses become required as a whole.
+            let clauses = [(Occur::Must, "c"), (Occur::Should, "d")];
+            assert_eq!(search(&make_boolean_query(&clauses, 0)).0, vec![0, 1, 2, 3]);
+            assert_eq!(search(&make_boolean_query(&clauses, 1)).0, vec![3]);
+        }
+        {
+            let clauses = [
+                (Occur::Should, "a"),
+                (Occur::Should, "b"),
+                (Occur::MustNot, "d"),
+            ];
+            assert_eq!(search(&make_boolean_query(&clauses, 2)).0, vec![0]);
+        }
+        {
+            let query = make_boolean_query(&should_abd, 2);
+            let explanation = reader.searcher().explain(&query, DocAddress(0, 0)).unwrap();
+            assert_eq!(explanation.details().len(), 2);
+            assert!(reader.searcher().explain(&query, DocAddress(0, 1)).is_err());
+        }
+    }
+
+    /// Wrapper over a `TermQuery`, checking that it is not scored.
+    #[derive(Clone, Debug)]
+    struct NotScoredQuery(TermQuery);
+
+    impl Query for NotScoredQuery {
+        fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+            assert!(!scoring_enabled);
+            self.0.weight(searcher, scoring_enabled)
+        }
+    }
+
+    #[test]
+    pub fn test_boolean_query_filter() {
+        let (index, text_field) = aux_test_helper();
+        let make_term_query = |occur: Occur, text: &str| {
+            let term_query = TermQuery::new(
+                Term::from_field_text(text_field, text),
+                IndexRecordOption::WithFreqs,
+            );
+            let query: Box<Query> = if occur == Occur::Filter {
+                Box::new(NotScoredQuery(term_query))
+            } else {
+                Box::new(term_query)
+            };
+            (occur, query)
+        };
+        let reader = index.reader().unwrap();
+        let search = |clauses: &[(Occur, &str)]| {
+            let boolean_query = BooleanQuery::from(
+                clauses
+                    .iter()
+                    .map(|&(occur, text)| make_term_query(occur, text))
+                    .collect::<Vec<_>>(),
+            );
+            let fruit = reader
+                .searcher()
+                .search(&boolean_query, &TestCollector)
+                .unwrap();
+            let docs = fruit.docs().iter().map(|doc| doc.1).collect::<Vec<DocId>>();
+            (docs, fruit.scores().to_vec())
+        };
+        {
+            let (docs, scores) = search(&[(Occur::Filter, "c")]);
+            assert_eq!(docs, vec![0, 1, 2, 3]);
+            assert_eq!(scores, vec![0f32; 4]);
+        }
+        {
+            let (docs, scores) = search(&[(Occur::Must, "a"), (Occur::Filter, "b")]);
+            let (a_docs, a_scores) = search(&[(Occur::Must, "a")]);
+            assert_eq!(a_docs, vec![0, 1, 3]);
+            assert_eq!(docs, vec![0, 3]);
+            assert_nearly_equals(scores[0], a_scores[0]);
+            assert_nearly_equals(scores[1], a_scores[2]);
+        }
+        {
+            // With a filter, the optional clauses are not required anymore.
+            let (docs, scores) = search(&[(Occur::Should, "d"), (Occur::Filter, "c")]);
+            let (_, d_scores) = search(&[(Occur::Should, "d")]);
+            assert_eq!(docs, vec![0, 1, 2, 3]);
+            assert_eq!(&scores[..3], &[0f32; 3]);
+            assert_nearly_equals(scores[3], d_scores[0]);
+        }
+        {
+            let (docs, _) = search(&[(Occur::Filter, "a"), (Occur::MustNot, "d")]);
+            assert_eq!(docs, vec![0, 1]);
+        }
+        {
+            let boolean_query = BooleanQuery::from(vec![
+                make_term_query(Occur::Must, "a"),
+                make_term_query(Occur::Filter, "b"),
+            ]);
+            let explanation = reader
+                .searcher()
+                .explain(&boolean_query, DocAddress(0, 0))
+                .unwrap();
+            assert_eq!(explanation.details().len(), 1);
+        }
+    }
+
     #[test]
     pub fn test_intersection_score() {
         let (index, text_field) = aux_test_helper();
@@ -205,4 +361,30 @@ mod tests {
             assert_eq!(score_docs(&boolean_query), vec![0.977973, 0.84699446]);
         }
     }
+
+    #[test]
+    pub fn test_boolean_query_explain() {
+        let (index, text_field) = aux_test_helper();
+        let query_parser = QueryParser::for_index(&index, vec![text_field]);
+        let query = query_parser.parse_query("+a b -d").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let scores = searcher
+            .search(query.as_ref(), &TestCollector)
+            .unwrap()
+            .scores()
+            .to_vec();
+        let explanation = searcher.explain(query.as_ref(), DocAddress(0, 0)).unwrap();
+        assert_eq!(explanation.value(), scores[0]);
+        assert_eq!(explanation.details().len(), 2);
+        let sum: f32 = explanation
+            .details()
+            .iter()
+            .map(|detail| detail.value())
+            .sum();
+        assert_nearly_equals(sum, explanation.value());
+        let explanation = searcher.explain(query.as_ref(), DocAddress(0, 1)).unwrap();
+        assert_eq!(explanation.details().len(), 1);
+        assert!(searcher.explain(query.as_ref(), DocAddress(0, 2)).is_err());
+        assert!(searcher.explain(query.as_ref(), DocAddress(0, 3)).is_err());
+    }
 }
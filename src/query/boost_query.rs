@@ -0,0 +1,212 @@
+use common::BitSet;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use fastfield::DeleteBitSet;
+use query::{Explanation, Query, Scorer, Weight};
+use std::collections::BTreeSet;
+use std::fmt;
+use DocId;
+use Result;
+use Score;
+use Searcher;
+use Term;
+
+/// `BoostQuery` is a wrapper over a query used to boost its score.
+///
+/// The document set matched by the `BoostQuery` is strictly the same as the underlying query.
+/// The score of each document, is the score of the underlying query multiplied by the `boost`
+/// factor.
+pub struct BoostQuery {
+    query: Box<Query>,
+    boost: f32,
+}
+
+impl BoostQuery {
+    /// Builds a boost query.
+    pub fn new(query: Box<Query>, boost: f32) -> BoostQuery {
+        BoostQuery { query, boost }
+    }
+
+    /// Returns the boost factor applied to the underlying query.
+    pub fn boost(&self) -> f32 {
+        self.boost
+    }
+
+    /// Returns the underlying query.
+    pub fn query(&self) -> &Query {
+        self.query.as_ref()
+    }
+}
+
+impl Clone for BoostQuery {
+    fn clone(&self) -> Self {
+        BoostQuery {
+            query: self.query.box_clone(),
+            boost: self.boost,
+        }
+    }
+}
+
+impl fmt::Debug for BoostQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Boost(query={:?}, boost={})", self.query, self.boost)
+    }
+}
+
+impl Query for BoostQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        let weight_without_boost = self.query.weight(searcher, scoring_enabled)?;
+        let boosted_weight = if scoring_enabled {
+            Box::new(BoostWeight::new(weight_without_boost, self.boost))
+        } else {
+            weight_without_boost
+        };
+        Ok(boosted_weight)
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.query.query_terms(term_set)
+    }
+}
+
+/// Weight associated to the `BoostQuery`.
+pub struct BoostWeight {
+    weight: Box<Weight>,
+    boost: f32,
+}
+
+impl BoostWeight {
+    /// Creates a new `BoostWeight`.
+    pub fn new(weight: Box<Weight>, boost: f32) -> Self {
+        BoostWeight { weight, boost }
+    }
+}
+
+impl Weight for BoostWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        self.weight
+            .scorer(reader)
+            .map(|scorer| Box::new(BoostScorer::new(scorer, self.boost)) as Box<Scorer>)
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let underlying_explanation = self.weight.explain(reader, doc)?;
+        let mut explanation = Explanation::new(
+            format!("Boost x{}, product of:", self.boost),
+            underlying_explanation.value() * self.boost,
+        );
+        explanation.add_detail(underlying_explanation);
+        Ok(explanation)
+    }
+
+    fn count(&self, reader: &SegmentReader) -> Result<u32> {
+        self.weight.count(reader)
+    }
+}
+
+/// Scorer multiplying the score of its underlying scorer by
+/// a constant factor.
+pub struct BoostScorer<S: Scorer> {
+    underlying: S,
+    boost: f32,
+}
+
+impl<S: Scorer> BoostScorer<S> {
+    /// Wraps the given scorer.
+    pub fn new(underlying: S, boost: f32) -> BoostScorer<S> {
+        BoostScorer { underlying, boost }
+    }
+}
+
+impl<S: Scorer> DocSet for BoostScorer<S> {
+    fn advance(&mut self) -> bool {
+        self.underlying.advance()
+    }
+
+    fn skip_next(&mut self, target: DocId) -> SkipResult {
+        self.underlying.skip_next(target)
+    }
+
+    fn fill_buffer(&mut self, buffer: &mut [DocId]) -> usize {
+        self.underlying.fill_buffer(buffer)
+    }
+
+    fn doc(&self) -> DocId {
+        self.underlying.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.underlying.size_hint()
+    }
+
+    fn append_to_bitset(&mut self, bitset: &mut BitSet) {
+        self.underlying.append_to_bitset(bitset)
+    }
+
+    fn count(&mut self, delete_bitset: &DeleteBitSet) -> u32 {
+        self.underlying.count(delete_bitset)
+    }
+
+    fn count_including_deleted(&mut self) -> u32 {
+        self.underlying.count_including_deleted()
+    }
+}
+
+impl<S: Scorer> Scorer for BoostScorer<S> {
+    fn score(&mut self) -> Score {
+        self.underlying.score() * self.boost
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoostQuery;
+    use collector::tests::TestCollector;
+    use query::{AllQuery, Query, TermQuery};
+    use schema::{IndexRecordOption, Schema, TEXT};
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use Document;
+    use Index;
+    use Term;
+
+    #[test]
+    fn test_boost_query_all() {
+        let schema = Schema::builder().build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(Document::new());
+        assert!(index_writer.commit().is_ok());
+        let reader = index.reader().unwrap();
+        let searcher = reader.searcher();
+        let query = BoostQuery::new(Box::new(AllQuery), 0.2);
+        let fruit = searcher.search(&query, &TestCollector).unwrap();
+        assert_eq!(fruit.docs(), &[DocAddress(0, 0)]);
+        assert_nearly_equals(fruit.scores()[0], 0.2);
+    }
+
+    #[test]
+    fn test_boost_query_multiplies_term_score() {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(doc!(text_field=>"a b"));
+        index_writer.add_document(doc!(text_field=>"b c"));
+        assert!(index_writer.commit().is_ok());
+        let searcher = index.reader().unwrap().searcher();
+        let term_query = TermQuery::new(
+            Term::from_field_text(text_field, "a"),
+            IndexRecordOption::WithFreqs,
+        );
+        let unboosted_score = searcher
+            .search(&term_query, &TestCollector)
+            .unwrap()
+            .scores()[0];
+        let boost_query = BoostQuery::new(Box::new(term_query), 3.0);
+        let fruit = searcher.search(&boost_query, &TestCollector).unwrap();
+        assert_eq!(fruit.docs(), &[DocAddress(0, 0)]);
+        assert_nearly_equals(fruit.scores()[0], unboosted_score * 3.0);
+        assert_eq!(boost_query.count(&searcher).unwrap(), 1);
+    }
+}
@@ -0,0 +1,355 @@
+use common::BitSet;
+use core::SegmentReader;
+use docset::DocSet;
+use query::filter_cache::FilterCacheKey;
+use query::BitSetDocSet;
+use query::ConstScorer;
+use query::FilterCache;
+use query::QueryCacheKey;
+use query::{does_not_match, Explanation};
+use query::{Query, Scorer, Weight};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+use DocId;
+use Result;
+use Searcher;
+use Term;
+
+/// `CachedQuery` is a wrapper over a query, caching the documents it matches
+/// in the [`FilterCache`](./struct.FilterCache.html) of the searcher.
+///
+/// The documents matched by a `CachedQuery` are the same as the underlying query,
+/// and all get the score `1f32`: a `CachedQuery` is meant to be used as a filter,
+/// typically as a `Filter` or `MustNot` clause of a `BooleanQuery`.
+///
+/// The cached documents of a segment are identified by the segment, its deletes
+/// and the key given when creating the `CachedQuery`. Cached queries created with
+/// the same key share the same cache entries, so they must match the same documents.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::collector::Count;
+/// use tantivy::query::{CachedQuery, TermQuery};
+/// use tantivy::schema::{IndexRecordOption, Schema, TEXT};
+/// use tantivy::{Index, Result, Term};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = Schema::builder();
+///     let title = schema_builder.add_text_field("title", TEXT);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(title => "The Name of the Wind"));
+///         index_writer.add_document(doc!(title => "The Diary of Muadib"));
+///         index_writer.commit()?;
+///     }
+///     let searcher = index.reader()?.searcher();
+///
+///     let term_query = TermQuery::new(
+///         Term::from_field_text(title, "diary"),
+///         IndexRecordOption::Basic,
+///     );
+///     let query = CachedQuery::new(Box::new(term_query), "title:diary");
+///     assert_eq!(searcher.search(&query, &Count)?, 1);
+///     assert_eq!(searcher.filter_cache().len(), 1);
+///     Ok(())
+/// }
+/// ```
+pub struct CachedQuery {
+    query: Box<Query>,
+    key: String,
+}
+
+impl CachedQuery {
+    /// Builds a cached query, whose documents are cached under `key`.
+    pub fn new(query: Box<Query>, key: &str) -> CachedQuery {
+        CachedQuery {
+            query,
+            key: key.to_string(),
+        }
+    }
+
+    /// Returns the underlying query.
+    pub fn query(&self) -> &Query {
+        self.query.as_ref()
+    }
+
+    /// Returns the key the documents are cached under.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+impl Clone for CachedQuery {
+    fn clone(&self) -> Self {
+        CachedQuery {
+            query: self.query.box_clone(),
+            key: self.key.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for CachedQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cached(key={:?}, query={:?})", self.key, self.query)
+    }
+}
+
+impl Query for CachedQuery {
+    fn weight(&self, searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        let mut cache_key = QueryCacheKey::new("cached");
+        cache_key.push(self.key.as_bytes());
+        Ok(Box::new(CachedWeight::new(
+            self.query.as_ref(),
+            cache_key,
+            searcher,
+        )?))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.query.query_terms(term_set)
+    }
+}
+
+/// Weight of a non-scoring query, whose matched documents are
+/// stored in the `FilterCache` of the searcher.
+pub(crate) struct CachedWeight {
+    weight: Box<Weight>,
+    cache_key: QueryCacheKey,
+    filter_cache: Arc<FilterCache>,
+}
+
+impl CachedWeight {
+    /// Creates the non-scoring weight of `query`, whose documents are cached
+    /// under `cache_key` in the `FilterCache` of `searcher`.
+    pub(crate) fn new(
+        query: &Query,
+        cache_key: QueryCacheKey,
+        searcher: &Searcher,
+    ) -> Result<CachedWeight> {
+        Ok(CachedWeight {
+            weight: query.weight(searcher, false)?,
+            cache_key,
+            filter_cache: searcher.filter_cache().clone(),
+        })
+    }
+
+    /// Returns the alive documents of the segment matched by the query,
+    /// computing them on a cache miss.
+    fn doc_bitset(&self, reader: &SegmentReader) -> Result<Arc<BitSet>> {
+        let key = FilterCacheKey::new(reader, self.cache_key.clone());
+        if let Some(doc_bitset) = self.filter_cache.get(&key) {
+            return Ok(doc_bitset);
+        }
+        let doc_bitset = Arc::new(alive_doc_bitset(self.weight.as_ref(), reader)?);
+        self.filter_cache.insert(key, doc_bitset.clone());
+        Ok(doc_bitset)
+    }
+}
+
+/// Returns the alive documents of the segment matched by a weight.
+pub(crate) fn alive_doc_bitset(weight: &Weight, reader: &SegmentReader) -> Result<BitSet> {
+    let mut doc_bitset = BitSet::with_max_value(reader.max_doc());
+    let mut scorer = weight.scorer(reader)?;
+    if let Some(delete_bitset) = reader.delete_bitset() {
+        while scorer.advance() {
+            let doc = scorer.doc();
+            if delete_bitset.is_alive(doc) {
+                doc_bitset.insert(doc);
+            }
+        }
+    } else {
+        while scorer.advance() {
+            doc_bitset.insert(scorer.doc());
+        }
+    }
+    Ok(doc_bitset)
+}
+
+impl Weight for CachedWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let doc_bitset = self.doc_bitset(reader)?;
+        Ok(Box::new(ConstScorer::new(BitSetDocSet::from(doc_bitset))))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        if self.doc_bitset(reader)?.contains(doc) {
+            Ok(Explanation::new("CachedQuery", 1f32))
+        } else {
+            Err(does_not_match(doc))
+        }
+    }
+
+    fn count(&self, reader: &SegmentReader) -> Result<u32> {
+        Ok(self.doc_bitset(reader)?.len() as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::CachedQuery;
+    use collector::{Count, TopDocs};
+    use futures::Future;
+    use query::{BooleanQuery, ExistsQuery, Occur, Query, RangeQuery, TermQuery, TermSetQuery};
+    use schema::{IndexRecordOption, Schema, INDEXED, TEXT};
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use Index;
+    use Term;
+
+    #[test]
+    fn test_cached_query() {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(doc!(text=>"a b"));
+        index_writer.add_document(doc!(text=>"a c"));
+        index_writer.add_document(doc!(text=>"b"));
+        index_writer.commit().unwrap();
+        let reader = index.reader().unwrap();
+        let term_query = |text_str: &str| -> Box<Query> {
+            let term = Term::from_field_text(text, text_str);
+            Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs))
+        };
+        let query = CachedQuery::new(term_query("a"), "text:a");
+        {
+            let searcher = reader.searcher();
+            assert!(searcher.filter_cache().is_empty());
+            let top_docs = searcher.search(&query, &TopDocs::with_limit(3)).unwrap();
+            assert_eq!(top_docs.len(), 2);
+            for (score, _) in top_docs {
+                assert_nearly_equals(score, 1f32);
+            }
+            assert_eq!(searcher.filter_cache().len(), 1);
+            assert_eq!(searcher.search(&query, &Count).unwrap(), 2);
+            assert_eq!(searcher.filter_cache().len(), 1);
+            assert!(searcher.explain(&query, DocAddress(0, 1)).is_ok());
+            assert!(searcher.explain(&query, DocAddress(0, 2)).is_err());
+        }
+
+        // deletes are a new generation of the segment.
+        index_writer.delete_term(Term::from_field_text(text, "c"));
+        index_writer.commit().unwrap();
+        reader.reload().unwrap();
+        {
+            let searcher = reader.searcher();
+            assert_eq!(searcher.filter_cache().len(), 1);
+            assert_eq!(searcher.search(&query, &Count).unwrap(), 1);
+            assert_eq!(searcher.filter_cache().len(), 2);
+        }
+
+        // entries are dropped with their segments.
+        index_writer.add_document(doc!(text=>"a"));
+        index_writer.commit().unwrap();
+        let segment_ids = index.searchable_segment_ids().unwrap();
+        index_writer
+            .merge(&segment_ids)
+            .expect("Failed to initiate merge")
+            .wait()
+            .expect("Merging failed");
+        index_writer.wait_merging_threads().unwrap();
+        reader.reload().unwrap();
+        let searcher = reader.searcher();
+        assert_eq!(searcher.segment_readers().len(), 1);
+        assert!(searcher.filter_cache().is_empty());
+        assert_eq!(searcher.search(&query, &Count).unwrap(), 2);
+        assert_eq!(searcher.filter_cache().len(), 1);
+    }
+
+    #[test]
+    fn test_cacheable_filter_clause() {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let year = schema_builder.add_u64_field("year", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(text=>"a", year=>2017u64));
+            index_writer.add_document(doc!(text=>"a b", year=>2018u64));
+            index_writer.add_document(doc!(text=>"b", year=>2019u64));
+            index_writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let term_query = TermQuery::new(
+            Term::from_field_text(text, "a"),
+            IndexRecordOption::WithFreqs,
+        );
+        let range_query = RangeQuery::new_u64(year, 2018..2020);
+        assert!(range_query.cache_key().is_some());
+        assert!(term_query.cache_key().is_none());
+        let query = BooleanQuery::from(vec![
+            (Occur::Must, Box::new(term_query.clone()) as Box<Query>),
+            (Occur::Filter, Box::new(range_query)),
+        ]);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(3)).unwrap();
+        assert_eq!(top_docs.len(), 1);
+        assert_eq!(top_docs[0].1, DocAddress(0, 1));
+        let term_score = searcher
+            .explain(&term_query, DocAddress(0, 1))
+            .unwrap()
+            .value();
+        assert_nearly_equals(top_docs[0].0, term_score);
+        assert_eq!(searcher.filter_cache().len(), 1);
+        assert_eq!(searcher.search(&query, &Count).unwrap(), 1);
+        assert_eq!(searcher.filter_cache().len(), 1);
+    }
+
+    #[test]
+    fn test_query_cache_keys() {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let year = schema_builder.add_u64_field("year", INDEXED);
+        let month = schema_builder.add_u64_field("month", INDEXED);
+        schema_builder.build();
+        let range_key = |field, range| RangeQuery::new_u64(field, range).cache_key();
+        assert_eq!(range_key(year, 2018..2020), range_key(year, 2018..2020));
+        assert_ne!(range_key(year, 2018..2020), range_key(year, 2018..2021));
+        assert_ne!(range_key(year, 2018..2020), range_key(month, 2018..2020));
+        let term_set_key = |texts: &[&str]| {
+            let terms = texts
+                .iter()
+                .map(|text_str| Term::from_field_text(text, text_str));
+            TermSetQuery::new(terms).cache_key()
+        };
+        assert_eq!(term_set_key(&["ab", "c"]), term_set_key(&["c", "ab", "c"]));
+        assert_ne!(term_set_key(&["ab", "c"]), term_set_key(&["a", "bc"]));
+        assert_ne!(
+            ExistsQuery::new(year).cache_key(),
+            ExistsQuery::new(month).cache_key()
+        );
+    }
+
+    #[test]
+    fn test_cached_query_keys() {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(text=>"a"));
+            index_writer.add_document(doc!(text=>"b"));
+            index_writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let term_query = |text_str: &str| -> Box<Query> {
+            let term = Term::from_field_text(text, text_str);
+            Box::new(TermQuery::new(term, IndexRecordOption::Basic))
+        };
+        // the same query under two keys is cached twice.
+        let first_query = CachedQuery::new(term_query("a"), "first");
+        let second_query = CachedQuery::new(term_query("a"), "second");
+        assert_eq!(searcher.search(&first_query, &Count).unwrap(), 1);
+        assert_eq!(searcher.search(&second_query, &Count).unwrap(), 1);
+        assert_eq!(searcher.filter_cache().len(), 2);
+        // queries sharing a key share their cached documents.
+        let shared_query = CachedQuery::new(term_query("a"), "first");
+        assert_eq!(searcher.search(&shared_query, &Count).unwrap(), 1);
+        assert_eq!(searcher.filter_cache().len(), 2);
+    }
+}
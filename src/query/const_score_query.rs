@@ -0,0 +1,190 @@
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use query::ConstScorer;
+use query::{does_not_match, Explanation};
+use query::{Query, Scorer, Weight};
+use std::collections::BTreeSet;
+use std::fmt;
+use DocId;
+use Result;
+use Score;
+use Searcher;
+use Term;
+
+/// `ConstScoreQuery` is a wrapper over a query giving the same
+/// score to all of the documents it matches.
+///
+/// The document set matched by the `ConstScoreQuery` is strictly the same
+/// as the underlying query, but the underlying query is not scored,
+/// e.g. a `TermQuery` does not compute BM25 scores. This is useful
+/// to search for filters, like a date range or a term, that should
+/// not affect the ranking of the documents.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::collector::TopDocs;
+/// use tantivy::query::{ConstScoreQuery, TermQuery};
+/// use tantivy::schema::{IndexRecordOption, Schema, TEXT};
+/// use tantivy::{Index, Result, Term};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = Schema::builder();
+///     let title = schema_builder.add_text_field("title", TEXT);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(title => "The Name of the Wind"));
+///         index_writer.add_document(doc!(title => "The Diary of Muadib"));
+///         index_writer.commit()?;
+///     }
+///     let searcher = index.reader()?.searcher();
+///
+///     let term_query = TermQuery::new(
+///         Term::from_field_text(title, "diary"),
+///         IndexRecordOption::Basic,
+///     );
+///     let query = ConstScoreQuery::new(Box::new(term_query), 2f32);
+///     let top_docs = searcher.search(&query, &TopDocs::with_limit(2))?;
+///     assert_eq!(top_docs.len(), 1);
+///     assert_eq!(top_docs[0].0, 2f32);
+///     Ok(())
+/// }
+/// ```
+pub struct ConstScoreQuery {
+    query: Box<Query>,
+    score: Score,
+}
+
+impl ConstScoreQuery {
+    /// Builds a constant score query.
+    pub fn new(query: Box<Query>, score: Score) -> ConstScoreQuery {
+        ConstScoreQuery { query, score }
+    }
+
+    /// Returns the score given to the matched documents.
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    /// Returns the underlying query.
+    pub fn query(&self) -> &Query {
+        self.query.as_ref()
+    }
+}
+
+impl Clone for ConstScoreQuery {
+    fn clone(&self) -> Self {
+        ConstScoreQuery {
+            query: self.query.box_clone(),
+            score: self.score,
+        }
+    }
+}
+
+impl fmt::Debug for ConstScoreQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ConstScore(query={:?}, score={})",
+            self.query, self.score
+        )
+    }
+}
+
+impl Query for ConstScoreQuery {
+    fn weight(&self, searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        let weight = self.query.weight(searcher, false)?;
+        Ok(Box::new(ConstScoreWeight {
+            weight,
+            score: self.score,
+        }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.query.query_terms(term_set)
+    }
+}
+
+struct ConstScoreWeight {
+    weight: Box<Weight>,
+    score: Score,
+}
+
+impl Weight for ConstScoreWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let mut const_scorer = ConstScorer::new(self.weight.scorer(reader)?);
+        const_scorer.set_score(self.score);
+        Ok(Box::new(const_scorer))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut scorer = self.weight.scorer(reader)?;
+        if scorer.skip_next(doc) == SkipResult::Reached {
+            Ok(Explanation::new("ConstScoreQuery", self.score))
+        } else {
+            Err(does_not_match(doc))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::ConstScoreQuery;
+    use collector::{Count, TopDocs};
+    use query::{AllQuery, BooleanQuery, Occur, Query, TermQuery};
+    use schema::{IndexRecordOption, Schema, TEXT};
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use Index;
+    use Term;
+
+    #[test]
+    fn test_const_score_query() {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(text=>"a b"));
+            index_writer.add_document(doc!(text=>"a a a c"));
+            index_writer.add_document(doc!(text=>"b"));
+            index_writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let term_query = |text_str: &str| -> Box<Query> {
+            let term = Term::from_field_text(text, text_str);
+            Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs))
+        };
+        let query = ConstScoreQuery::new(term_query("a"), 0.5);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(3)).unwrap();
+        assert_eq!(top_docs.len(), 2);
+        for (score, _) in top_docs {
+            assert_nearly_equals(score, 0.5);
+        }
+        let explanation = searcher.explain(&query, DocAddress(0, 1)).unwrap();
+        assert_nearly_equals(explanation.value(), 0.5);
+        assert!(searcher.explain(&query, DocAddress(0, 2)).is_err());
+
+        let boolean_query = BooleanQuery::from(vec![
+            (Occur::Should, term_query("b")),
+            (
+                Occur::Should,
+                Box::new(ConstScoreQuery::new(Box::new(AllQuery), 0f32)) as Box<Query>,
+            ),
+        ]);
+        assert_eq!(searcher.search(&boolean_query, &Count).unwrap(), 3);
+        let b_score = searcher
+            .explain(term_query("b").as_ref(), DocAddress(0, 0))
+            .unwrap()
+            .value();
+        let boolean_score = searcher
+            .explain(&boolean_query, DocAddress(0, 0))
+            .unwrap()
+            .value();
+        assert_nearly_equals(boolean_score, b_score);
+    }
+}
@@ -0,0 +1,266 @@
+use core::SegmentReader;
+use query::score_combiner::{DisjunctionMaxCombiner, DoNothingCombiner};
+use query::EmptyScorer;
+use query::Union;
+use query::{does_not_match, Explanation};
+use query::{Query, Scorer, Weight};
+use std::collections::BTreeSet;
+use DocId;
+use Result;
+use Score;
+use Searcher;
+use Term;
+
+/// The `DisjunctionMaxQuery` matches the documents matching any of its subqueries,
+/// called disjuncts.
+///
+/// Unlike a `BooleanQuery` of `Should` clauses, which sums the scores of the
+/// matching subqueries, the score of a document is the maximum of the scores of
+/// the matching disjuncts, plus the scores of the other matching disjuncts
+/// multiplied by a `tie_breaker`.
+///
+/// This is useful when searching a term over several fields, like `title` and `body`:
+/// a document containing the term in both fields is not ranked far above a document
+/// containing it in its title only. A small tie breaker, e.g. `0.1`, still ranks
+/// the former slightly higher.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::collector::TopDocs;
+/// use tantivy::query::{DisjunctionMaxQuery, QueryParser};
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{DocAddress, Index, Result};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = Schema::builder();
+///     let title = schema_builder.add_text_field("title", TEXT);
+///     let body = schema_builder.add_text_field("body", TEXT);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(title => "rust", body => "rust rust rust"));
+///         index_writer.add_document(doc!(title => "rust", body => "python"));
+///         index_writer.commit()?;
+///     }
+///     let searcher = index.reader()?.searcher();
+///
+///     let title_query = QueryParser::for_index(&index, vec![title]).parse_query("rust")?;
+///     let body_query = QueryParser::for_index(&index, vec![body]).parse_query("rust")?;
+///     let query = DisjunctionMaxQuery::with_tie_breaker(vec![title_query, body_query], 0.1);
+///     let top_docs = searcher.search(&query, &TopDocs::with_limit(2))?;
+///     assert_eq!(top_docs.len(), 2);
+///     assert_eq!(top_docs[0].1, DocAddress(0, 0));
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DisjunctionMaxQuery {
+    disjuncts: Vec<Box<Query>>,
+    tie_breaker: Score,
+}
+
+impl Clone for DisjunctionMaxQuery {
+    fn clone(&self) -> Self {
+        DisjunctionMaxQuery {
+            disjuncts: self
+                .disjuncts
+                .iter()
+                .map(|disjunct| disjunct.box_clone())
+                .collect(),
+            tie_breaker: self.tie_breaker,
+        }
+    }
+}
+
+impl DisjunctionMaxQuery {
+    /// Creates a `DisjunctionMaxQuery`, scoring the documents with
+    /// the maximum of the scores of the matching disjuncts.
+    pub fn new(disjuncts: Vec<Box<Query>>) -> DisjunctionMaxQuery {
+        DisjunctionMaxQuery::with_tie_breaker(disjuncts, 0f32)
+    }
+
+    /// Creates a `DisjunctionMaxQuery`, scoring the documents with
+    /// the maximum of the scores of the matching disjuncts, plus `tie_breaker`
+    /// times the scores of the other matching disjuncts.
+    ///
+    /// The tie breaker is typically a value between `0` and `1`.
+    pub fn with_tie_breaker(disjuncts: Vec<Box<Query>>, tie_breaker: Score) -> DisjunctionMaxQuery {
+        DisjunctionMaxQuery {
+            disjuncts,
+            tie_breaker,
+        }
+    }
+
+    /// Returns the disjuncts of the query.
+    pub fn disjuncts(&self) -> &[Box<Query>] {
+        &self.disjuncts[..]
+    }
+
+    /// Returns the factor applied to the scores of the matching
+    /// disjuncts that do not have the maximum score.
+    pub fn tie_breaker(&self) -> Score {
+        self.tie_breaker
+    }
+}
+
+impl Query for DisjunctionMaxQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        let weights = self
+            .disjuncts
+            .iter()
+            .map(|disjunct| disjunct.weight(searcher, scoring_enabled))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Box::new(DisjunctionMaxWeight {
+            weights,
+            tie_breaker: self.tie_breaker,
+            scoring_enabled,
+        }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for disjunct in &self.disjuncts {
+            disjunct.query_terms(term_set);
+        }
+    }
+}
+
+struct DisjunctionMaxWeight {
+    weights: Vec<Box<Weight>>,
+    tie_breaker: Score,
+    scoring_enabled: bool,
+}
+
+impl Weight for DisjunctionMaxWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let mut scorers = self
+            .weights
+            .iter()
+            .map(|weight| weight.scorer(reader))
+            .collect::<Result<Vec<_>>>()?;
+        if scorers.len() <= 1 {
+            return Ok(scorers.pop().unwrap_or_else(|| Box::new(EmptyScorer)));
+        }
+        if self.scoring_enabled {
+            let score_combiner = DisjunctionMaxCombiner::with_tie_breaker(self.tie_breaker);
+            Ok(Box::new(Union::with_score_combiner(
+                scorers,
+                score_combiner,
+            )))
+        } else {
+            Ok(Box::new(Union::<_, DoNothingCombiner>::from(scorers)))
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let sub_explanations = self
+            .weights
+            .iter()
+            .filter_map(|weight| weight.explain(reader, doc).ok())
+            .collect::<Vec<_>>();
+        if sub_explanations.is_empty() {
+            return Err(does_not_match(doc));
+        }
+        let max = sub_explanations
+            .iter()
+            .map(Explanation::value)
+            .fold(0f32, Score::max);
+        let sum: Score = sub_explanations.iter().map(Explanation::value).sum();
+        let mut explanation = Explanation::new(
+            format!(
+                "DisjunctionMaxQuery, max of the matching disjuncts plus {} times the others:",
+                self.tie_breaker
+            ),
+            max + (sum - max) * self.tie_breaker,
+        );
+        for sub_explanation in sub_explanations {
+            explanation.add_detail(sub_explanation);
+        }
+        Ok(explanation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::DisjunctionMaxQuery;
+    use collector::{Count, TopDocs};
+    use query::{BooleanQuery, Occur, Query, TermQuery};
+    use schema::{Field, IndexRecordOption, Schema, TEXT};
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use Index;
+    use Term;
+
+    fn term_query(field: Field, text: &str) -> Box<Query> {
+        let term = Term::from_field_text(field, text);
+        Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs))
+    }
+
+    #[test]
+    fn test_disjunction_max_query() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(title=>"rust", body=>"rust"));
+            index_writer.add_document(doc!(title=>"rust"));
+            index_writer.add_document(doc!(title=>"python", body=>"rust"));
+            index_writer.add_document(doc!(title=>"python", body=>"python"));
+            index_writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let score =
+            |query: &Query, doc: u32| searcher.explain(query, DocAddress(0, doc)).unwrap().value();
+        let title_query = term_query(title, "rust");
+        let body_query = term_query(body, "rust");
+        let title_score = score(title_query.as_ref(), 0);
+        let body_score = score(body_query.as_ref(), 0);
+
+        let disjuncts = || vec![title_query.box_clone(), body_query.box_clone()];
+        let boolean_query = BooleanQuery::from(vec![
+            (Occur::Should, title_query.box_clone()),
+            (Occur::Should, body_query.box_clone()),
+        ]);
+        let max_query = DisjunctionMaxQuery::new(disjuncts());
+        let tie_breaker_query = DisjunctionMaxQuery::with_tie_breaker(disjuncts(), 0.5);
+        let sum_query = DisjunctionMaxQuery::with_tie_breaker(disjuncts(), 1f32);
+        assert_eq!(searcher.search(&max_query, &Count).unwrap(), 3);
+        assert_eq!(
+            searcher.search(&max_query, &Count).unwrap(),
+            searcher.search(&boolean_query, &Count).unwrap()
+        );
+        assert_nearly_equals(score(&max_query, 0), title_score.max(body_score));
+        assert_nearly_equals(
+            score(&tie_breaker_query, 0),
+            title_score.max(body_score) + title_score.min(body_score) * 0.5,
+        );
+        assert_nearly_equals(score(&sum_query, 0), score(&boolean_query, 0));
+        for doc in 0..3 {
+            let top_docs = searcher
+                .search(&tie_breaker_query, &TopDocs::with_limit(3))
+                .unwrap();
+            let (top_score, _) = top_docs
+                .into_iter()
+                .find(|&(_, doc_address)| doc_address == DocAddress(0, doc))
+                .unwrap();
+            assert_nearly_equals(top_score, score(&tie_breaker_query, doc));
+        }
+        assert!(searcher
+            .explain(&tie_breaker_query, DocAddress(0, 3))
+            .is_err());
+
+        assert_eq!(
+            searcher
+                .search(&DisjunctionMaxQuery::new(vec![]), &Count)
+                .unwrap(),
+            0
+        );
+        let single_query = DisjunctionMaxQuery::new(vec![term_query(body, "rust")]);
+        assert_eq!(searcher.search(&single_query, &Count).unwrap(), 2);
+    }
+}
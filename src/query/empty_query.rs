@@ -1,6 +1,7 @@
 use super::Scorer;
 use query::Query;
 use query::Weight;
+use query::{does_not_match, Explanation};
 use DocId;
 use DocSet;
 use Result;
@@ -32,6 +33,10 @@ impl Weight for EmptyWeight {
     fn scorer(&self, _reader: &SegmentReader) -> Result<Box<Scorer>> {
         Ok(Box::new(EmptyScorer))
     }
+
+    fn explain(&self, _reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        Err(does_not_match(doc))
+    }
 }
 
 /// `EmptyScorer` is a dummy `Scorer` in which no document matches.
@@ -0,0 +1,272 @@
+use common::BitSet;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use error::TantivyError;
+use query::BitSetDocSet;
+use query::ConstScorer;
+use query::{does_not_match, Explanation};
+use query::{AllWeight, Query, QueryCacheKey, Scorer, Weight};
+use schema::{Cardinality, Field, FieldType, IndexRecordOption};
+use DocId;
+use Result;
+use Searcher;
+
+/// An Exists Query matches all of the documents
+/// having at least one value for a given field.
+///
+/// For indexed fields, including text and facet fields, this is the union of
+/// the postings of all of the terms of the field. For fast fields that are not
+/// indexed, this is the documents having at least one value in a multivalued
+/// or bytes fast field. As all of the documents have a value in a single-valued
+/// fast field, defaulting to `0`, they are all matched. For vector fields, this is
+/// the documents having a vector.
+///
+/// All of the documents matched get the score `1f32`.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::collector::Count;
+/// use tantivy::query::ExistsQuery;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{Index, Result};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = Schema::builder();
+///     let title = schema_builder.add_text_field("title", TEXT);
+///     let body = schema_builder.add_text_field("body", TEXT);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(title => "The Name of the Wind", body => "Fantasy"));
+///         index_writer.add_document(doc!(title => "The Diary of Muadib"));
+///         index_writer.commit()?;
+///     }
+///     let searcher = index.reader()?.searcher();
+///
+///     assert_eq!(searcher.search(&ExistsQuery::new(title), &Count)?, 2);
+///     assert_eq!(searcher.search(&ExistsQuery::new(body), &Count)?, 1);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ExistsQuery {
+    field: Field,
+}
+
+impl ExistsQuery {
+    /// Creates a new `ExistsQuery` matching the documents having a value for `field`.
+    pub fn new(field: Field) -> ExistsQuery {
+        ExistsQuery { field }
+    }
+
+    /// The `Field` this `ExistsQuery` is targeting.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+}
+
+impl Query for ExistsQuery {
+    fn weight(&self, searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        let field_entry = searcher.schema().get_field_entry(self.field);
+        let source = if field_entry.is_indexed() {
+            ValueSource::Terms
+        } else {
+            match *field_entry.field_type() {
+                FieldType::U64(ref options)
+                | FieldType::I64(ref options)
+                | FieldType::Date(ref options) => match options.get_fastfield_cardinality() {
+                    Some(Cardinality::SingleValue) => ValueSource::SingleValueFastField,
+                    Some(Cardinality::MultiValues) => ValueSource::MultiValueFastField,
+                    None => ValueSource::None,
+                },
+                FieldType::Bytes => ValueSource::BytesFastField,
+                FieldType::Vector(_) => ValueSource::Vectors,
+                FieldType::Str(_) | FieldType::HierarchicalFacet => ValueSource::None,
+            }
+        };
+        if let ValueSource::None = source {
+            return Err(TantivyError::SchemaError(format!(
+                "Field {:?} is neither indexed nor a fast field",
+                field_entry.name()
+            )));
+        }
+        Ok(Box::new(ExistsWeight {
+            field: self.field,
+            source,
+        }))
+    }
+
+    fn cache_key(&self) -> Option<QueryCacheKey> {
+        let mut cache_key = QueryCacheKey::new("exists");
+        cache_key.push_u64(u64::from(self.field.0));
+        Some(cache_key)
+    }
+}
+
+/// Where the values of a field are read from.
+enum ValueSource {
+    Terms,
+    SingleValueFastField,
+    MultiValueFastField,
+    BytesFastField,
+    Vectors,
+    None,
+}
+
+struct ExistsWeight {
+    field: Field,
+    source: ValueSource,
+}
+
+impl ExistsWeight {
+    fn doc_bitset(&self, reader: &SegmentReader) -> Result<BitSet> {
+        let max_doc = reader.max_doc();
+        let mut doc_bitset = BitSet::with_max_value(max_doc);
+        match self.source {
+            ValueSource::Terms => {
+                let inverted_index = reader.inverted_index(self.field);
+                let mut term_stream = inverted_index.terms().range().into_stream();
+                while term_stream.advance() {
+                    let mut block_segment_postings = inverted_index
+                        .read_block_postings_from_terminfo(
+                            term_stream.value(),
+                            IndexRecordOption::Basic,
+                        );
+                    while block_segment_postings.advance() {
+                        for &doc in block_segment_postings.docs() {
+                            doc_bitset.insert(doc);
+                        }
+                    }
+                }
+            }
+            ValueSource::MultiValueFastField => {
+                if let Some(multi_value_reader) = reader.fast_fields().u64s_lenient(self.field) {
+                    for doc in 0..max_doc {
+                        if multi_value_reader.num_vals(doc) > 0 {
+                            doc_bitset.insert(doc);
+                        }
+                    }
+                }
+            }
+            ValueSource::BytesFastField => {
+                if let Some(bytes_reader) = reader.fast_fields().bytes(self.field) {
+                    for doc in 0..max_doc {
+                        if !bytes_reader.get_bytes(doc).is_empty() {
+                            doc_bitset.insert(doc);
+                        }
+                    }
+                }
+            }
+            ValueSource::Vectors => {
+                for &doc in reader.vector_reader(self.field)?.doc_ids() {
+                    doc_bitset.insert(doc);
+                }
+            }
+            ValueSource::SingleValueFastField | ValueSource::None => {}
+        }
+        Ok(doc_bitset)
+    }
+}
+
+impl Weight for ExistsWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        if let ValueSource::SingleValueFastField = self.source {
+            return AllWeight.scorer(reader);
+        }
+        let doc_bitset = BitSetDocSet::from(self.doc_bitset(reader)?);
+        Ok(Box::new(ConstScorer::new(doc_bitset)))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut scorer = self.scorer(reader)?;
+        if scorer.skip_next(doc) == SkipResult::Reached {
+            Ok(Explanation::new("ExistsQuery", 1f32))
+        } else {
+            Err(does_not_match(doc))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::ExistsQuery;
+    use collector::{Count, TopDocs};
+    use error::TantivyError;
+    use query::{BooleanQuery, Occur, Query, TermQuery};
+    use schema::{Cardinality, Facet, IndexRecordOption, IntOptions, Schema, STORED, TEXT};
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use Index;
+    use Term;
+
+    #[test]
+    fn test_exists_query() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let stored = schema_builder.add_text_field("stored", STORED);
+        let year = schema_builder.add_u64_field("year", IntOptions::default().set_indexed());
+        let rating = schema_builder.add_i64_field(
+            "rating",
+            IntOptions::default().set_fast(Cardinality::SingleValue),
+        );
+        let tags = schema_builder.add_u64_field(
+            "tags",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        let category = schema_builder.add_facet_field("category");
+        let thumbnail = schema_builder.add_bytes_field("thumbnail");
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(
+                title=>"a",
+                body=>"b c",
+                year=>2019u64,
+                tags=>1u64,
+                tags=>2u64,
+                category=>Facet::from("/rust"),
+                thumbnail=>vec![1u8, 2u8]
+            ));
+            index_writer.add_document(doc!(title=>"d", rating=>3i64, tags=>3u64));
+            index_writer.add_document(doc!(body=>"e", stored=>"f"));
+            index_writer.add_document(doc!());
+            index_writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let count = |field| searcher.search(&ExistsQuery::new(field), &Count).unwrap();
+        assert_eq!(count(title), 2);
+        assert_eq!(count(body), 2);
+        assert_eq!(count(year), 1);
+        assert_eq!(count(rating), 4);
+        assert_eq!(count(tags), 2);
+        assert_eq!(count(category), 1);
+        assert_eq!(count(thumbnail), 1);
+        match searcher.search(&ExistsQuery::new(stored), &Count) {
+            Err(TantivyError::SchemaError(_)) => {}
+            _ => panic!("Should have returned an error"),
+        }
+
+        let query = ExistsQuery::new(body);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(2)).unwrap();
+        assert_nearly_equals(top_docs[0].0, 1f32);
+        assert!(searcher.explain(&query, DocAddress(0, 2)).is_ok());
+        assert!(searcher.explain(&query, DocAddress(0, 1)).is_err());
+
+        let missing_body = BooleanQuery::from(vec![
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(title, "d"),
+                    IndexRecordOption::Basic,
+                )) as Box<Query>,
+            ),
+            (Occur::MustNot, Box::new(ExistsQuery::new(body))),
+        ]);
+        assert_eq!(searcher.search(&missing_body, &Count).unwrap(), 1);
+    }
+}
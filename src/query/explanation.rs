@@ -0,0 +1,74 @@
+use error::TantivyError;
+use serde_json;
+use std::fmt;
+use DocId;
+use Score;
+
+/// Returns the error reported when explaining the score
+/// of a document that does not match the query.
+pub(crate) fn does_not_match(doc: DocId) -> TantivyError {
+    TantivyError::InvalidArgument(format!("Document #({}) does not match", doc))
+}
+
+/// Object describing how the score of a document was computed.
+///
+/// An `Explanation` is a tree: the score is described by
+/// its `value`, a `description` of how it was computed,
+/// and the explanations of the values it was computed from.
+///
+/// It is returned by [`Searcher::explain`](../struct.Searcher.html#method.explain).
+#[derive(Clone, Serialize)]
+pub struct Explanation {
+    value: Score,
+    description: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    details: Vec<Explanation>,
+}
+
+impl fmt::Debug for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Explanation({})", self.to_pretty_json())
+    }
+}
+
+impl Explanation {
+    /// Creates a new explanation object.
+    pub fn new<T: ToString>(description: T, value: Score) -> Explanation {
+        Explanation {
+            value,
+            description: description.to_string(),
+            details: vec![],
+        }
+    }
+
+    /// Returns the value associated to the current node.
+    pub fn value(&self) -> Score {
+        self.value
+    }
+
+    /// Returns the description of how the value was computed.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns the explanations of the values the current value was computed from.
+    pub fn details(&self) -> &[Explanation] {
+        &self.details
+    }
+
+    /// Adds the explanation of a value the current value was computed from.
+    pub fn add_detail(&mut self, child_explanation: Explanation) {
+        self.details.push(child_explanation);
+    }
+
+    /// Shortcut for `self.add_detail(Explanation::new(name, value))`.
+    pub fn add_const<T: ToString>(&mut self, name: T, value: Score) {
+        self.details.push(Explanation::new(name, value));
+    }
+
+    /// Returns an indented JSON representation of the explanation tree,
+    /// for debugging purposes.
+    pub fn to_pretty_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
@@ -0,0 +1,182 @@
+use byteorder::{BigEndian, ByteOrder};
+use common::BitSet;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use query::BitSetDocSet;
+use query::ConstScorer;
+use query::{does_not_match, Explanation};
+use query::{AllWeight, EmptyScorer, Scorer, Weight};
+use schema::Field;
+use std::cmp;
+use std::collections::Bound;
+use DocId;
+use Result;
+
+/// Number of documents whose fast field values are read at once.
+const BLOCK_SIZE: usize = 1_024;
+
+/// Converts the bounds of a range of terms into the inclusive bounds
+/// of the `u64` fast field values in this range.
+///
+/// Returns `None` if the range is empty.
+fn u64_bounds(left_bound: &Bound<Vec<u8>>, right_bound: &Bound<Vec<u8>>) -> Option<(u64, u64)> {
+    use std::collections::Bound::*;
+    let lower = match *left_bound {
+        Included(ref term_val) => BigEndian::read_u64(term_val),
+        Excluded(ref term_val) => BigEndian::read_u64(term_val).checked_add(1)?,
+        Unbounded => 0u64,
+    };
+    let upper = match *right_bound {
+        Included(ref term_val) => BigEndian::read_u64(term_val),
+        Excluded(ref term_val) => BigEndian::read_u64(term_val).checked_sub(1)?,
+        Unbounded => u64::max_value(),
+    };
+    if lower <= upper {
+        Some((lower, upper))
+    } else {
+        None
+    }
+}
+
+/// `Weight` of a `RangeQuery` on a fast field which is not indexed.
+///
+/// Rather than walking the terms of the range through the term dictionary,
+/// the fast field values of the documents are scanned block by block.
+/// Segments whose values are all outside of the range, or all within the range,
+/// are answered from the minimum and the maximum value of the fast field, without
+/// any scanning.
+pub struct FastFieldRangeWeight {
+    field: Field,
+    bounds: Option<(u64, u64)>,
+}
+
+impl FastFieldRangeWeight {
+    /// Creates a new `FastFieldRangeWeight` given the bounds of the range,
+    /// as the value bytes of `u64`, `i64` or date terms.
+    pub fn new(
+        field: Field,
+        left_bound: &Bound<Vec<u8>>,
+        right_bound: &Bound<Vec<u8>>,
+    ) -> FastFieldRangeWeight {
+        FastFieldRangeWeight {
+            field,
+            bounds: u64_bounds(left_bound, right_bound),
+        }
+    }
+
+    fn doc_bitset(&self, reader: &SegmentReader, lower: u64, upper: u64) -> BitSet {
+        let max_doc = reader.max_doc();
+        let mut doc_bitset = BitSet::with_max_value(max_doc);
+        let fast_fields = reader.fast_fields();
+        if let Some(fast_field_reader) = fast_fields.u64_lenient(self.field) {
+            let mut vals = [0u64; BLOCK_SIZE];
+            let mut start = 0;
+            while start < max_doc {
+                let len = cmp::min(BLOCK_SIZE as u32, max_doc - start);
+                let block_vals = &mut vals[..len as usize];
+                fast_field_reader.get_range(start, block_vals);
+                for (doc, &val) in (start..).zip(block_vals.iter()) {
+                    if lower <= val && val <= upper {
+                        doc_bitset.insert(doc);
+                    }
+                }
+                start += len;
+            }
+        } else if let Some(multi_value_reader) = fast_fields.u64s_lenient(self.field) {
+            let mut vals = Vec::new();
+            for doc in 0..max_doc {
+                multi_value_reader.get_vals(doc, &mut vals);
+                if vals.iter().any(|&val| lower <= val && val <= upper) {
+                    doc_bitset.insert(doc);
+                }
+            }
+        }
+        doc_bitset
+    }
+}
+
+impl Weight for FastFieldRangeWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let (lower, upper) = match self.bounds {
+            Some(bounds) => bounds,
+            None => return Ok(Box::new(EmptyScorer)),
+        };
+        let fast_fields = reader.fast_fields();
+        let (min_value, max_value, single_valued) =
+            if let Some(fast_field_reader) = fast_fields.u64_lenient(self.field) {
+                let min_value = fast_field_reader.min_value();
+                let max_value = fast_field_reader.max_value();
+                (min_value, max_value, true)
+            } else if let Some(multi_value_reader) = fast_fields.u64s_lenient(self.field) {
+                let min_value = multi_value_reader.min_value();
+                let max_value = multi_value_reader.max_value();
+                (min_value, max_value, false)
+            } else {
+                return Ok(Box::new(EmptyScorer));
+            };
+        if min_value > upper || max_value < lower {
+            return Ok(Box::new(EmptyScorer));
+        }
+        if single_valued && lower <= min_value && max_value <= upper {
+            return AllWeight.scorer(reader);
+        }
+        let doc_bitset = BitSetDocSet::from(self.doc_bitset(reader, lower, upper));
+        Ok(Box::new(ConstScorer::new(doc_bitset)))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut scorer = self.scorer(reader)?;
+        if scorer.skip_next(doc) == SkipResult::Reached {
+            Ok(Explanation::new("RangeQuery", 1f32))
+        } else {
+            Err(does_not_match(doc))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::u64_bounds;
+    use common::i64_to_u64;
+    use schema::{Field, Term};
+    use std::collections::Bound;
+
+    fn bound(bound: Bound<i64>) -> Bound<Vec<u8>> {
+        match bound {
+            Bound::Included(val) => {
+                Bound::Included(Term::from_field_i64(Field(0), val).value_bytes().to_vec())
+            }
+            Bound::Excluded(val) => {
+                Bound::Excluded(Term::from_field_i64(Field(0), val).value_bytes().to_vec())
+            }
+            Bound::Unbounded => Bound::Unbounded,
+        }
+    }
+
+    #[test]
+    fn test_u64_bounds() {
+        let bounds = |left, right| u64_bounds(&bound(left), &bound(right));
+        assert_eq!(
+            bounds(Bound::Included(-3), Bound::Excluded(5)),
+            Some((i64_to_u64(-3), i64_to_u64(4)))
+        );
+        assert_eq!(
+            bounds(Bound::Excluded(-3), Bound::Included(5)),
+            Some((i64_to_u64(-2), i64_to_u64(5)))
+        );
+        assert_eq!(
+            bounds(Bound::Unbounded, Bound::Unbounded),
+            Some((0, u64::max_value()))
+        );
+        assert_eq!(bounds(Bound::Included(2), Bound::Excluded(2)), None);
+        assert_eq!(
+            bounds(Bound::Excluded(i64::max_value()), Bound::Unbounded),
+            None
+        );
+        assert_eq!(
+            bounds(Bound::Unbounded, Bound::Excluded(i64::min_value())),
+            None
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use common::BitSet;
+use common::VInt;
+use core::SegmentId;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::mem;
+use std::sync::{Arc, Mutex, MutexGuard};
+use Opstamp;
+use SegmentReader;
+
+/// Default memory budget of the `FilterCache` of an `IndexReader`, in bytes.
+pub const DEFAULT_FILTER_CACHE_MEMORY_BUDGET: usize = 32_000_000;
+
+/// Identifies the documents matched by a query in the `FilterCache`.
+///
+/// A cache key is made of the type of the query, followed by values identifying
+/// the query among the queries of this type. Two queries having the same cache key
+/// must match the same documents.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct QueryCacheKey(Vec<u8>);
+
+impl QueryCacheKey {
+    /// Creates the cache key of a query of type `query_type`, without any value.
+    pub fn new(query_type: &str) -> QueryCacheKey {
+        let mut key = QueryCacheKey(Vec::new());
+        key.push(query_type.as_bytes());
+        key
+    }
+
+    /// Appends a value identifying the query.
+    ///
+    /// Values are prefixed by their length, so that different sequences
+    /// of values give different keys.
+    pub fn push(&mut self, value: &[u8]) {
+        VInt(value.len() as u64).serialize_into_vec(&mut self.0);
+        self.0.extend_from_slice(value);
+    }
+
+    /// Appends an integer identifying the query, such as a field id.
+    pub fn push_u64(&mut self, value: u64) {
+        VInt(value).serialize_into_vec(&mut self.0);
+    }
+
+    /// Returns the number of bytes of the key.
+    pub fn num_bytes(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Identifies the documents matched by a query on a given segment,
+/// for a given set of deletes.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct FilterCacheKey {
+    segment_id: SegmentId,
+    delete_opstamp: Option<Opstamp>,
+    query: QueryCacheKey,
+}
+
+impl FilterCacheKey {
+    pub(crate) fn new(segment_reader: &SegmentReader, query: QueryCacheKey) -> FilterCacheKey {
+        FilterCacheKey {
+            segment_id: segment_reader.segment_id(),
+            delete_opstamp: segment_reader.delete_opstamp(),
+            query,
+        }
+    }
+}
+
+struct FilterCacheEntry {
+    bitset: Arc<BitSet>,
+    num_bytes: usize,
+    last_access: u64,
+}
+
+#[derive(Default)]
+struct InnerFilterCache {
+    entries: HashMap<FilterCacheKey, FilterCacheEntry>,
+    // entries, from the least recently used to the most recently used.
+    lru: BTreeMap<u64, FilterCacheKey>,
+    clock: u64,
+    memory_usage: usize,
+}
+
+impl InnerFilterCache {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn remove(&mut self, key: &FilterCacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.lru.remove(&entry.last_access);
+            self.memory_usage -= entry.num_bytes;
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) -> bool {
+        let lru_key_opt = self.lru.values().next().cloned();
+        if let Some(lru_key) = lru_key_opt {
+            self.remove(&lru_key);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Approximate memory used by a `BitSet`, in bytes.
+fn bitset_num_bytes(bitset: &BitSet) -> usize {
+    mem::size_of::<BitSet>() + (bitset.max_value() as usize / 64 + 1) * 8
+}
+
+/// Approximate memory used by a cache entry, in bytes.
+///
+/// The key is stored twice, in the entries and in the LRU order.
+fn entry_num_bytes(key: &FilterCacheKey, bitset: &BitSet) -> usize {
+    bitset_num_bytes(bitset) + 2 * (mem::size_of::<FilterCacheKey>() + key.query.num_bytes())
+}
+
+/// Cache of the documents matched by non-scoring queries, such as filters,
+/// on each segment.
+///
+/// The documents alive in a segment that match a query are stored as a `BitSet`,
+/// identified by the id of the segment, the opstamp of its deletes and the query.
+///
+/// The cache is bounded by a memory budget: when it is exceeded, the least recently
+/// used entries are evicted. As segments are immutable, entries never need to be
+/// invalidated, but the entries of the segments that are not searched anymore are
+/// dropped when the `IndexReader` is reloaded.
+///
+/// The `FilterCache` is shared by all of the `Searcher`s of an `IndexReader`,
+/// and is used by [`CachedQuery`](./struct.CachedQuery.html) and by the
+/// filters whose [`cache_key`](./trait.Query.html#method.cache_key) returns a key.
+pub struct FilterCache {
+    memory_budget: usize,
+    inner: Mutex<InnerFilterCache>,
+}
+
+impl FilterCache {
+    /// Creates a `FilterCache` using at most `memory_budget` bytes.
+    ///
+    /// A budget of `0` disables the cache.
+    pub fn with_memory_budget(memory_budget: usize) -> FilterCache {
+        FilterCache {
+            memory_budget,
+            inner: Mutex::new(InnerFilterCache::default()),
+        }
+    }
+
+    /// Returns the memory budget of the cache, in bytes.
+    pub fn memory_budget(&self) -> usize {
+        self.memory_budget
+    }
+
+    /// Returns the memory currently used by the cached `BitSet`s and their keys, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.lock().memory_usage
+    }
+
+    /// Returns the number of cached `BitSet`s.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Returns true iff the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all of the entries of the cache.
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        inner.entries.clear();
+        inner.lru.clear();
+        inner.memory_usage = 0;
+    }
+
+    fn lock<'a>(&'a self) -> MutexGuard<'a, InnerFilterCache> {
+        self.inner
+            .lock()
+            .expect("Filter cache lock poisoned. This should never happen.")
+    }
+
+    /// Returns the cached `BitSet` associated to the key,
+    /// marking it as the most recently used.
+    pub(crate) fn get(&self, key: &FilterCacheKey) -> Option<Arc<BitSet>> {
+        let mut inner = self.lock();
+        let last_access = inner.tick();
+        let (bitset, previous_access) = {
+            let entry = inner.entries.get_mut(key)?;
+            let previous_access = entry.last_access;
+            entry.last_access = last_access;
+            (entry.bitset.clone(), previous_access)
+        };
+        inner.lru.remove(&previous_access);
+        inner.lru.insert(last_access, key.clone());
+        Some(bitset)
+    }
+
+    /// Caches a `BitSet`, evicting the least recently used entries
+    /// if the memory budget is exceeded.
+    ///
+    /// Entries larger than the memory budget are not cached.
+    pub(crate) fn insert(&self, key: FilterCacheKey, bitset: Arc<BitSet>) {
+        let num_bytes = entry_num_bytes(&key, &bitset);
+        if num_bytes > self.memory_budget {
+            return;
+        }
+        let mut inner = self.lock();
+        inner.remove(&key);
+        while inner.memory_usage + num_bytes > self.memory_budget {
+            if !inner.evict_least_recently_used() {
+                break;
+            }
+        }
+        let last_access = inner.tick();
+        inner.lru.insert(last_access, key.clone());
+        inner.memory_usage += num_bytes;
+        inner.entries.insert(
+            key,
+            FilterCacheEntry {
+                bitset,
+                num_bytes,
+                last_access,
+            },
+        );
+    }
+
+    /// Drops the entries of the segments that are not part of `segment_ids`.
+    pub(crate) fn retain_segments(&self, segment_ids: &[SegmentId]) {
+        let segment_ids: HashSet<SegmentId> = segment_ids.iter().cloned().collect();
+        let mut inner = self.lock();
+        let obsolete_keys: Vec<FilterCacheKey> = inner
+            .entries
+            .keys()
+            .filter(|key| !segment_ids.contains(&key.segment_id))
+            .cloned()
+            .collect();
+        for key in &obsolete_keys {
+            inner.remove(key);
+        }
+    }
+}
+
+impl Default for FilterCache {
+    fn default() -> FilterCache {
+        FilterCache::with_memory_budget(DEFAULT_FILTER_CACHE_MEMORY_BUDGET)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{entry_num_bytes, FilterCache, FilterCacheKey, QueryCacheKey};
+    use common::BitSet;
+    use core::SegmentId;
+    use std::sync::Arc;
+
+    fn key(segment_id: SegmentId, query: &str) -> FilterCacheKey {
+        FilterCacheKey {
+            segment_id,
+            delete_opstamp: None,
+            query: {
+                let mut query_key = QueryCacheKey::new("test");
+                query_key.push(query.as_bytes());
+                query_key
+            },
+        }
+    }
+
+    #[test]
+    fn test_filter_cache_lru() {
+        let bitset = Arc::new(BitSet::with_max_value(1_000));
+        let segment_id = SegmentId::generate_random();
+        let num_bytes = entry_num_bytes(&key(segment_id, "a"), &bitset);
+        let filter_cache = FilterCache::with_memory_budget(num_bytes * 2);
+        filter_cache.insert(key(segment_id, "a"), bitset.clone());
+        filter_cache.insert(key(segment_id, "b"), bitset.clone());
+        assert_eq!(filter_cache.len(), 2);
+        assert_eq!(filter_cache.memory_usage(), num_bytes * 2);
+        // "a" becomes the most recently used entry.
+        assert!(filter_cache.get(&key(segment_id, "a")).is_some());
+        filter_cache.insert(key(segment_id, "c"), bitset.clone());
+        assert_eq!(filter_cache.len(), 2);
+        assert!(filter_cache.get(&key(segment_id, "a")).is_some());
+        assert!(filter_cache.get(&key(segment_id, "b")).is_none());
+        assert!(filter_cache.get(&key(segment_id, "c")).is_some());
+        // inserting the same key twice does not count twice.
+        filter_cache.insert(key(segment_id, "c"), bitset.clone());
+        assert_eq!(filter_cache.memory_usage(), num_bytes * 2);
+
+        let too_large = Arc::new(BitSet::with_max_value(1_000_000));
+        filter_cache.insert(key(segment_id, "d"), too_large);
+        assert!(filter_cache.get(&key(segment_id, "d")).is_none());
+        assert_eq!(filter_cache.len(), 2);
+
+        filter_cache.clear();
+        assert!(filter_cache.is_empty());
+        assert_eq!(filter_cache.memory_usage(), 0);
+    }
+
+    #[test]
+    fn test_filter_cache_retain_segments() {
+        let bitset = Arc::new(BitSet::with_max_value(100));
+        let filter_cache = FilterCache::default();
+        let first_segment_id = SegmentId::generate_random();
+        let second_segment_id = SegmentId::generate_random();
+        filter_cache.insert(key(first_segment_id, "a"), bitset.clone());
+        filter_cache.insert(key(second_segment_id, "a"), bitset.clone());
+        filter_cache.retain_segments(&[second_segment_id]);
+        assert_eq!(filter_cache.len(), 1);
+        assert!(filter_cache.get(&key(first_segment_id, "a")).is_none());
+        assert!(filter_cache.get(&key(second_segment_id, "a")).is_some());
+        assert_eq!(
+            filter_cache.memory_usage(),
+            entry_num_bytes(&key(second_segment_id, "a"), &bitset)
+        );
+    }
+
+    #[test]
+    fn test_filter_cache_counts_keys() {
+        let bitset = Arc::new(BitSet::with_max_value(100));
+        let segment_id = SegmentId::generate_random();
+        let long_query: String = (0..1_000)
+            .map(|i| format!("!room{}:matrix.org", i))
+            .collect();
+        let short_key = key(segment_id, "a");
+        let long_key = key(segment_id, &long_query);
+        assert!(
+            entry_num_bytes(&long_key, &bitset) - entry_num_bytes(&short_key, &bitset)
+                >= 2 * (long_query.len() - 1)
+        );
+        let filter_cache = FilterCache::with_memory_budget(entry_num_bytes(&short_key, &bitset));
+        filter_cache.insert(long_key.clone(), bitset.clone());
+        assert!(filter_cache.is_empty());
+        filter_cache.insert(short_key.clone(), bitset.clone());
+        assert_eq!(filter_cache.len(), 1);
+        assert_eq!(
+            filter_cache.memory_usage(),
+            entry_num_bytes(&short_key, &bitset)
+        );
+    }
+}
@@ -0,0 +1,591 @@
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use error::TantivyError;
+use fastfield::FastFieldReader;
+use query::{Explanation, Query, Scorer, Weight};
+use schema::Field;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+use DocId;
+use Result;
+use Score;
+use Searcher;
+use Term;
+
+/// Shape of the curve of a decay function.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decay {
+    /// The value decreases linearly, down to `0`.
+    Linear,
+    /// The value decreases exponentially.
+    Exponential,
+    /// The value follows a normal distribution.
+    Gaussian,
+}
+
+/// Closure computing a factor from the values of some fast fields,
+/// see `ScoreFunction::custom`.
+pub type CustomScoreFunction = Fn(&[f64]) -> Score + Send + Sync;
+
+/// Function computing a factor from the fast field values of a document,
+/// used by a `FunctionScoreQuery` to adjust the score of the document.
+///
+/// The fast fields have to be single-valued `u64` or `i64` fast fields,
+/// e.g. the `i64` timestamp of a message.
+#[derive(Clone)]
+pub enum ScoreFunction {
+    /// Decreases with the distance between the value of the field and `origin`.
+    ///
+    /// The factor is `1` for the values within `offset` of `origin`,
+    /// and `decay` for the values at `offset + scale` of `origin`.
+    Decay {
+        /// Shape of the decay.
+        shape: Decay,
+        /// Field read.
+        field: Field,
+        /// Value for which the factor is `1`, e.g. the current timestamp.
+        origin: f64,
+        /// Distance beyond the offset at which the factor is `decay`.
+        scale: f64,
+        /// Distance to `origin` under which the factor is `1`.
+        offset: f64,
+        /// Factor at `scale` beyond the offset, between `0` and `1`.
+        decay: f64,
+    },
+    /// The value of the field multiplied by `factor`.
+    FieldValueFactor {
+        /// Field read.
+        field: Field,
+        /// Factor applied to the value.
+        factor: f64,
+    },
+    /// `ln(1 + factor * value)`, where `value` is the value of the field.
+    ///
+    /// This is useful to dampen the effect of large values, like a number of likes.
+    Log1p {
+        /// Field read.
+        field: Field,
+        /// Factor applied to the value.
+        factor: f64,
+    },
+    /// Computes the factor with a closure, given the values of the fields.
+    Custom {
+        /// Fields read, in the order of the values given to the closure.
+        fields: Vec<Field>,
+        /// Closure computing the factor.
+        function: Arc<CustomScoreFunction>,
+    },
+}
+
+impl ScoreFunction {
+    fn decay(shape: Decay, field: Field, origin: f64, scale: f64, offset: f64, decay: f64) -> Self {
+        assert!(
+            scale > 0f64,
+            "The scale of a decay function must be positive"
+        );
+        assert!(
+            decay > 0f64 && decay < 1f64,
+            "The decay of a decay function must be between 0 and 1"
+        );
+        ScoreFunction::Decay {
+            shape,
+            field,
+            origin,
+            scale,
+            offset,
+            decay,
+        }
+    }
+
+    /// Creates a linear decay function, see `ScoreFunction::Decay`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is not positive, or if `decay` is not within `(0, 1)`.
+    pub fn linear_decay(field: Field, origin: f64, scale: f64, offset: f64, decay: f64) -> Self {
+        ScoreFunction::decay(Decay::Linear, field, origin, scale, offset, decay)
+    }
+
+    /// Creates an exponential decay function, see `ScoreFunction::Decay`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is not positive, or if `decay` is not within `(0, 1)`.
+    pub fn exponential_decay(
+        field: Field,
+        origin: f64,
+        scale: f64,
+        offset: f64,
+        decay: f64,
+    ) -> Self {
+        ScoreFunction::decay(Decay::Exponential, field, origin, scale, offset, decay)
+    }
+
+    /// Creates a gaussian decay function, see `ScoreFunction::Decay`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is not positive, or if `decay` is not within `(0, 1)`.
+    pub fn gaussian_decay(field: Field, origin: f64, scale: f64, offset: f64, decay: f64) -> Self {
+        ScoreFunction::decay(Decay::Gaussian, field, origin, scale, offset, decay)
+    }
+
+    /// Creates a function multiplying the value of the field by `factor`.
+    pub fn field_value_factor(field: Field, factor: f64) -> Self {
+        ScoreFunction::FieldValueFactor { field, factor }
+    }
+
+    /// Creates a function computing `ln(1 + factor * value)`.
+    pub fn log1p(field: Field, factor: f64) -> Self {
+        ScoreFunction::Log1p { field, factor }
+    }
+
+    /// Creates a function computing the factor with a closure,
+    /// given the values of the fields for the document.
+    pub fn custom<F>(fields: Vec<Field>, function: F) -> Self
+    where
+        F: Fn(&[f64]) -> Score + Send + Sync + 'static,
+    {
+        ScoreFunction::Custom {
+            fields,
+            function: Arc::new(function),
+        }
+    }
+
+    fn fields(&self) -> Vec<Field> {
+        match *self {
+            ScoreFunction::Decay { field, .. }
+            | ScoreFunction::FieldValueFactor { field, .. }
+            | ScoreFunction::Log1p { field, .. } => vec![field],
+            ScoreFunction::Custom { ref fields, .. } => fields.clone(),
+        }
+    }
+
+    /// Computes the factor, given the values of the fields.
+    fn compute(&self, values: &[f64]) -> Score {
+        match *self {
+            ScoreFunction::Decay {
+                shape,
+                origin,
+                scale,
+                offset,
+                decay,
+                ..
+            } => {
+                let distance = ((values[0] - origin).abs() - offset).max(0f64);
+                let factor = match shape {
+                    Decay::Linear => {
+                        let s = scale / (1f64 - decay);
+                        ((s - distance) / s).max(0f64)
+                    }
+                    Decay::Exponential => (decay.ln() / scale * distance).exp(),
+                    Decay::Gaussian => {
+                        let sigma_square = -scale * scale / (2f64 * decay.ln());
+                        (-distance * distance / (2f64 * sigma_square)).exp()
+                    }
+                };
+                factor as Score
+            }
+            ScoreFunction::FieldValueFactor { factor, .. } => (factor * values[0]) as Score,
+            ScoreFunction::Log1p { factor, .. } => (factor * values[0]).ln_1p() as Score,
+            ScoreFunction::Custom { ref function, .. } => function(values),
+        }
+    }
+}
+
+impl fmt::Debug for ScoreFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ScoreFunction::Decay {
+                shape,
+                field,
+                origin,
+                scale,
+                offset,
+                decay,
+            } => write!(
+                f,
+                "{:?}Decay(field={:?}, origin={}, scale={}, offset={}, decay={})",
+                shape, field, origin, scale, offset, decay
+            ),
+            ScoreFunction::FieldValueFactor { field, factor } => {
+                write!(f, "FieldValueFactor(field={:?}, factor={})", field, factor)
+            }
+            ScoreFunction::Log1p { field, factor } => {
+                write!(f, "Log1p(field={:?}, factor={})", field, factor)
+            }
+            ScoreFunction::Custom { ref fields, .. } => write!(f, "Custom(fields={:?})", fields),
+        }
+    }
+}
+
+/// `FunctionScoreQuery` is a wrapper over a query, multiplying its
+/// score by factors computed from the fast fields of the documents.
+///
+/// The document set matched by the `FunctionScoreQuery` is strictly the same
+/// as the underlying query. This makes it possible to rank recent messages higher,
+/// e.g. by multiplying the BM25 score by a decay of their timestamp.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::collector::TopDocs;
+/// use tantivy::query::{FunctionScoreQuery, QueryParser, ScoreFunction};
+/// use tantivy::schema::{Schema, FAST, TEXT};
+/// use tantivy::{DocAddress, Index, Result};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = Schema::builder();
+///     let body = schema_builder.add_text_field("body", TEXT);
+///     let timestamp = schema_builder.add_i64_field("timestamp", FAST);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(body => "hello", timestamp => 1_000i64));
+///         index_writer.add_document(doc!(body => "hello", timestamp => 90_000i64));
+///         index_writer.commit()?;
+///     }
+///     let searcher = index.reader()?.searcher();
+///
+///     let query = QueryParser::for_index(&index, vec![body]).parse_query("hello")?;
+///     let now = 100_000f64;
+///     let one_day = 86_400f64;
+///     let recency = ScoreFunction::exponential_decay(timestamp, now, one_day, 0f64, 0.5);
+///     let query = FunctionScoreQuery::new(query, vec![recency]);
+///     let top_docs = searcher.search(&query, &TopDocs::with_limit(2))?;
+///     assert_eq!(top_docs[0].1, DocAddress(0, 1));
+///     Ok(())
+/// }
+/// ```
+pub struct FunctionScoreQuery {
+    query: Box<Query>,
+    functions: Vec<ScoreFunction>,
+}
+
+impl FunctionScoreQuery {
+    /// Builds a function score query, multiplying the score
+    /// of `query` by the factors computed by each of the `functions`.
+    pub fn new(query: Box<Query>, functions: Vec<ScoreFunction>) -> FunctionScoreQuery {
+        FunctionScoreQuery { query, functions }
+    }
+
+    /// Returns the underlying query.
+    pub fn query(&self) -> &Query {
+        self.query.as_ref()
+    }
+
+    /// Returns the functions computing the factors applied to the score.
+    pub fn functions(&self) -> &[ScoreFunction] {
+        &self.functions[..]
+    }
+}
+
+impl Clone for FunctionScoreQuery {
+    fn clone(&self) -> Self {
+        FunctionScoreQuery {
+            query: self.query.box_clone(),
+            functions: self.functions.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for FunctionScoreQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "FunctionScore(query={:?}, functions={:?})",
+            self.query, self.functions
+        )
+    }
+}
+
+impl Query for FunctionScoreQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        let weight = self.query.weight(searcher, scoring_enabled)?;
+        if !scoring_enabled {
+            return Ok(weight);
+        }
+        Ok(Box::new(FunctionScoreWeight {
+            weight,
+            functions: self.functions.clone(),
+        }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.query.query_terms(term_set)
+    }
+}
+
+/// Reader of the values of a `u64` or `i64` fast field, as `f64`.
+enum FastFieldValues {
+    U64(FastFieldReader<u64>),
+    I64(FastFieldReader<i64>),
+}
+
+impl FastFieldValues {
+    fn open(reader: &SegmentReader, field: Field) -> Result<FastFieldValues> {
+        let fast_fields = reader.fast_fields();
+        if let Some(fast_field_reader) = fast_fields.u64(field) {
+            Ok(FastFieldValues::U64(fast_field_reader))
+        } else if let Some(fast_field_reader) = fast_fields.i64(field) {
+            Ok(FastFieldValues::I64(fast_field_reader))
+        } else {
+            let field_name = reader.schema().get_field_name(field);
+            Err(TantivyError::SchemaError(format!(
+                "Field {:?} is not a single-valued u64 or i64 fast field",
+                field_name
+            )))
+        }
+    }
+
+    fn get(&self, doc: DocId) -> f64 {
+        match *self {
+            FastFieldValues::U64(ref fast_field_reader) => fast_field_reader.get(doc) as f64,
+            FastFieldValues::I64(ref fast_field_reader) => fast_field_reader.get(doc) as f64,
+        }
+    }
+}
+
+/// A `ScoreFunction`, with the readers of the fields it reads in a segment.
+struct SegmentScoreFunction {
+    function: ScoreFunction,
+    fast_field_values: Vec<FastFieldValues>,
+    values: Vec<f64>,
+}
+
+impl SegmentScoreFunction {
+    fn open(reader: &SegmentReader, function: &ScoreFunction) -> Result<SegmentScoreFunction> {
+        let fast_field_values = function
+            .fields()
+            .into_iter()
+            .map(|field| FastFieldValues::open(reader, field))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(SegmentScoreFunction {
+            function: function.clone(),
+            values: Vec::with_capacity(fast_field_values.len()),
+            fast_field_values,
+        })
+    }
+
+    fn compute(&mut self, doc: DocId) -> Score {
+        self.values.clear();
+        for fast_field_values in &self.fast_field_values {
+            self.values.push(fast_field_values.get(doc));
+        }
+        self.function.compute(&self.values)
+    }
+}
+
+struct FunctionScoreWeight {
+    weight: Box<Weight>,
+    functions: Vec<ScoreFunction>,
+}
+
+impl FunctionScoreWeight {
+    fn segment_functions(&self, reader: &SegmentReader) -> Result<Vec<SegmentScoreFunction>> {
+        self.functions
+            .iter()
+            .map(|function| SegmentScoreFunction::open(reader, function))
+            .collect()
+    }
+}
+
+impl Weight for FunctionScoreWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        Ok(Box::new(FunctionScorer {
+            scorer: self.weight.scorer(reader)?,
+            functions: self.segment_functions(reader)?,
+        }))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let underlying_explanation = self.weight.explain(reader, doc)?;
+        let mut score = underlying_explanation.value();
+        let mut function_explanations = Vec::new();
+        for mut function in self.segment_functions(reader)? {
+            let factor = function.compute(doc);
+            score *= factor;
+            function_explanations.push(Explanation::new(
+                format!("{:?}, with values {:?}", function.function, function.values),
+                factor,
+            ));
+        }
+        let mut explanation = Explanation::new("FunctionScoreQuery, product of:", score);
+        explanation.add_detail(underlying_explanation);
+        for function_explanation in function_explanations {
+            explanation.add_detail(function_explanation);
+        }
+        Ok(explanation)
+    }
+}
+
+struct FunctionScorer {
+    scorer: Box<Scorer>,
+    functions: Vec<SegmentScoreFunction>,
+}
+
+impl DocSet for FunctionScorer {
+    fn advance(&mut self) -> bool {
+        self.scorer.advance()
+    }
+
+    fn skip_next(&mut self, target: DocId) -> SkipResult {
+        self.scorer.skip_next(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.scorer.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.scorer.size_hint()
+    }
+}
+
+impl Scorer for FunctionScorer {
+    fn score(&mut self) -> Score {
+        let doc = self.scorer.doc();
+        let mut score = self.scorer.score();
+        for function in &mut self.functions {
+            score *= function.compute(doc);
+        }
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{FunctionScoreQuery, ScoreFunction};
+    use collector::{Count, TopDocs};
+    use query::{AllQuery, Query, TermQuery};
+    use schema::{IndexRecordOption, Schema, FAST, INDEXED, TEXT};
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use Index;
+    use Score;
+    use Term;
+
+    #[test]
+    fn test_score_functions() {
+        let mut schema_builder = Schema::builder();
+        let field = schema_builder.add_u64_field("value", FAST);
+        schema_builder.build();
+        let compute = |function: ScoreFunction, value: f64| function.compute(&[value]);
+
+        // The factor is `decay` at `offset + scale`, and `1` within `offset`.
+        for function in &[
+            ScoreFunction::linear_decay(field, 100f64, 10f64, 5f64, 0.5),
+            ScoreFunction::exponential_decay(field, 100f64, 10f64, 5f64, 0.5),
+            ScoreFunction::gaussian_decay(field, 100f64, 10f64, 5f64, 0.5),
+        ] {
+            assert_nearly_equals(compute(function.clone(), 100f64), 1f32);
+            assert_nearly_equals(compute(function.clone(), 95f64), 1f32);
+            assert_nearly_equals(compute(function.clone(), 115f64), 0.5);
+            assert_nearly_equals(compute(function.clone(), 85f64), 0.5);
+            assert!(compute(function.clone(), 120f64) < 0.5);
+        }
+        let linear_decay = ScoreFunction::linear_decay(field, 0f64, 10f64, 0f64, 0.5);
+        assert_nearly_equals(compute(linear_decay.clone(), 15f64), 0.25);
+        assert_eq!(compute(linear_decay, 30f64), 0f32);
+        let exponential_decay = ScoreFunction::exponential_decay(field, 0f64, 10f64, 0f64, 0.5);
+        assert_nearly_equals(compute(exponential_decay, 20f64), 0.25);
+        let gaussian_decay = ScoreFunction::gaussian_decay(field, 0f64, 10f64, 0f64, 0.5);
+        assert_nearly_equals(compute(gaussian_decay, 20f64), 0.0625);
+
+        assert_nearly_equals(
+            compute(ScoreFunction::field_value_factor(field, 0.5), 3f64),
+            1.5,
+        );
+        assert_nearly_equals(compute(ScoreFunction::log1p(field, 2f64), 3f64), 7f32.ln());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_decay_function_invalid_decay() {
+        let mut schema_builder = Schema::builder();
+        let field = schema_builder.add_u64_field("value", FAST);
+        ScoreFunction::linear_decay(field, 0f64, 10f64, 0f64, 1f64);
+    }
+
+    #[test]
+    fn test_function_score_query() {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let likes = schema_builder.add_u64_field("likes", FAST);
+        let timestamp = schema_builder.add_i64_field("timestamp", FAST);
+        let not_fast = schema_builder.add_u64_field("not_fast", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(text=>"a", likes=>2u64, timestamp=>-10i64));
+            index_writer.add_document(doc!(text=>"a", likes=>1u64, timestamp=>0i64));
+            index_writer.add_document(doc!(text=>"b", likes=>9u64, timestamp=>0i64));
+            index_writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let term_query = || -> Box<Query> {
+            let term = Term::from_field_text(text, "a");
+            Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs))
+        };
+        let a_score = searcher
+            .explain(term_query().as_ref(), DocAddress(0, 0))
+            .unwrap()
+            .value();
+        let top_docs = |query: &Query| -> Vec<(Score, u32)> {
+            searcher
+                .search(query, &TopDocs::with_limit(3))
+                .unwrap()
+                .into_iter()
+                .map(|(score, doc_address)| (score, doc_address.doc()))
+                .collect()
+        };
+
+        let query = FunctionScoreQuery::new(
+            term_query(),
+            vec![ScoreFunction::field_value_factor(likes, 1f64)],
+        );
+        assert_eq!(searcher.search(&query, &Count).unwrap(), 2);
+        let docs = top_docs(&query);
+        assert_eq!(docs[0].1, 0);
+        assert_nearly_equals(docs[0].0, a_score * 2f32);
+        assert_nearly_equals(docs[1].0, a_score);
+
+        let query = FunctionScoreQuery::new(
+            term_query(),
+            vec![
+                ScoreFunction::field_value_factor(likes, 1f64),
+                ScoreFunction::linear_decay(timestamp, 0f64, 10f64, 0f64, 0.25),
+            ],
+        );
+        let docs = top_docs(&query);
+        assert_eq!(docs[0].1, 1);
+        assert_nearly_equals(docs[0].0, a_score);
+        assert_nearly_equals(docs[1].0, a_score * 0.5);
+        let explanation = searcher.explain(&query, DocAddress(0, 0)).unwrap();
+        assert_nearly_equals(explanation.value(), a_score * 0.5);
+        assert_eq!(explanation.details().len(), 3);
+        assert!(searcher.explain(&query, DocAddress(0, 2)).is_err());
+
+        let query = FunctionScoreQuery::new(
+            Box::new(AllQuery),
+            vec![ScoreFunction::custom(
+                vec![likes, timestamp],
+                |values: &[f64]| (values[0] - values[1]) as Score,
+            )],
+        );
+        let docs = top_docs(&query);
+        assert_eq!(docs, vec![(12f32, 0), (9f32, 2), (1f32, 1)]);
+
+        let query = FunctionScoreQuery::new(
+            Box::new(AllQuery),
+            vec![ScoreFunction::log1p(not_fast, 1f64)],
+        );
+        assert!(searcher.search(&query, &TopDocs::with_limit(3)).is_err());
+        assert_eq!(searcher.search(&query, &Count).unwrap(), 3);
+    }
+}
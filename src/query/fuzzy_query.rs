@@ -1,3 +1,4 @@
+use error::TantivyError;
 use levenshtein_automata::{LevenshteinAutomatonBuilder, DFA};
 use query::{AutomatonWeight, Query, Weight};
 use schema::Term;
@@ -75,7 +76,7 @@ pub struct FuzzyTermQuery {
     distance: u8,
     /// Should a transposition cost 1 or 2?
     transposition_cost_one: bool,
-    ///
+    /// Should the term be matched against prefixes of the indexed terms?
     prefix: bool,
 }
 
@@ -90,7 +91,8 @@ impl FuzzyTermQuery {
         }
     }
 
-    /// Creates a new Fuzzy Query that treats transpositions as cost one rather than two
+    /// Creates a new Fuzzy Query matching all of the terms
+    /// that start with a prefix within the given Levenshtein distance.
     pub fn new_prefix(term: Term, distance: u8, transposition_cost_one: bool) -> FuzzyTermQuery {
         FuzzyTermQuery {
             term,
@@ -100,10 +102,40 @@ impl FuzzyTermQuery {
         }
     }
 
+    /// The term searched by this query.
+    pub fn term(&self) -> &Term {
+        &self.term
+    }
+
+    /// The Levenshtein distance allowed between the term and the matched terms.
+    pub fn distance(&self) -> u8 {
+        self.distance
+    }
+
+    /// Returns true if a transposition costs `1`, and `2` otherwise.
+    pub fn transposition_cost_one(&self) -> bool {
+        self.transposition_cost_one
+    }
+
+    /// Returns true if the term is matched against prefixes of the indexed terms.
+    pub fn is_prefix(&self) -> bool {
+        self.prefix
+    }
+
     fn specialized_weight(&self) -> Result<AutomatonWeight<DFA>> {
-        let automaton = LEV_BUILDER.get(&(self.distance, false))
-            .unwrap() // TODO return an error
-            .build_dfa(self.term.text());
+        let automaton_builder = LEV_BUILDER
+            .get(&(self.distance, self.transposition_cost_one))
+            .ok_or_else(|| {
+                TantivyError::InvalidArgument(format!(
+                    "Levenshtein distance of {} is not supported",
+                    self.distance
+                ))
+            })?;
+        let automaton = if self.prefix {
+            automaton_builder.build_prefix_dfa(self.term.text())
+        } else {
+            automaton_builder.build_dfa(self.term.text())
+        };
         Ok(AutomatonWeight::new(self.term.field(), automaton))
     }
 }
@@ -153,5 +185,27 @@ mod test {
             let (score, _) = top_docs[0];
             assert_nearly_equals(1f32, score);
         }
+        {
+            let term = Term::from_field_text(country_field, "jap");
+
+            let fuzzy_query = FuzzyTermQuery::new(term.clone(), 1, true);
+            let top_docs = searcher
+                .search(&fuzzy_query, &TopDocs::with_limit(2))
+                .unwrap();
+            assert!(top_docs.is_empty(), "Expected no document");
+
+            let fuzzy_query = FuzzyTermQuery::new_prefix(term, 1, true);
+            let top_docs = searcher
+                .search(&fuzzy_query, &TopDocs::with_limit(2))
+                .unwrap();
+            assert_eq!(top_docs.len(), 1, "Expected only 1 document");
+        }
+        {
+            let term = Term::from_field_text(country_field, "japan");
+            let fuzzy_query = FuzzyTermQuery::new(term, 3, true);
+            assert!(searcher
+                .search(&fuzzy_query, &TopDocs::with_limit(2))
+                .is_err());
+        }
     }
 }
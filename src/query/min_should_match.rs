@@ -0,0 +1,226 @@
+use docset::{DocSet, SkipResult};
+use query::score_combiner::ScoreCombiner;
+use query::union::unordered_drain_filter;
+use query::Scorer;
+use std::cmp::Ordering;
+use DocId;
+use Score;
+
+/// Creates a `DocSet` that iterates through the documents
+/// matching at least `minimum_should_match` of the underlying scorers.
+///
+/// This is useful for queries like "at least 3 of these 5 optional clauses".
+/// With a `minimum_should_match` of `1`, it matches the same documents
+/// as a `Union` of the scorers.
+pub struct MinShouldMatchScorer<TScorer, TScoreCombiner> {
+    docsets: Vec<TScorer>,
+    minimum_should_match: usize,
+    score_combiner: TScoreCombiner,
+    doc: DocId,
+    score: Score,
+}
+
+impl<TScorer, TScoreCombiner> MinShouldMatchScorer<TScorer, TScoreCombiner>
+where
+    TScorer: Scorer,
+    TScoreCombiner: ScoreCombiner,
+{
+    /// Creates a new `MinShouldMatchScorer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `minimum_should_match` is `0`.
+    pub fn new(
+        docsets: Vec<TScorer>,
+        minimum_should_match: usize,
+    ) -> MinShouldMatchScorer<TScorer, TScoreCombiner> {
+        assert!(minimum_should_match > 0);
+        let non_empty_docsets: Vec<TScorer> = docsets
+            .into_iter()
+            .filter_map(|mut docset| if docset.advance() { Some(docset) } else { None })
+            .collect();
+        MinShouldMatchScorer {
+            docsets: non_empty_docsets,
+            minimum_should_match,
+            score_combiner: TScoreCombiner::default(),
+            doc: 0,
+            score: 0f32,
+        }
+    }
+}
+
+impl<TScorer, TScoreCombiner> DocSet for MinShouldMatchScorer<TScorer, TScoreCombiner>
+where
+    TScorer: Scorer,
+    TScoreCombiner: ScoreCombiner,
+{
+    fn advance(&mut self) -> bool {
+        while self.docsets.len() >= self.minimum_should_match {
+            let doc = self
+                .docsets
+                .iter()
+                .map(DocSet::doc)
+                .min()
+                .expect("There should be at least one docset");
+            self.score_combiner.clear();
+            let mut num_matches = 0;
+            for docset in &mut self.docsets {
+                if docset.doc() == doc {
+                    self.score_combiner.update(docset);
+                    num_matches += 1;
+                }
+            }
+            // The docsets positioned on `doc` are moved past it,
+            // and removed once they are entirely consumed.
+            unordered_drain_filter(&mut self.docsets, |docset| {
+                docset.doc() == doc && !docset.advance()
+            });
+            if num_matches >= self.minimum_should_match {
+                self.doc = doc;
+                self.score = self.score_combiner.score();
+                return true;
+            }
+        }
+        false
+    }
+
+    fn skip_next(&mut self, target: DocId) -> SkipResult {
+        unordered_drain_filter(&mut self.docsets, |docset| {
+            docset.doc() < target && docset.skip_next(target) == SkipResult::End
+        });
+        if !self.advance() {
+            return SkipResult::End;
+        }
+        match self.doc.cmp(&target) {
+            Ordering::Equal => SkipResult::Reached,
+            _ => SkipResult::OverStep,
+        }
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.docsets
+            .iter()
+            .map(|docset| docset.size_hint())
+            .max()
+            .unwrap_or(0u32)
+    }
+}
+
+impl<TScorer, TScoreCombiner> Scorer for MinShouldMatchScorer<TScorer, TScoreCombiner>
+where
+    TScorer: Scorer,
+    TScoreCombiner: ScoreCombiner,
+{
+    fn score(&mut self) -> Score {
+        self.score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::MinShouldMatchScorer;
+    use docset::DocSet;
+    use postings::tests::test_skip_against_unoptimized;
+    use query::score_combiner::{DoNothingCombiner, SumWithCoordsCombiner};
+    use query::{ConstScorer, Scorer, VecDocSet};
+    use std::collections::BTreeMap;
+    use tests;
+    use DocId;
+
+    fn make_scorer(
+        vals: &[Vec<DocId>],
+        minimum_should_match: usize,
+    ) -> MinShouldMatchScorer<ConstScorer<VecDocSet>, DoNothingCombiner> {
+        let docsets = vals
+            .iter()
+            .cloned()
+            .map(VecDocSet::from)
+            .map(ConstScorer::new)
+            .collect();
+        MinShouldMatchScorer::new(docsets, minimum_should_match)
+    }
+
+    fn aux_test_min_should_match(vals: Vec<Vec<DocId>>, minimum_should_match: usize) {
+        let mut num_matches: BTreeMap<DocId, usize> = BTreeMap::new();
+        for docs in &vals {
+            for &doc in docs {
+                *num_matches.entry(doc).or_insert(0) += 1;
+            }
+        }
+        let expected: Vec<DocId> = num_matches
+            .into_iter()
+            .filter(|&(_, count)| count >= minimum_should_match)
+            .map(|(doc, _)| doc)
+            .collect();
+        let mut scorer = make_scorer(&vals, minimum_should_match);
+        let mut docs = Vec::new();
+        while scorer.advance() {
+            docs.push(scorer.doc());
+        }
+        assert_eq!(docs, expected);
+        assert_eq!(
+            make_scorer(&vals, minimum_should_match).count_including_deleted() as usize,
+            expected.len()
+        );
+        let targets: Vec<DocId> = expected
+            .into_iter()
+            .step_by(1_000)
+            .chain(vec![0, 1, 2])
+            .collect();
+        test_skip_against_unoptimized(
+            || {
+                let scorer = make_scorer(&vals, minimum_should_match);
+                Box::new(scorer)
+            },
+            targets,
+        );
+    }
+
+    #[test]
+    fn test_min_should_match() {
+        let vals = vec![
+            vec![1, 2, 5, 7],
+            vec![2, 3, 5, 8],
+            vec![2, 5, 7, 8, 9],
+            vec![],
+        ];
+        aux_test_min_should_match(vals.clone(), 1);
+        aux_test_min_should_match(vals.clone(), 2);
+        aux_test_min_should_match(vals.clone(), 3);
+        aux_test_min_should_match(vals, 4);
+        aux_test_min_should_match(
+            vec![
+                tests::sample_with_seed(100_000, 0.1, 1),
+                tests::sample_with_seed(100_000, 0.2, 2),
+                tests::sample_with_seed(100_000, 0.05, 3),
+                tests::sample_with_seed(100_000, 0.3, 4),
+            ],
+            2,
+        );
+    }
+
+    #[test]
+    fn test_min_should_match_score() {
+        let docsets = vec![
+            ConstScorer::new(VecDocSet::from(vec![1, 2])),
+            ConstScorer::new(VecDocSet::from(vec![2, 3])),
+            ConstScorer::new(VecDocSet::from(vec![1, 2, 3])),
+        ];
+        let mut scorer = MinShouldMatchScorer::<_, SumWithCoordsCombiner>::new(docsets, 2);
+        assert!(scorer.advance());
+        assert_eq!(scorer.doc(), 1);
+        assert_eq!(scorer.score(), 2f32);
+        assert!(scorer.advance());
+        assert_eq!(scorer.doc(), 2);
+        assert_eq!(scorer.score(), 3f32);
+        assert!(scorer.advance());
+        assert_eq!(scorer.doc(), 3);
+        assert_eq!(scorer.score(), 2f32);
+        assert!(!scorer.advance());
+    }
+}
@@ -5,23 +5,41 @@ Query
 mod all_query;
 mod automaton_weight;
 mod bitset;
-mod bm25;
+mod block_max_wand;
 mod boolean_query;
+mod boost_query;
+mod cached_query;
+mod const_score_query;
+mod disjunction_max_query;
 mod empty_query;
 mod exclude;
+mod exists_query;
+mod explanation;
+mod fast_field_range_weight;
+mod filter_cache;
+mod function_score_query;
 mod fuzzy_query;
 mod intersection;
+mod min_should_match;
+mod more_like_this_query;
 mod occur;
 mod phrase_query;
+mod prefix_query;
 mod query;
+mod query_description;
 mod query_parser;
 mod range_query;
 mod regex_query;
 mod reqopt_scorer;
 mod scorer;
+mod similarity;
+mod span_query;
 mod term_query;
+mod term_set_query;
 mod union;
+mod vector_query;
 mod weight;
+mod wildcard_query;
 
 #[cfg(test)]
 mod vec_docset;
@@ -37,13 +55,32 @@ pub use self::all_query::{AllQuery, AllScorer, AllWeight};
 pub use self::automaton_weight::AutomatonWeight;
 pub use self::bitset::BitSetDocSet;
 pub use self::boolean_query::BooleanQuery;
+pub use self::boost_query::{BoostQuery, BoostScorer, BoostWeight};
+pub use self::cached_query::CachedQuery;
+pub use self::const_score_query::ConstScoreQuery;
+pub use self::disjunction_max_query::DisjunctionMaxQuery;
 pub use self::empty_query::{EmptyQuery, EmptyScorer, EmptyWeight};
 pub use self::exclude::Exclude;
+pub use self::exists_query::ExistsQuery;
+pub(crate) use self::explanation::does_not_match;
+pub use self::explanation::Explanation;
+pub(crate) use self::filter_cache::DEFAULT_FILTER_CACHE_MEMORY_BUDGET;
+pub use self::filter_cache::{FilterCache, QueryCacheKey};
+use self::fast_field_range_weight::FastFieldRangeWeight;
+pub use self::function_score_query::{
+    CustomScoreFunction, Decay, FunctionScoreQuery, ScoreFunction,
+};
 pub use self::fuzzy_query::FuzzyTermQuery;
 pub use self::intersection::intersect_scorers;
+pub use self::min_should_match::MinShouldMatchScorer;
+pub use self::more_like_this_query::{MoreLikeThisQuery, MoreLikeThisSource};
 pub use self::occur::Occur;
-pub use self::phrase_query::PhraseQuery;
+pub use self::phrase_query::{MultiPhraseQuery, PhrasePrefixQuery, PhraseQuery};
+pub use self::prefix_query::{PrefixAutomaton, PrefixQuery};
 pub use self::query::Query;
+pub use self::query_description::{
+    QueryDescription, ScoreFunctionDescription, SpanQueryDescription,
+};
 pub use self::query_parser::QueryParser;
 pub use self::query_parser::QueryParserError;
 pub use self::range_query::RangeQuery;
@@ -51,8 +88,16 @@ pub use self::regex_query::RegexQuery;
 pub use self::reqopt_scorer::RequiredOptionalScorer;
 pub use self::scorer::ConstScorer;
 pub use self::scorer::Scorer;
+pub use self::similarity::{
+    BM25Similarity, CollectionStatistics, ConstantSimilarity, Similarity, SimilarityManager,
+    SimilarityWeight, TfIdfSimilarity,
+};
+pub use self::span_query::SpanQuery;
 pub use self::term_query::TermQuery;
+pub use self::term_set_query::TermSetQuery;
+pub use self::vector_query::VectorQuery;
 pub use self::weight::Weight;
+pub use self::wildcard_query::WildcardQuery;
 
 #[cfg(test)]
 mod tests {
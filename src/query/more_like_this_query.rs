@@ -0,0 +1,359 @@
+use query::similarity::idf;
+use query::{BooleanQuery, BoostQuery, Occur, Query, TermQuery, Weight};
+use schema::{Field, IndexRecordOption, Term};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use tokenizer::Token;
+use DocAddress;
+use Result;
+use Searcher;
+
+/// Default minimum number of occurrences of a term in the source of a `MoreLikeThisQuery`.
+pub(crate) const DEFAULT_MIN_TERM_FREQ: usize = 2;
+/// Default minimum number of documents a term of a `MoreLikeThisQuery` must appear in.
+pub(crate) const DEFAULT_MIN_DOC_FREQ: u64 = 5;
+/// Default maximum number of terms of a `MoreLikeThisQuery`.
+pub(crate) const DEFAULT_MAX_QUERY_TERMS: usize = 25;
+
+/// What a `MoreLikeThisQuery` searches documents similar to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MoreLikeThisSource {
+    /// A document of the index, whose stored text values are re-tokenized.
+    Document(DocAddress),
+    /// A raw text, tokenized as a value of each of the fields.
+    Text(String),
+}
+
+/// `MoreLikeThisQuery` matches the documents similar to a given
+/// document, or to a raw text, e.g. to show related content.
+///
+/// The text values of the source are run through the tokenizer of each
+/// of the `fields`. For a document, these are the values read from the store,
+/// so the fields must be stored. The most significant of the resulting terms,
+/// according to their tf-idf, are then searched in a `BooleanQuery` of `Should`
+/// clauses, each of them boosted by the tf-idf of its term.
+///
+/// A term is only kept if:
+/// * it appears at least `min_term_freq` times in the source, `2` by default.
+/// * it appears in at least `min_doc_freq` documents of the index, `5` by default.
+/// * it appears in at most `max_doc_freq` documents of the index, if set.
+///
+/// At most `max_query_terms` terms are kept, `25` by default.
+///
+/// The source document is not excluded from the results, and is
+/// typically the best match.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::collector::TopDocs;
+/// use tantivy::query::MoreLikeThisQuery;
+/// use tantivy::schema::{Schema, STORED, TEXT};
+/// use tantivy::{DocAddress, Index, Result};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = Schema::builder();
+///     let body = schema_builder.add_text_field("body", TEXT | STORED);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(body => "the rust compiler checks the lifetimes"));
+///         index_writer.add_document(doc!(body => "the lifetimes of references in rust"));
+///         index_writer.add_document(doc!(body => "the python interpreter"));
+///         index_writer.commit()?;
+///     }
+///     let searcher = index.reader()?.searcher();
+///
+///     let mut query = MoreLikeThisQuery::with_document(DocAddress(0, 0), vec![body]);
+///     query.set_min_term_freq(1);
+///     query.set_min_doc_freq(1);
+///     query.set_max_doc_freq(2);
+///     let top_docs = searcher.search(&query, &TopDocs::with_limit(3))?;
+///     assert_eq!(top_docs.len(), 2);
+///     assert_eq!(top_docs[0].1, DocAddress(0, 0));
+///     assert_eq!(top_docs[1].1, DocAddress(0, 1));
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MoreLikeThisQuery {
+    source: MoreLikeThisSource,
+    fields: Vec<Field>,
+    min_term_freq: usize,
+    min_doc_freq: u64,
+    max_doc_freq: Option<u64>,
+    max_query_terms: usize,
+}
+
+impl MoreLikeThisQuery {
+    /// Creates a new `MoreLikeThisQuery` matching the documents similar to `source`,
+    /// according to the terms of its values for `fields`.
+    pub fn new(source: MoreLikeThisSource, fields: Vec<Field>) -> MoreLikeThisQuery {
+        MoreLikeThisQuery {
+            source,
+            fields,
+            min_term_freq: DEFAULT_MIN_TERM_FREQ,
+            min_doc_freq: DEFAULT_MIN_DOC_FREQ,
+            max_doc_freq: None,
+            max_query_terms: DEFAULT_MAX_QUERY_TERMS,
+        }
+    }
+
+    /// Creates a new `MoreLikeThisQuery` matching the documents similar to
+    /// the document at `doc_address`, according to its stored values for `fields`.
+    pub fn with_document(doc_address: DocAddress, fields: Vec<Field>) -> MoreLikeThisQuery {
+        MoreLikeThisQuery::new(MoreLikeThisSource::Document(doc_address), fields)
+    }
+
+    /// Creates a new `MoreLikeThisQuery` matching the documents similar to
+    /// `text`, tokenized as a value of each of the `fields`.
+    pub fn with_text(text: &str, fields: Vec<Field>) -> MoreLikeThisQuery {
+        MoreLikeThisQuery::new(MoreLikeThisSource::Text(text.to_string()), fields)
+    }
+
+    /// What this `MoreLikeThisQuery` searches documents similar to.
+    pub fn source(&self) -> &MoreLikeThisSource {
+        &self.source
+    }
+
+    /// The `Field`s the terms are taken from.
+    pub fn fields(&self) -> &[Field] {
+        &self.fields[..]
+    }
+
+    /// Sets the minimum number of occurrences of a term in the source.
+    pub fn set_min_term_freq(&mut self, min_term_freq: usize) {
+        self.min_term_freq = min_term_freq;
+    }
+
+    /// The minimum number of occurrences of a term in the source.
+    pub fn min_term_freq(&self) -> usize {
+        self.min_term_freq
+    }
+
+    /// Sets the minimum number of documents of the index a term must appear in.
+    pub fn set_min_doc_freq(&mut self, min_doc_freq: u64) {
+        self.min_doc_freq = min_doc_freq;
+    }
+
+    /// The minimum number of documents of the index a term must appear in.
+    pub fn min_doc_freq(&self) -> u64 {
+        self.min_doc_freq
+    }
+
+    /// Sets the maximum number of documents of the index a term may appear in,
+    /// e.g. to ignore the words which are too common.
+    pub fn set_max_doc_freq(&mut self, max_doc_freq: u64) {
+        self.max_doc_freq = Some(max_doc_freq);
+    }
+
+    /// The maximum number of documents of the index a term may appear in, if any.
+    pub fn max_doc_freq(&self) -> Option<u64> {
+        self.max_doc_freq
+    }
+
+    /// Sets the maximum number of terms searched.
+    pub fn set_max_query_terms(&mut self, max_query_terms: usize) {
+        self.max_query_terms = max_query_terms;
+    }
+
+    /// The maximum number of terms searched.
+    pub fn max_query_terms(&self) -> usize {
+        self.max_query_terms
+    }
+
+    /// Counts the occurrences of each of the terms of the source.
+    fn term_freqs(&self, searcher: &Searcher) -> Result<HashMap<Term, usize>> {
+        let doc = match self.source {
+            MoreLikeThisSource::Document(doc_address) => Some(searcher.doc(doc_address)?),
+            MoreLikeThisSource::Text(_) => None,
+        };
+        let mut term_freqs: HashMap<Term, usize> = HashMap::new();
+        for &field in &self.fields {
+            let tokenizer = searcher.index().tokenizer_for_field(field)?;
+            let texts: Vec<&str> = if let MoreLikeThisSource::Text(ref text) = self.source {
+                vec![text.as_str()]
+            } else if let Some(ref doc) = doc {
+                doc.get_all(field)
+                    .into_iter()
+                    .filter_map(|value| value.text())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            for text in texts {
+                tokenizer.token_stream(text).process(&mut |token: &Token| {
+                    let term = Term::from_field_text(field, &token.text);
+                    *term_freqs.entry(term).or_insert(0) += 1;
+                });
+            }
+        }
+        Ok(term_freqs)
+    }
+
+    /// Returns the terms searched, with their tf-idf, from the most to the least significant.
+    pub fn terms(&self, searcher: &Searcher) -> Result<Vec<(Term, f32)>> {
+        let num_docs: u64 = searcher
+            .segment_readers()
+            .iter()
+            .map(|segment_reader| u64::from(segment_reader.max_doc()))
+            .sum();
+        let mut scored_terms: Vec<(Term, f32)> = self
+            .term_freqs(searcher)?
+            .into_iter()
+            .filter(|&(_, term_freq)| term_freq >= self.min_term_freq)
+            .filter_map(|(term, term_freq)| {
+                let doc_freq = searcher.doc_freq(&term);
+                if doc_freq == 0 || doc_freq < self.min_doc_freq {
+                    return None;
+                }
+                if let Some(max_doc_freq) = self.max_doc_freq {
+                    if doc_freq > max_doc_freq {
+                        return None;
+                    }
+                }
+                Some((term, term_freq as f32 * idf(doc_freq, num_docs)))
+            })
+            .collect();
+        scored_terms.sort_by(|(left_term, left_score), (right_term, right_score)| {
+            right_score
+                .partial_cmp(left_score)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| left_term.cmp(right_term))
+        });
+        scored_terms.truncate(self.max_query_terms);
+        Ok(scored_terms)
+    }
+
+    /// Builds the `BooleanQuery` searching the most significant terms of the source,
+    /// each of them boosted by its tf-idf.
+    pub fn query(&self, searcher: &Searcher) -> Result<BooleanQuery> {
+        let clauses: Vec<(Occur, Box<Query>)> = self
+            .terms(searcher)?
+            .into_iter()
+            .map(|(term, score)| {
+                let term_query = Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs));
+                let boost_query: Box<Query> = Box::new(BoostQuery::new(term_query, score));
+                (Occur::Should, boost_query)
+            })
+            .collect();
+        Ok(BooleanQuery::from(clauses))
+    }
+}
+
+impl Query for MoreLikeThisQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        self.query(searcher)?.weight(searcher, scoring_enabled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::MoreLikeThisQuery;
+    use collector::{Count, TopDocs};
+    use error::TantivyError;
+    use query::BoostQuery;
+    use schema::{Field, Schema, INDEXED, STORED, TEXT};
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use Index;
+    use Term;
+
+    fn create_index() -> (Index, Field, Field) {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(title=>"rust rust borrow checker", body=>"b"));
+            index_writer.add_document(doc!(title=>"the rust borrow checker"));
+            index_writer.add_document(doc!(title=>"rust lifetimes"));
+            index_writer.add_document(doc!(title=>"the python interpreter"));
+            index_writer.add_document(doc!(title=>"the borrow of a book"));
+            index_writer.commit().unwrap();
+        }
+        (index, title, body)
+    }
+
+    #[test]
+    fn test_more_like_this_query_terms() {
+        let (index, title, body) = create_index();
+        let searcher = index.reader().unwrap().searcher();
+        let term = |text: &str| Term::from_field_text(title, text);
+        let texts = |query: &MoreLikeThisQuery| -> Vec<String> {
+            query
+                .terms(&searcher)
+                .unwrap()
+                .into_iter()
+                .map(|(term, _)| term.text().to_string())
+                .collect()
+        };
+
+        let mut query = MoreLikeThisQuery::with_document(DocAddress(0, 0), vec![title, body]);
+        assert!(texts(&query).is_empty());
+        query.set_min_doc_freq(1);
+        assert_eq!(texts(&query), vec!["rust"]);
+        query.set_min_term_freq(1);
+        assert_eq!(texts(&query), vec!["rust", "checker", "borrow"]);
+        query.set_max_doc_freq(2);
+        assert_eq!(texts(&query), vec!["checker"]);
+        query.set_max_doc_freq(3);
+        query.set_max_query_terms(2);
+        assert_eq!(texts(&query), vec!["rust", "checker"]);
+
+        let mut query = MoreLikeThisQuery::with_text("The Borrow Checker checker", vec![title]);
+        query.set_min_doc_freq(1);
+        assert_eq!(texts(&query), vec!["checker"]);
+        query.set_min_doc_freq(3);
+        query.set_min_term_freq(1);
+        assert_eq!(texts(&query), vec!["borrow", "the"]);
+
+        let boolean_query = query.query(&searcher).unwrap();
+        let terms = query.terms(&searcher).unwrap();
+        assert_eq!(boolean_query.clauses().len(), 2);
+        let boost_query = boolean_query.clauses()[0]
+            .1
+            .downcast_ref::<BoostQuery>()
+            .unwrap();
+        assert_eq!(terms[0].0, term("borrow"));
+        assert_nearly_equals(boost_query.boost(), terms[0].1);
+    }
+
+    #[test]
+    fn test_more_like_this_query_search() {
+        let (index, title, _) = create_index();
+        let searcher = index.reader().unwrap().searcher();
+        let mut query = MoreLikeThisQuery::with_document(DocAddress(0, 1), vec![title]);
+        query.set_min_term_freq(1);
+        query.set_min_doc_freq(1);
+        query.set_max_doc_freq(3);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(5)).unwrap();
+        let docs: Vec<u32> = top_docs
+            .iter()
+            .map(|(_, doc_address)| doc_address.1)
+            .collect();
+        assert_eq!(docs, vec![1, 0, 4, 2, 3]);
+        let explanation = searcher.explain(&query, DocAddress(0, 1)).unwrap();
+        assert_nearly_equals(explanation.value(), top_docs[0].0);
+
+        query.set_max_doc_freq(2);
+        assert_eq!(searcher.search(&query, &Count).unwrap(), 2);
+
+        // Nothing is searched for if no term is significant enough.
+        query.set_min_doc_freq(10);
+        assert_eq!(searcher.search(&query, &Count).unwrap(), 0);
+
+        let mut schema_builder = Schema::builder();
+        let year = schema_builder.add_u64_field("year", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let searcher = index.reader().unwrap().searcher();
+        match searcher.search(&MoreLikeThisQuery::with_text("1", vec![year]), &Count) {
+            Err(TantivyError::SchemaError(_)) => {}
+            _ => panic!("Should have returned an error"),
+        }
+    }
+}
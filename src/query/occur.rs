@@ -1,16 +1,23 @@
 /// Defines whether a term in a query must be present,
 /// should be present or must not be present.
-#[derive(Debug, Clone, Hash, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Hash, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Occur {
     /// For a given document to be considered for scoring,
     /// at least one of the document with the Should or the Must
     /// Occur constraint must be within the document.
+    #[serde(rename = "should")]
     Should,
     /// Document without the term are excluded from the search.
+    #[serde(rename = "must")]
     Must,
     /// Document that contain the term are excluded from the
     /// search.
+    #[serde(rename = "must_not")]
     MustNot,
+    /// Document without the term are excluded from the search,
+    /// like with `Must`, but the term does not contribute to the score.
+    #[serde(rename = "filter")]
+    Filter,
 }
 
 impl Occur {
@@ -18,11 +25,13 @@ impl Occur {
     /// - `Should` => '?',
     /// - `Must` => '+'
     /// - `Not` => '-'
+    /// - `Filter` => '#'
     pub fn to_char(self) -> char {
         match self {
             Occur::Should => '?',
             Occur::Must => '+',
             Occur::MustNot => '-',
+            Occur::Filter => '#',
         }
     }
 }
@@ -31,18 +40,23 @@ impl Occur {
 pub fn compose_occur(left: Occur, right: Occur) -> Occur {
     match left {
         Occur::Should => right,
-        Occur::Must => {
+        Occur::Must => match right {
+            Occur::MustNot => Occur::MustNot,
+            Occur::Filter => Occur::Filter,
+            _ => Occur::Must,
+        },
+        Occur::MustNot => {
             if right == Occur::MustNot {
-                Occur::MustNot
-            } else {
                 Occur::Must
+            } else {
+                Occur::MustNot
             }
         }
-        Occur::MustNot => {
+        Occur::Filter => {
             if right == Occur::MustNot {
-                Occur::Must
-            } else {
                 Occur::MustNot
+            } else {
+                Occur::Filter
             }
         }
     }
@@ -1,18 +1,27 @@
+mod multi_phrase_query;
+mod phrase_prefix_query;
 mod phrase_query;
 mod phrase_scorer;
 mod phrase_weight;
+mod union_postings;
 
+pub use self::multi_phrase_query::MultiPhraseQuery;
+pub use self::phrase_prefix_query::PhrasePrefixQuery;
+pub(crate) use self::phrase_prefix_query::DEFAULT_MAX_EXPANSIONS;
 pub use self::phrase_query::PhraseQuery;
 pub use self::phrase_scorer::PhraseScorer;
 pub use self::phrase_weight::PhraseWeight;
+pub use self::union_postings::UnionPostings;
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
     use collector::tests::TestCollector;
+    use collector::Count;
     use core::Index;
     use error::TantivyError;
+    use query::Query;
     use schema::{Schema, Term, TEXT};
     use tests::assert_nearly_equals;
     use DocAddress;
@@ -130,6 +139,92 @@ mod tests {
         assert_nearly_equals(scores[1], 0.46844664);
     }
 
+    #[test]
+    pub fn test_phrase_query_explain() {
+        let index = create_index(&["a b c", "a c", "c a"]);
+        let schema = index.schema();
+        let text_field = schema.get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let mut phrase_query = PhraseQuery::new(vec![
+            Term::from_field_text(text_field, "a"),
+            Term::from_field_text(text_field, "c"),
+        ]);
+        phrase_query.set_slop(1);
+        let scores = searcher
+            .search(&phrase_query, &TestCollector)
+            .unwrap()
+            .scores()
+            .to_vec();
+        for (doc, &score) in scores.iter().enumerate() {
+            let explanation = searcher
+                .explain(&phrase_query, DocAddress(0, doc as DocId))
+                .unwrap();
+            assert_nearly_equals(score, explanation.value());
+        }
+        let explanation = searcher.explain(&phrase_query, DocAddress(0, 0)).unwrap();
+        let bm25_explanation = &explanation.details()[0];
+        let idf_explanation = &bm25_explanation.details()[1];
+        assert_eq!(idf_explanation.details().len(), 2);
+        let tf_explanation = &bm25_explanation.details()[2];
+        assert!(tf_explanation.details()[0].value() < 1f32);
+        assert!(searcher.explain(&phrase_query, DocAddress(0, 2)).is_err());
+    }
+
+    #[test]
+    pub fn test_phrase_query_slop() {
+        let index = create_index(&["a b c d", "a x b", "b a", "a x y z b", "b b", "b"]);
+        let schema = index.schema();
+        let text_field = schema.get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let test_query = |texts: Vec<&str>, slop: u32| {
+            let terms: Vec<Term> = texts
+                .iter()
+                .map(|text| Term::from_field_text(text_field, text))
+                .collect();
+            let mut phrase_query = PhraseQuery::new(terms);
+            phrase_query.set_slop(slop);
+            let test_fruits = searcher
+                .search(&phrase_query, &TestCollector)
+                .expect("search should succeed");
+            assert_eq!(
+                phrase_query.count(&searcher).unwrap(),
+                test_fruits.docs().len()
+            );
+            test_fruits
+                .docs()
+                .iter()
+                .map(|docaddr| docaddr.1)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(test_query(vec!["a", "b"], 0), vec![0]);
+        assert_eq!(test_query(vec!["a", "b"], 1), vec![0, 1]);
+        assert_eq!(test_query(vec!["a", "b"], 2), vec![0, 1, 2]);
+        assert_eq!(test_query(vec!["a", "b"], 3), vec![0, 1, 2, 3]);
+        assert_eq!(test_query(vec!["a", "c"], 1), vec![0]);
+        assert_eq!(test_query(vec!["b", "b"], 1), vec![4]);
+        assert_eq!(test_query(vec!["a", "b", "c"], 1), vec![0]);
+    }
+
+    #[test]
+    pub fn test_phrase_query_slop_score() {
+        let index = create_index(&["a b", "a x b", "a x x b"]);
+        let schema = index.schema();
+        let text_field = schema.get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let mut phrase_query = PhraseQuery::new(vec![
+            Term::from_field_text(text_field, "a"),
+            Term::from_field_text(text_field, "b"),
+        ]);
+        phrase_query.set_slop(2);
+        let fruit = searcher
+            .search(&phrase_query, &TestCollector)
+            .expect("search should succeed");
+        let scores = fruit.scores();
+        assert_eq!(scores.len(), 3);
+        assert!(scores[0] > scores[1]);
+        assert!(scores[1] > scores[2]);
+    }
+
     #[test] // motivated by #234
     pub fn test_phrase_query_docfreq_order() {
         let mut schema_builder = Schema::builder();
@@ -197,4 +292,182 @@ mod tests {
         assert!(test_query(vec![(0, "a"), (2, "d")]).is_empty());
         assert_eq!(test_query(vec![(1, "a"), (3, "c")]), vec![0]);
     }
+
+    #[test]
+    pub fn test_multi_phrase_query() {
+        let index = create_index(&[
+            "a quick car",
+            "a fast car",
+            "a fast and quick car",
+            "the car is quick",
+            "quick fast",
+        ]);
+        let schema = index.schema();
+        let text_field = schema.get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let test_query = |texts: Vec<Vec<&str>>, slop: u32| {
+            let terms: Vec<Vec<Term>> = texts
+                .iter()
+                .map(|texts| {
+                    texts
+                        .iter()
+                        .map(|text| Term::from_field_text(text_field, text))
+                        .collect()
+                })
+                .collect();
+            let mut multi_phrase_query = MultiPhraseQuery::new(terms);
+            multi_phrase_query.set_slop(slop);
+            searcher
+                .search(&multi_phrase_query, &TestCollector)
+                .expect("search should succeed")
+                .docs()
+                .iter()
+                .map(|doc_address| doc_address.1)
+                .collect::<Vec<DocId>>()
+        };
+        assert_eq!(
+            test_query(vec![vec!["quick", "fast"], vec!["car"]], 0),
+            vec![0, 1, 2]
+        );
+        assert_eq!(test_query(vec![vec!["fast"], vec!["car"]], 0), vec![1]);
+        assert_eq!(
+            test_query(vec![vec!["a"], vec!["quick", "fast"]], 0),
+            vec![0, 1, 2]
+        );
+        assert_eq!(
+            test_query(vec![vec!["a"], vec!["missing", "quick"]], 0),
+            vec![0]
+        );
+        assert!(test_query(vec![vec!["a"], vec!["missing"]], 0).is_empty());
+        assert_eq!(
+            test_query(vec![vec!["car"], vec!["quick", "fast"]], 1),
+            vec![3]
+        );
+        // the same token can not match two positions
+        assert!(test_query(
+            vec![vec!["quick", "fast"], vec!["quick", "fast"], vec!["car"]],
+            0
+        )
+        .is_empty());
+        assert_eq!(
+            test_query(vec![vec!["quick", "fast"], vec!["quick", "fast"]], 0),
+            vec![4]
+        );
+
+        // A multi phrase query with a single term at each position
+        // is scored as a phrase query.
+        let terms = vec![
+            Term::from_field_text(text_field, "fast"),
+            Term::from_field_text(text_field, "car"),
+        ];
+        let phrase_query = PhraseQuery::new(terms.clone());
+        let multi_phrase_query =
+            MultiPhraseQuery::new(terms.into_iter().map(|term| vec![term]).collect());
+        let phrase_explanation = searcher.explain(&phrase_query, DocAddress(0, 1)).unwrap();
+        let multi_phrase_explanation = searcher
+            .explain(&multi_phrase_query, DocAddress(0, 1))
+            .unwrap();
+        assert_nearly_equals(phrase_explanation.value(), multi_phrase_explanation.value());
+    }
+
+    #[test]
+    pub fn test_multi_phrase_query_score() {
+        let index = create_index(&["a b", "a c", "a b a c", "a b c"]);
+        let schema = index.schema();
+        let text_field = schema.get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let multi_phrase_query = MultiPhraseQuery::new(vec![
+            vec![Term::from_field_text(text_field, "a")],
+            vec![
+                Term::from_field_text(text_field, "b"),
+                Term::from_field_text(text_field, "c"),
+            ],
+        ]);
+        let fruit = searcher
+            .search(&multi_phrase_query, &TestCollector)
+            .expect("search should succeed");
+        assert_eq!(fruit.docs().len(), 4);
+        for (doc_address, &score) in fruit.docs().iter().zip(fruit.scores()) {
+            let explanation = searcher.explain(&multi_phrase_query, *doc_address).unwrap();
+            assert_nearly_equals(score, explanation.value());
+        }
+        let scores = fruit.scores();
+        assert_nearly_equals(scores[0], scores[1]);
+        assert!(scores[2] > scores[0]);
+    }
+
+    #[test]
+    pub fn test_phrase_prefix_query() {
+        let index = create_index(&[
+            "hello world",
+            "hello word",
+            "hello, this is the world",
+            "hello wo",
+            "hello war",
+            "world hello",
+        ]);
+        let schema = index.schema();
+        let text_field = schema.get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let test_query = |texts: Vec<&str>, max_expansions: Option<usize>| {
+            let terms: Vec<Term> = texts
+                .iter()
+                .map(|text| Term::from_field_text(text_field, text))
+                .collect();
+            let mut phrase_prefix_query = PhrasePrefixQuery::new(terms);
+            if let Some(max_expansions) = max_expansions {
+                phrase_prefix_query.set_max_expansions(max_expansions);
+            }
+            let test_fruits = searcher
+                .search(&phrase_prefix_query, &TestCollector)
+                .expect("search should succeed");
+            for (doc_address, &score) in test_fruits.docs().iter().zip(test_fruits.scores()) {
+                let explanation = searcher
+                    .explain(&phrase_prefix_query, *doc_address)
+                    .unwrap();
+                assert_nearly_equals(score, explanation.value());
+            }
+            test_fruits
+                .docs()
+                .iter()
+                .map(|doc_address| doc_address.1)
+                .collect::<Vec<DocId>>()
+        };
+        assert_eq!(test_query(vec!["hello", "wor"], None), vec![0, 1]);
+        assert_eq!(test_query(vec!["hello", "w"], None), vec![0, 1, 3, 4]);
+        assert_eq!(test_query(vec!["hello", "world"], None), vec![0]);
+        assert!(test_query(vec!["hello", "x"], None).is_empty());
+        assert!(test_query(vec!["missing", "wor"], None).is_empty());
+        // "wa", "wo", "word", "world" in lexicographic order.
+        assert_eq!(test_query(vec!["hello", "w"], Some(2)), vec![3, 4]);
+        assert_eq!(test_query(vec!["hello", "w"], Some(3)), vec![1, 3, 4]);
+        assert!(test_query(vec!["hello", "w"], Some(0)).is_empty());
+        assert_eq!(test_query(vec!["world", "h"], None), vec![5]);
+    }
+
+    #[test]
+    pub fn test_phrase_prefix_query_expansions_across_segments() {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(text_field=>"a bc"));
+            index_writer.add_document(doc!(text_field=>"a bd"));
+            assert!(index_writer.commit().is_ok());
+            index_writer.add_document(doc!(text_field=>"a bb"));
+            index_writer.add_document(doc!(text_field=>"a bd"));
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+        let mut phrase_prefix_query = PhrasePrefixQuery::new(vec![
+            Term::from_field_text(text_field, "a"),
+            Term::from_field_text(text_field, "b"),
+        ]);
+        assert_eq!(searcher.search(&phrase_prefix_query, &Count).unwrap(), 4);
+        // "bb" and "bc" are the first two expansions over all segments.
+        phrase_prefix_query.set_max_expansions(2);
+        assert_eq!(searcher.search(&phrase_prefix_query, &Count).unwrap(), 2);
+    }
 }
@@ -0,0 +1,153 @@
+use super::phrase_query::check_has_positions;
+use super::PhraseWeight;
+use core::searcher::Searcher;
+use query::similarity;
+use query::Query;
+use query::Weight;
+use schema::{Field, Term};
+use std::collections::BTreeSet;
+use Result;
+
+/// `MultiPhraseQuery` matches a sequence of words, accepting any
+/// of several terms at each position of the sequence.
+///
+/// For instance the multi phrase query for `["quick", "fast"] ["car"]`
+/// matches both **quick car** and **fast car**, which is useful to search
+/// a phrase together with the synonyms of its words.
+///
+/// Using a `MultiPhraseQuery` on a field requires positions
+/// to be indexed for this field.
+///
+/// Like for a `PhraseQuery`, a slop can be set with
+/// [`.set_slop(...)`](#method.set_slop).
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::collector::Count;
+/// use tantivy::query::MultiPhraseQuery;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{Index, Result, Term};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = Schema::builder();
+///     let body = schema_builder.add_text_field("body", TEXT);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(body => "a quick car"));
+///         index_writer.add_document(doc!(body => "a fast car"));
+///         index_writer.add_document(doc!(body => "a fast and quick car"));
+///         index_writer.commit()?;
+///     }
+///     let searcher = index.reader()?.searcher();
+///
+///     let query = MultiPhraseQuery::new(vec![
+///         vec![
+///             Term::from_field_text(body, "quick"),
+///             Term::from_field_text(body, "fast"),
+///         ],
+///         vec![Term::from_field_text(body, "car")],
+///     ]);
+///     assert_eq!(searcher.search(&query, &Count)?, 3);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MultiPhraseQuery {
+    field: Field,
+    phrase_terms: Vec<(usize, Vec<Term>)>,
+    slop: u32,
+}
+
+impl MultiPhraseQuery {
+    /// Creates a new `MultiPhraseQuery` given, for each position of the phrase,
+    /// the terms accepted at this position.
+    ///
+    /// There must be at least two positions, each with at least one term,
+    /// and all terms must belong to the same field.
+    pub fn new(terms: Vec<Vec<Term>>) -> MultiPhraseQuery {
+        let terms_with_offset = terms.into_iter().enumerate().collect();
+        MultiPhraseQuery::new_with_offset(terms_with_offset)
+    }
+
+    /// Creates a new `MultiPhraseQuery` given the terms accepted at each offset.
+    ///
+    /// Can be used to provide custom offset for each position.
+    pub fn new_with_offset(mut terms: Vec<(usize, Vec<Term>)>) -> MultiPhraseQuery {
+        assert!(
+            terms.len() > 1,
+            "A multi phrase query is required to have strictly more than one position."
+        );
+        assert!(
+            terms.iter().all(|(_, terms)| !terms.is_empty()),
+            "A multi phrase query requires at least one term at each position."
+        );
+        terms.sort_by_key(|&(offset, _)| offset);
+        let field = terms[0].1[0].field();
+        assert!(
+            terms
+                .iter()
+                .flat_map(|(_, terms)| terms.iter())
+                .all(|term| term.field() == field),
+            "All terms from a multi phrase query must belong to the same field"
+        );
+        MultiPhraseQuery {
+            field,
+            phrase_terms: terms,
+            slop: 0,
+        }
+    }
+
+    /// Sets the slop of the phrase query.
+    ///
+    /// See [`PhraseQuery::set_slop`](./struct.PhraseQuery.html#method.set_slop).
+    pub fn set_slop(&mut self, value: u32) {
+        self.slop = value;
+    }
+
+    /// Returns the slop of the phrase query.
+    pub fn slop(&self) -> u32 {
+        self.slop
+    }
+
+    /// The `Field` this `MultiPhraseQuery` is targeting.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// Terms accepted at each position with their offsets, sorted by offset.
+    pub fn phrase_terms_with_offsets(&self) -> &[(usize, Vec<Term>)] {
+        &self.phrase_terms[..]
+    }
+}
+
+impl Query for MultiPhraseQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        check_has_positions(searcher, self.field)?;
+        let similarity_weight = if scoring_enabled {
+            let terms: Vec<Term> = self
+                .phrase_terms
+                .iter()
+                .flat_map(|(_, terms)| terms.iter().cloned())
+                .collect();
+            similarity::weight_for_terms(searcher, &terms)?
+        } else {
+            similarity::disabled_weight()
+        };
+        Ok(Box::new(PhraseWeight::with_alternatives(
+            self.phrase_terms.clone(),
+            similarity_weight,
+            scoring_enabled,
+            self.slop,
+        )))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for (_, terms) in &self.phrase_terms {
+            term_set.extend(terms.iter().cloned());
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use super::phrase_query::check_has_positions;
+use super::PhraseWeight;
+use core::searcher::Searcher;
+use query::similarity;
+use query::EmptyWeight;
+use query::Query;
+use query::Weight;
+use schema::{Field, Term};
+use std::collections::BTreeSet;
+use Result;
+
+/// Default maximum number of terms the prefix of a `PhrasePrefixQuery` expands to.
+pub(crate) const DEFAULT_MAX_EXPANSIONS: usize = 50;
+
+/// `PhrasePrefixQuery` matches a sequence of words whose last word
+/// is only given by a prefix.
+///
+/// For instance the phrase prefix query for `"hello wor"` matches
+/// **hello world** and **hello world wide web**, which is typically
+/// used to search as the user types.
+///
+/// The prefix is expanded to the terms of the index starting with it,
+/// looked up through the term dictionary. To bound the cost of the query,
+/// it is expanded to at most `max_expansions` terms, the first ones in
+/// lexicographic order, `50` by default.
+///
+/// Using a `PhrasePrefixQuery` on a field requires positions
+/// to be indexed for this field.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::collector::Count;
+/// use tantivy::query::PhrasePrefixQuery;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{Index, Result, Term};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = Schema::builder();
+///     let body = schema_builder.add_text_field("body", TEXT);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(body => "hello world"));
+///         index_writer.add_document(doc!(body => "hello word"));
+///         index_writer.add_document(doc!(body => "hello, this is the world"));
+///         index_writer.commit()?;
+///     }
+///     let searcher = index.reader()?.searcher();
+///
+///     let query = PhrasePrefixQuery::new(vec![
+///         Term::from_field_text(body, "hello"),
+///         Term::from_field_text(body, "wor"),
+///     ]);
+///     assert_eq!(searcher.search(&query, &Count)?, 2);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct PhrasePrefixQuery {
+    field: Field,
+    phrase_terms: Vec<(usize, Term)>,
+    prefix: (usize, Term),
+    slop: u32,
+    max_expansions: usize,
+}
+
+impl PhrasePrefixQuery {
+    /// Creates a new `PhrasePrefixQuery` given a list of terms,
+    /// the last one being the prefix.
+    ///
+    /// There must be at least two terms, and all terms
+    /// must belong to the same field.
+    pub fn new(terms: Vec<Term>) -> PhrasePrefixQuery {
+        let terms_with_offset = terms.into_iter().enumerate().collect();
+        PhrasePrefixQuery::new_with_offset(terms_with_offset)
+    }
+
+    /// Creates a new `PhrasePrefixQuery` given a list of terms and their offsets,
+    /// the term with the largest offset being the prefix.
+    pub fn new_with_offset(mut terms: Vec<(usize, Term)>) -> PhrasePrefixQuery {
+        assert!(
+            terms.len() > 1,
+            "A phrase prefix query is required to have strictly more than one term."
+        );
+        terms.sort_by_key(|&(offset, _)| offset);
+        let field = terms[0].1.field();
+        assert!(
+            terms[1..].iter().all(|term| term.1.field() == field),
+            "All terms from a phrase prefix query must belong to the same field"
+        );
+        let prefix = terms.pop().unwrap(); //< terms has at least two elements.
+        PhrasePrefixQuery {
+            field,
+            phrase_terms: terms,
+            prefix,
+            slop: 0,
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
+        }
+    }
+
+    /// Sets the slop of the phrase query.
+    ///
+    /// See [`PhraseQuery::set_slop`](./struct.PhraseQuery.html#method.set_slop).
+    pub fn set_slop(&mut self, value: u32) {
+        self.slop = value;
+    }
+
+    /// Returns the slop of the phrase query.
+    pub fn slop(&self) -> u32 {
+        self.slop
+    }
+
+    /// Limits the number of terms the prefix may expand to.
+    ///
+    /// Only the first `max_expansions` matching terms, in lexicographic order, are searched.
+    pub fn set_max_expansions(&mut self, max_expansions: usize) {
+        self.max_expansions = max_expansions;
+    }
+
+    /// The maximum number of terms the prefix may expand to.
+    pub fn max_expansions(&self) -> usize {
+        self.max_expansions
+    }
+
+    /// The `Field` this `PhrasePrefixQuery` is targeting.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// `Term`s of the phrase preceding the prefix, with their offsets, sorted by offset.
+    pub fn phrase_terms_with_offsets(&self) -> &[(usize, Term)] {
+        &self.phrase_terms[..]
+    }
+
+    /// The prefix ending the phrase, with its offset.
+    pub fn prefix_with_offset(&self) -> &(usize, Term) {
+        &self.prefix
+    }
+
+    /// Returns the first `max_expansions` terms of the index starting with the prefix.
+    fn expand_prefix(&self, searcher: &Searcher) -> Vec<Term> {
+        let prefix = self.prefix.1.value_bytes();
+        let mut expansions: BTreeSet<Vec<u8>> = BTreeSet::new();
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(self.field);
+            let mut term_stream = inverted_index.terms().range().ge(prefix).into_stream();
+            let mut num_expansions = 0;
+            while num_expansions < self.max_expansions && term_stream.advance() {
+                let key = term_stream.key();
+                if !key.starts_with(prefix) {
+                    break;
+                }
+                expansions.insert(key.to_vec());
+                num_expansions += 1;
+            }
+        }
+        expansions
+            .into_iter()
+            .take(self.max_expansions)
+            .map(|key| Term::from_field_bytes(self.field, &key))
+            .collect()
+    }
+}
+
+impl Query for PhrasePrefixQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        check_has_positions(searcher, self.field)?;
+        let expansions = self.expand_prefix(searcher);
+        if expansions.is_empty() {
+            return Ok(Box::new(EmptyWeight));
+        }
+        let mut phrase_terms: Vec<(usize, Vec<Term>)> = self
+            .phrase_terms
+            .iter()
+            .map(|(offset, term)| (*offset, vec![term.clone()]))
+            .collect();
+        phrase_terms.push((self.prefix.0, expansions));
+        let similarity_weight = if scoring_enabled {
+            let terms: Vec<Term> = phrase_terms
+                .iter()
+                .flat_map(|(_, terms)| terms.iter().cloned())
+                .collect();
+            similarity::weight_for_terms(searcher, &terms)?
+        } else {
+            similarity::disabled_weight()
+        };
+        Ok(Box::new(PhraseWeight::with_alternatives(
+            phrase_terms,
+            similarity_weight,
+            scoring_enabled,
+            self.slop,
+        )))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for (_, query_term) in &self.phrase_terms {
+            term_set.insert(query_term.clone());
+        }
+    }
+}
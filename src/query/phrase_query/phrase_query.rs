@@ -1,7 +1,7 @@
 use super::PhraseWeight;
 use core::searcher::Searcher;
 use error::TantivyError;
-use query::bm25::BM25Weight;
+use query::similarity;
 use query::Query;
 use query::Weight;
 use schema::IndexRecordOption;
@@ -9,6 +9,26 @@ use schema::{Field, Term};
 use std::collections::BTreeSet;
 use Result;
 
+/// Returns an error if the positions of `field` are not indexed,
+/// as they are required to match phrases.
+pub(crate) fn check_has_positions(searcher: &Searcher, field: Field) -> Result<()> {
+    let schema = searcher.schema();
+    let field_entry = schema.get_field_entry(field);
+    let has_positions = field_entry
+        .field_type()
+        .get_index_record_option()
+        .map(IndexRecordOption::has_positions)
+        .unwrap_or(false);
+    if has_positions {
+        Ok(())
+    } else {
+        Err(TantivyError::SchemaError(format!(
+            "Applied phrase query on field {:?}, which does not have positions indexed",
+            field_entry.name()
+        )))
+    }
+}
+
 /// `PhraseQuery` matches a specific sequence of words.
 ///
 /// For instance the phrase query for `"part time"` will match
@@ -23,10 +43,18 @@ use Result;
 /// Using a `PhraseQuery` on a field requires positions
 /// to be indexed for this field.
 ///
+/// By default, the terms must appear exactly at the expected positions.
+/// Setting a slop with [`.set_slop(...)`](#method.set_slop) makes the
+/// phrase also match when its terms are at most `slop` positions away from their
+/// expected position, possibly out of order. For instance, with a slop of `1`,
+/// `"part job"` matches **part time job**.
+/// Such sloppy matches are scored lower as they get looser.
+///
 #[derive(Clone, Debug)]
 pub struct PhraseQuery {
     field: Field,
     phrase_terms: Vec<(usize, Term)>,
+    slop: u32,
 }
 
 impl PhraseQuery {
@@ -57,9 +85,23 @@ impl PhraseQuery {
         PhraseQuery {
             field,
             phrase_terms: terms,
+            slop: 0,
         }
     }
 
+    /// Sets the slop of the phrase query.
+    ///
+    /// The slop is the number of positions the terms may be moved by
+    /// for the phrase to match. Swapping two consecutive terms costs `2`.
+    pub fn set_slop(&mut self, value: u32) {
+        self.slop = value;
+    }
+
+    /// Returns the slop of the phrase query.
+    pub fn slop(&self) -> u32 {
+        self.slop
+    }
+
     /// The `Field` this `PhraseQuery` is targeting.
     pub fn field(&self) -> Field {
         self.field
@@ -72,6 +114,11 @@ impl PhraseQuery {
             .map(|(_, term)| term.clone())
             .collect::<Vec<Term>>()
     }
+
+    /// `Term`s in the phrase with their offsets, sorted by offset.
+    pub fn phrase_terms_with_offsets(&self) -> &[(usize, Term)] {
+        &self.phrase_terms[..]
+    }
 }
 
 impl Query for PhraseQuery {
@@ -79,33 +126,22 @@ impl Query for PhraseQuery {
     ///
     /// See [`Weight`](./trait.Weight.html).
     fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
-        let schema = searcher.schema();
-        let field_entry = schema.get_field_entry(self.field);
-        let has_positions = field_entry
-            .field_type()
-            .get_index_record_option()
-            .map(IndexRecordOption::has_positions)
-            .unwrap_or(false);
-        if !has_positions {
-            let field_name = field_entry.name();
-            return Err(TantivyError::SchemaError(format!(
-                "Applied phrase query on field {:?}, which does not have positions indexed",
-                field_name
-            )));
-        }
+        check_has_positions(searcher, self.field)?;
         if scoring_enabled {
             let terms = self.phrase_terms();
-            let bm25_weight = BM25Weight::for_terms(searcher, &terms);
+            let similarity_weight = similarity::weight_for_terms(searcher, &terms)?;
             Ok(Box::new(PhraseWeight::new(
                 self.phrase_terms.clone(),
-                bm25_weight,
+                similarity_weight,
                 true,
+                self.slop,
             )))
         } else {
             Ok(Box::new(PhraseWeight::new(
                 self.phrase_terms.clone(),
-                BM25Weight::null(),
+                similarity::disabled_weight(),
                 false,
+                self.slop,
             )))
         }
     }
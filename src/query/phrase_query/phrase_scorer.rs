@@ -1,8 +1,9 @@
 use docset::{DocSet, SkipResult};
 use fieldnorm::FieldNormReader;
 use postings::Postings;
-use query::bm25::BM25Weight;
-use query::{Intersection, Scorer};
+use query::SimilarityWeight;
+use query::{Explanation, Intersection, Scorer};
+use std::sync::Arc;
 use DocId;
 
 struct PostingsWithOffset<TPostings> {
@@ -46,9 +47,13 @@ pub struct PhraseScorer<TPostings: Postings> {
     num_terms: usize,
     left: Vec<u32>,
     right: Vec<u32>,
-    phrase_count: u32,
+    slop: u32,
+    term_positions: Vec<Vec<u32>>,
+    term_offsets: Vec<u32>,
+    cursors: Vec<usize>,
+    phrase_freq: f32,
     fieldnorm_reader: FieldNormReader,
-    similarity_weight: BM25Weight,
+    similarity_weight: Arc<SimilarityWeight>,
     score_needed: bool,
 }
 
@@ -117,12 +122,80 @@ fn intersection(left: &mut [u32], right: &[u32]) -> usize {
     count
 }
 
+/// Returns true iff the positions currently pointed at by the cursors
+/// all correspond to different tokens of the document.
+///
+/// This prevents a phrase with a repeated term, like `"b b"`, from matching a single `b`.
+fn distinct_positions(positions: &[Vec<u32>], offsets: &[u32], cursors: &[usize]) -> bool {
+    for i in 1..cursors.len() {
+        let pos_i = positions[i][cursors[i]] - offsets[i];
+        for j in 0..i {
+            if positions[j][cursors[j]] - offsets[j] == pos_i {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Computes the sloppy frequency of a phrase within a document.
+///
+/// `positions[i]` holds the sorted positions of the i-th term, shifted by `offsets[i]`
+/// so that an exact phrase match yields the same value for every term.
+///
+/// The positions are swept by always advancing the term with the lowest position.
+/// Every window where all terms are at most `slop` positions away from where the phrase
+/// expects them counts as a match. A match spanning `span` positions contributes
+/// `1 / (1 + span)` to the frequency, so that exact matches count as `1`,
+/// and looser matches, including out of order ones, count for less.
+fn sloppy_phrase_freq(
+    positions: &[Vec<u32>],
+    offsets: &[u32],
+    cursors: &mut Vec<usize>,
+    slop: u32,
+    stop_at_first_match: bool,
+) -> f32 {
+    cursors.clear();
+    cursors.resize(positions.len(), 0);
+    if positions.iter().any(Vec::is_empty) {
+        return 0f32;
+    }
+    let mut freq = 0f32;
+    loop {
+        let (min_term_ord, min_pos) = positions
+            .iter()
+            .zip(cursors.iter())
+            .map(|(term_positions, &cursor)| term_positions[cursor])
+            .enumerate()
+            .min_by_key(|&(_, pos)| pos)
+            .unwrap(); //< positions is not empty.
+        let max_pos = positions
+            .iter()
+            .zip(cursors.iter())
+            .map(|(term_positions, &cursor)| term_positions[cursor])
+            .max()
+            .unwrap();
+        let span = max_pos - min_pos;
+        if span <= slop && distinct_positions(positions, offsets, &cursors[..]) {
+            freq += 1f32 / (1 + span) as f32;
+            if stop_at_first_match {
+                return freq;
+            }
+        }
+        cursors[min_term_ord] += 1;
+        if cursors[min_term_ord] == positions[min_term_ord].len() {
+            return freq;
+        }
+    }
+}
+
 impl<TPostings: Postings> PhraseScorer<TPostings> {
     pub fn new(
         term_postings: Vec<(usize, TPostings)>,
-        similarity_weight: BM25Weight,
+        similarity_weight: Arc<SimilarityWeight>,
         fieldnorm_reader: FieldNormReader,
         score_needed: bool,
+        slop: u32,
     ) -> PhraseScorer<TPostings> {
         let max_offset = term_postings
             .iter()
@@ -136,28 +209,62 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
                 PostingsWithOffset::new(postings, (max_offset - offset) as u32)
             })
             .collect::<Vec<_>>();
+        let mut intersection_docset = Intersection::new(postings_with_offsets);
+        let term_offsets = (0..num_docsets)
+            .map(|ord| intersection_docset.docset_mut_specialized(ord).offset)
+            .collect();
         PhraseScorer {
-            intersection_docset: Intersection::new(postings_with_offsets),
+            intersection_docset,
             num_terms: num_docsets,
             left: Vec::with_capacity(100),
             right: Vec::with_capacity(100),
-            phrase_count: 0u32,
+            slop,
+            term_positions: vec![Vec::new(); num_docsets],
+            term_offsets,
+            cursors: Vec::with_capacity(num_docsets),
+            phrase_freq: 0f32,
             similarity_weight,
             fieldnorm_reader,
             score_needed,
         }
     }
 
+    /// Explains the score of the current document.
+    pub fn explain(&self) -> Explanation {
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(self.doc());
+        self.similarity_weight
+            .explain(fieldnorm_id, self.phrase_freq)
+    }
+
     fn phrase_match(&mut self) -> bool {
-        if self.score_needed {
+        if self.slop > 0 {
+            let freq = self.sloppy_phrase_freq();
+            self.phrase_freq = freq;
+            freq > 0f32
+        } else if self.score_needed {
             let count = self.phrase_count();
-            self.phrase_count = count;
+            self.phrase_freq = count as f32;
             count > 0u32
         } else {
             self.phrase_exists()
         }
     }
 
+    fn sloppy_phrase_freq(&mut self) -> f32 {
+        for (ord, term_positions) in self.term_positions.iter_mut().enumerate() {
+            self.intersection_docset
+                .docset_mut_specialized(ord)
+                .positions(term_positions);
+        }
+        sloppy_phrase_freq(
+            &self.term_positions[..],
+            &self.term_offsets[..],
+            &mut self.cursors,
+            self.slop,
+            !self.score_needed,
+        )
+    }
+
     fn phrase_exists(&mut self) -> bool {
         {
             self.intersection_docset
@@ -251,14 +358,40 @@ impl<TPostings: Postings> Scorer for PhraseScorer<TPostings> {
         let doc = self.doc();
         let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(doc);
         self.similarity_weight
-            .score(fieldnorm_id, self.phrase_count)
+            .score_with_freq(fieldnorm_id, self.phrase_freq)
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use super::{intersection, intersection_count};
+    use super::{intersection, intersection_count, sloppy_phrase_freq};
+    use tests::assert_nearly_equals;
+
+    fn test_sloppy_freq(positions: &[&[u32]], offsets: &[u32], slop: u32) -> f32 {
+        let positions: Vec<Vec<u32>> = positions.iter().map(|pos| pos.to_vec()).collect();
+        sloppy_phrase_freq(&positions[..], offsets, &mut Vec::new(), slop, false)
+    }
+
+    #[test]
+    fn test_sloppy_phrase_freq() {
+        // exact match
+        assert_nearly_equals(test_sloppy_freq(&[&[3], &[3]], &[1, 0], 1), 1f32);
+        // one word in between
+        assert_nearly_equals(test_sloppy_freq(&[&[3], &[4]], &[1, 0], 1), 0.5f32);
+        assert_eq!(test_sloppy_freq(&[&[3], &[5]], &[1, 0], 1), 0f32);
+        // out of order
+        assert_nearly_equals(test_sloppy_freq(&[&[3], &[1]], &[1, 0], 2), 1f32 / 3f32);
+        assert_eq!(test_sloppy_freq(&[&[3], &[1]], &[1, 0], 1), 0f32);
+        // repeated term can't match the same token twice
+        assert_eq!(test_sloppy_freq(&[&[1], &[0]], &[1, 0], 1), 0f32);
+        // several matches
+        assert_nearly_equals(
+            test_sloppy_freq(&[&[1, 10], &[1, 12]], &[1, 0], 2),
+            1f32 + 1f32 / 3f32,
+        );
+        assert_eq!(test_sloppy_freq(&[&[], &[1]], &[1, 0], 2), 0f32);
+    }
 
     fn test_intersection_sym(left: &[u32], right: &[u32], expected: &[u32]) {
         test_intersection_aux(left, right, expected);
@@ -1,75 +1,147 @@
 use super::PhraseScorer;
+use super::UnionPostings;
 use core::SegmentReader;
-use query::bm25::BM25Weight;
+use docset::{DocSet, SkipResult};
+use postings::{Postings, SegmentPostings};
 use query::EmptyScorer;
 use query::Scorer;
+use query::SimilarityWeight;
 use query::Weight;
+use query::{does_not_match, Explanation};
 use schema::IndexRecordOption;
 use schema::Term;
+use std::sync::Arc;
+use DocId;
 use Result;
 
 pub struct PhraseWeight {
-    phrase_terms: Vec<(usize, Term)>,
-    similarity_weight: BM25Weight,
+    phrase_terms: Vec<(usize, Vec<Term>)>,
+    similarity_weight: Arc<SimilarityWeight>,
     score_needed: bool,
+    slop: u32,
 }
 
 impl PhraseWeight {
     /// Creates a new phrase weight.
     pub fn new(
         phrase_terms: Vec<(usize, Term)>,
-        similarity_weight: BM25Weight,
+        similarity_weight: Arc<SimilarityWeight>,
         score_needed: bool,
+        slop: u32,
+    ) -> PhraseWeight {
+        let phrase_terms = phrase_terms
+            .into_iter()
+            .map(|(offset, term)| (offset, vec![term]))
+            .collect();
+        PhraseWeight::with_alternatives(phrase_terms, similarity_weight, score_needed, slop)
+    }
+
+    /// Creates a new phrase weight, matching any of several terms at each of its offsets.
+    pub fn with_alternatives(
+        phrase_terms: Vec<(usize, Vec<Term>)>,
+        similarity_weight: Arc<SimilarityWeight>,
+        score_needed: bool,
+        slop: u32,
     ) -> PhraseWeight {
         PhraseWeight {
             phrase_terms,
             similarity_weight,
             score_needed,
+            slop,
+        }
+    }
+
+    fn term_postings(&self, reader: &SegmentReader, term: &Term) -> Option<SegmentPostings> {
+        let inverted_index = reader.inverted_index(term.field());
+        if reader.has_deletes() {
+            inverted_index.read_postings(term, IndexRecordOption::WithFreqsAndPositions)
+        } else {
+            inverted_index.read_postings_no_deletes(term, IndexRecordOption::WithFreqsAndPositions)
+        }
+    }
+
+    fn new_phrase_scorer<TPostings: Postings>(
+        &self,
+        reader: &SegmentReader,
+        term_postings_list: Vec<(usize, TPostings)>,
+    ) -> PhraseScorer<TPostings> {
+        let field = self.phrase_terms[0].1[0].field();
+        PhraseScorer::new(
+            term_postings_list,
+            self.similarity_weight.clone(),
+            reader.get_fieldnorms_reader(field),
+            self.score_needed,
+            self.slop,
+        )
+    }
+
+    fn phrase_scorer(&self, reader: &SegmentReader) -> Option<PhraseScorer<SegmentPostings>> {
+        let mut term_postings_list = Vec::new();
+        for &(offset, ref terms) in &self.phrase_terms {
+            let postings = self.term_postings(reader, &terms[0])?;
+            term_postings_list.push((offset, postings));
         }
+        Some(self.new_phrase_scorer(reader, term_postings_list))
+    }
+
+    fn union_phrase_scorer(
+        &self,
+        reader: &SegmentReader,
+    ) -> Option<PhraseScorer<UnionPostings<SegmentPostings>>> {
+        let mut term_postings_list = Vec::new();
+        for &(offset, ref terms) in &self.phrase_terms {
+            let postings: Vec<SegmentPostings> = terms
+                .iter()
+                .filter_map(|term| self.term_postings(reader, term))
+                .collect();
+            if postings.is_empty() {
+                return None;
+            }
+            term_postings_list.push((offset, UnionPostings::new(postings)));
+        }
+        Some(self.new_phrase_scorer(reader, term_postings_list))
+    }
+
+    fn has_alternatives(&self) -> bool {
+        self.phrase_terms.iter().any(|(_, terms)| terms.len() > 1)
+    }
+
+    fn explain_scorer<TPostings: Postings>(
+        &self,
+        scorer_opt: Option<PhraseScorer<TPostings>>,
+        doc: DocId,
+    ) -> Result<Explanation> {
+        let mut scorer = scorer_opt.ok_or_else(|| does_not_match(doc))?;
+        if scorer.skip_next(doc) != SkipResult::Reached {
+            return Err(does_not_match(doc));
+        }
+        let similarity_explanation = scorer.explain();
+        let mut explanation = Explanation::new(
+            format!("PhraseQuery(slop={})", self.slop),
+            similarity_explanation.value(),
+        );
+        explanation.add_detail(similarity_explanation);
+        Ok(explanation)
     }
 }
 
 impl Weight for PhraseWeight {
     fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
-        let similarity_weight = self.similarity_weight.clone();
-        let field = self.phrase_terms[0].1.field();
-        let fieldnorm_reader = reader.get_fieldnorms_reader(field);
-        if reader.has_deletes() {
-            let mut term_postings_list = Vec::new();
-            for &(offset, ref term) in &self.phrase_terms {
-                if let Some(postings) = reader
-                    .inverted_index(term.field())
-                    .read_postings(&term, IndexRecordOption::WithFreqsAndPositions)
-                {
-                    term_postings_list.push((offset, postings));
-                } else {
-                    return Ok(Box::new(EmptyScorer));
-                }
+        if self.has_alternatives() {
+            if let Some(scorer) = self.union_phrase_scorer(reader) {
+                return Ok(Box::new(scorer));
             }
-            Ok(Box::new(PhraseScorer::new(
-                term_postings_list,
-                similarity_weight,
-                fieldnorm_reader,
-                self.score_needed,
-            )))
+        } else if let Some(scorer) = self.phrase_scorer(reader) {
+            return Ok(Box::new(scorer));
+        }
+        Ok(Box::new(EmptyScorer))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        if self.has_alternatives() {
+            self.explain_scorer(self.union_phrase_scorer(reader), doc)
         } else {
-            let mut term_postings_list = Vec::new();
-            for &(offset, ref term) in &self.phrase_terms {
-                if let Some(postings) = reader
-                    .inverted_index(term.field())
-                    .read_postings_no_deletes(&term, IndexRecordOption::WithFreqsAndPositions)
-                {
-                    term_postings_list.push((offset, postings));
-                } else {
-                    return Ok(Box::new(EmptyScorer));
-                }
-            }
-            Ok(Box::new(PhraseScorer::new(
-                term_postings_list,
-                similarity_weight,
-                fieldnorm_reader,
-                self.score_needed,
-            )))
+            self.explain_scorer(self.phrase_scorer(reader), doc)
         }
     }
 }
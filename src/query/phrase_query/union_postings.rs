@@ -0,0 +1,163 @@
+use docset::{DocSet, SkipResult};
+use postings::Postings;
+use DocId;
+
+/// Postings of the union of several terms, as if they were a single term.
+///
+/// The positions of the document are the merged positions of the terms it contains.
+pub struct UnionPostings<TPostings: Postings> {
+    postings: Vec<TPostings>,
+    started: bool,
+    doc: DocId,
+    buffer: Vec<u32>,
+}
+
+impl<TPostings: Postings> UnionPostings<TPostings> {
+    /// Creates the union of the postings of several terms.
+    pub fn new(postings: Vec<TPostings>) -> UnionPostings<TPostings> {
+        UnionPostings {
+            postings,
+            started: false,
+            doc: 0,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Advances all of the postings a first time, removing the empty ones.
+    fn start(&mut self) {
+        self.started = true;
+        let mut ord = 0;
+        while ord < self.postings.len() {
+            if self.postings[ord].advance() {
+                ord += 1;
+            } else {
+                self.postings.swap_remove(ord);
+            }
+        }
+    }
+
+    /// Positions the union on the smallest document of its postings.
+    fn update_doc(&mut self) -> bool {
+        if let Some(doc) = self.postings.iter().map(DocSet::doc).min() {
+            self.doc = doc;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<TPostings: Postings> DocSet for UnionPostings<TPostings> {
+    fn advance(&mut self) -> bool {
+        if !self.started {
+            self.start();
+            return self.update_doc();
+        }
+        let doc = self.doc;
+        let mut ord = 0;
+        while ord < self.postings.len() {
+            if self.postings[ord].doc() != doc || self.postings[ord].advance() {
+                ord += 1;
+            } else {
+                self.postings.swap_remove(ord);
+            }
+        }
+        self.update_doc()
+    }
+
+    fn skip_next(&mut self, target: DocId) -> SkipResult {
+        if !self.started {
+            self.start();
+        } else if self.doc >= target && !self.advance() {
+            return SkipResult::End;
+        }
+        let mut ord = 0;
+        while ord < self.postings.len() {
+            if self.postings[ord].doc() >= target
+                || self.postings[ord].skip_next(target) != SkipResult::End
+            {
+                ord += 1;
+            } else {
+                self.postings.swap_remove(ord);
+            }
+        }
+        if !self.update_doc() {
+            SkipResult::End
+        } else if self.doc == target {
+            SkipResult::Reached
+        } else {
+            SkipResult::OverStep
+        }
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings.iter().map(DocSet::size_hint).sum()
+    }
+}
+
+impl<TPostings: Postings> Postings for UnionPostings<TPostings> {
+    fn term_freq(&self) -> u32 {
+        self.postings
+            .iter()
+            .filter(|postings| postings.doc() == self.doc)
+            .map(Postings::term_freq)
+            .sum()
+    }
+
+    fn positions_with_offset(&mut self, offset: u32, output: &mut Vec<u32>) {
+        output.clear();
+        for postings in &mut self.postings {
+            if postings.doc() == self.doc {
+                postings.positions_with_offset(offset, &mut self.buffer);
+                output.extend_from_slice(&self.buffer[..]);
+            }
+        }
+        output.sort();
+        output.dedup();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::UnionPostings;
+    use docset::{DocSet, SkipResult};
+    use postings::tests::test_skip_against_unoptimized;
+    use postings::Postings;
+    use postings::SegmentPostings;
+
+    fn union_postings(docs: &[&[u32]]) -> UnionPostings<SegmentPostings> {
+        UnionPostings::new(
+            docs.iter()
+                .map(|docs| SegmentPostings::create_from_docs(docs))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_union_postings() {
+        let mut union = union_postings(&[&[1, 3, 5], &[2, 3, 7], &[]]);
+        let mut docs = Vec::new();
+        while union.advance() {
+            docs.push((union.doc(), union.term_freq()));
+        }
+        assert_eq!(docs, vec![(1, 1), (2, 1), (3, 2), (5, 1), (7, 1)]);
+
+        let mut union = union_postings(&[&[1, 3, 5], &[2, 3, 7]]);
+        assert_eq!(union.skip_next(3), SkipResult::Reached);
+        assert_eq!(union.skip_next(3), SkipResult::OverStep);
+        assert_eq!(union.doc(), 5);
+        assert_eq!(union.skip_next(6), SkipResult::OverStep);
+        assert_eq!(union.doc(), 7);
+        assert_eq!(union.skip_next(8), SkipResult::End);
+
+        test_skip_against_unoptimized(
+            || Box::new(union_postings(&[&[1, 3, 5, 100], &[2, 3, 7, 50]])),
+            vec![0, 2, 3, 4, 6, 7, 51, 100, 101],
+        );
+    }
+}
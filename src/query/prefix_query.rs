@@ -0,0 +1,183 @@
+use query::{AutomatonWeight, Query, Weight};
+use schema::Term;
+use tantivy_fst::Automaton;
+use Result;
+use Searcher;
+
+/// Automaton matching all of the byte strings starting
+/// with a given prefix.
+#[derive(Clone, Debug)]
+pub struct PrefixAutomaton {
+    prefix: Vec<u8>,
+}
+
+impl PrefixAutomaton {
+    /// Creates a new `PrefixAutomaton`.
+    pub fn new(prefix: &[u8]) -> PrefixAutomaton {
+        PrefixAutomaton {
+            prefix: prefix.to_vec(),
+        }
+    }
+}
+
+impl Automaton for PrefixAutomaton {
+    /// Number of bytes of the prefix matched so far,
+    /// or `None` if the input diverged from the prefix.
+    type State = Option<usize>;
+
+    fn start(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn is_match(&self, state: &Option<usize>) -> bool {
+        *state == Some(self.prefix.len())
+    }
+
+    fn can_match(&self, state: &Option<usize>) -> bool {
+        state.is_some()
+    }
+
+    fn will_always_match(&self, state: &Option<usize>) -> bool {
+        self.is_match(state)
+    }
+
+    fn accept(&self, state: &Option<usize>, byte: u8) -> Option<usize> {
+        match *state {
+            Some(len) if len == self.prefix.len() => Some(len),
+            Some(len) if self.prefix[len] == byte => Some(len + 1),
+            _ => None,
+        }
+    }
+}
+
+/// A Prefix Query matches all of the documents
+/// containing a term that starts with a given prefix.
+///
+/// All of the matching documents get the score `1`.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{Index, Result, Term};
+/// use tantivy::collector::Count;
+/// use tantivy::query::PrefixQuery;
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = Schema::builder();
+///     let title = schema_builder.add_text_field("title", TEXT);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(
+///             title => "The Name of the Wind",
+///         ));
+///         index_writer.add_document(doc!(
+///             title => "The Diary of Muadib",
+///         ));
+///         index_writer.add_document(doc!(
+///             title => "A Dairy Cow",
+///         ));
+///         index_writer.commit().unwrap();
+///     }
+///
+///     let reader = index.reader()?;
+///     let searcher = reader.searcher();
+///
+///     let query = PrefixQuery::new(Term::from_field_text(title, "di"));
+///     let count = searcher.search(&query, &Count)?;
+///     assert_eq!(count, 1);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrefixQuery {
+    prefix: Term,
+    max_expansions: Option<usize>,
+}
+
+impl PrefixQuery {
+    /// Creates a new Prefix Query
+    pub fn new(prefix: Term) -> PrefixQuery {
+        PrefixQuery {
+            prefix,
+            max_expansions: None,
+        }
+    }
+
+    /// The prefix searched by this query.
+    pub fn prefix(&self) -> &Term {
+        &self.prefix
+    }
+
+    /// Limits the number of terms the prefix may expand to in each segment.
+    ///
+    /// Only the documents containing one of the first `max_expansions`
+    /// matching terms, in lexicographic order, are matched.
+    pub fn set_max_expansions(&mut self, max_expansions: usize) {
+        self.max_expansions = Some(max_expansions);
+    }
+
+    /// The maximum number of terms this query may expand to, if limited.
+    pub fn max_expansions(&self) -> Option<usize> {
+        self.max_expansions
+    }
+
+    fn specialized_weight(&self) -> AutomatonWeight<PrefixAutomaton> {
+        let automaton = PrefixAutomaton::new(self.prefix.value_bytes());
+        let mut weight = AutomatonWeight::new(self.prefix.field(), automaton);
+        if let Some(max_expansions) = self.max_expansions {
+            weight.set_max_expansions(max_expansions);
+        }
+        weight
+    }
+}
+
+impl Query for PrefixQuery {
+    fn weight(&self, _searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(self.specialized_weight()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PrefixQuery;
+    use collector::Count;
+    use schema::Schema;
+    use schema::TEXT;
+    use Index;
+    use Term;
+
+    #[test]
+    pub fn test_prefix_query() {
+        let mut schema_builder = Schema::builder();
+        let country_field = schema_builder.add_text_field("country", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 10_000_000).unwrap();
+            index_writer.add_document(doc!(country_field => "japan"));
+            index_writer.add_document(doc!(country_field => "jamaica"));
+            index_writer.add_document(doc!(country_field => "korea"));
+            index_writer.add_document(doc!(country_field => "ja"));
+            index_writer.commit().unwrap();
+        }
+        let reader = index.reader().unwrap();
+        let searcher = reader.searcher();
+        let count = |prefix: &str, max_expansions: Option<usize>| {
+            let mut query = PrefixQuery::new(Term::from_field_text(country_field, prefix));
+            if let Some(max_expansions) = max_expansions {
+                query.set_max_expansions(max_expansions);
+            }
+            searcher.search(&query, &Count).unwrap()
+        };
+        assert_eq!(count("ja", None), 3);
+        assert_eq!(count("jap", None), 1);
+        assert_eq!(count("japan", None), 1);
+        assert_eq!(count("japans", None), 0);
+        assert_eq!(count("", None), 4);
+        assert_eq!(count("ja", Some(2)), 2);
+    }
+}
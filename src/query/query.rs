@@ -1,6 +1,7 @@
 use super::Weight;
 use core::searcher::Searcher;
 use downcast_rs;
+use query::QueryCacheKey;
 use std::collections::BTreeSet;
 use std::fmt;
 use Result;
@@ -61,6 +62,16 @@ pub trait Query: QueryClone + downcast_rs::Downcast + fmt::Debug {
     /// Extract all of the terms associated to the query and insert them in the
     /// term set given in arguments.
     fn query_terms(&self, _term_set: &mut BTreeSet<Term>) {}
+
+    /// Returns the key identifying the documents matched by the query in the
+    /// [`FilterCache`](./struct.FilterCache.html) of the searcher, if they are worth
+    /// caching when the query is used as a filter.
+    ///
+    /// The `Filter` and `MustNot` clauses of a `BooleanQuery` having a cache key
+    /// are evaluated through the `FilterCache`.
+    fn cache_key(&self) -> Option<QueryCacheKey> {
+        None
+    }
 }
 
 pub trait QueryClone {
@@ -88,6 +99,10 @@ impl Query for Box<Query> {
     fn query_terms(&self, term_set: &mut BTreeSet<Term<Vec<u8>>>) {
         self.as_ref().query_terms(term_set);
     }
+
+    fn cache_key(&self) -> Option<QueryCacheKey> {
+        self.as_ref().cache_key()
+    }
 }
 
 impl QueryClone for Box<Query> {
@@ -0,0 +1,1597 @@
+use chrono::{self, TimeZone};
+use error::TantivyError;
+use query::more_like_this_query::{
+    DEFAULT_MAX_QUERY_TERMS, DEFAULT_MIN_DOC_FREQ, DEFAULT_MIN_TERM_FREQ,
+};
+use query::phrase_query::DEFAULT_MAX_EXPANSIONS;
+use query::vector_query::DEFAULT_NUM_CANDIDATES;
+use query::{
+    AllQuery, BooleanQuery, BoostQuery, CachedQuery, ConstScoreQuery, Decay, DisjunctionMaxQuery,
+    EmptyQuery, ExistsQuery, FunctionScoreQuery, FuzzyTermQuery, MoreLikeThisQuery,
+    MoreLikeThisSource, MultiPhraseQuery, Occur, PhrasePrefixQuery, PhraseQuery, PrefixQuery,
+    Query, RangeQuery, RegexQuery, ScoreFunction, SpanQuery, TermQuery, TermSetQuery, VectorQuery,
+    WildcardQuery,
+};
+use schema::{Facet, Field, FieldType, IndexRecordOption, Schema, Term};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use vector::VectorSimilarity;
+use DocAddress;
+use Result;
+
+fn default_index_record_option() -> IndexRecordOption {
+    IndexRecordOption::WithFreqs
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_expansions() -> usize {
+    DEFAULT_MAX_EXPANSIONS
+}
+
+fn default_min_term_freq() -> usize {
+    DEFAULT_MIN_TERM_FREQ
+}
+
+fn default_min_doc_freq() -> u64 {
+    DEFAULT_MIN_DOC_FREQ
+}
+
+fn default_max_query_terms() -> usize {
+    DEFAULT_MAX_QUERY_TERMS
+}
+
+fn default_num_candidates() -> usize {
+    DEFAULT_NUM_CANDIDATES
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+/// Serializable description of a query.
+///
+/// A `QueryDescription` can be built from, and turned into, any of the queries of
+/// `tantivy::query`. Fields are referred to by their name and values are JSON
+/// values, so that the description of a query can be serialized, e.g. as JSON,
+/// to be logged, stored or sent over the network, and later rebuilt against a `Schema`.
+///
+/// Values are interpreted according to the type of their field: strings for text
+/// fields, integers for `u64` and `i64` fields, RFC 3339 strings for date fields and
+/// paths like `"/category/rust"` for facet fields.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// extern crate serde_json;
+/// use tantivy::collector::Count;
+/// use tantivy::query::QueryDescription;
+/// use tantivy::schema::{Schema, INDEXED, TEXT};
+/// use tantivy::{Index, Result};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = Schema::builder();
+///     let title = schema_builder.add_text_field("title", TEXT);
+///     let year = schema_builder.add_u64_field("year", INDEXED);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema.clone());
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(title => "The Name of the Wind", year => 2007u64));
+///         index_writer.add_document(doc!(title => "The Wise Man's Fear", year => 2011u64));
+///         index_writer.commit()?;
+///     }
+///     let searcher = index.reader()?.searcher();
+///
+///     let description: QueryDescription = serde_json::from_str(r#"{
+///         "boolean": {
+///             "clauses": [
+///                 ["must", {"term": {"field": "title", "value": "the"}}],
+///                 ["must_not", {"range": {"field": "year", "gte": 2010}}]
+///             ]
+///         }
+///     }"#).unwrap();
+///     let query = description.to_query(&schema)?;
+///     assert_eq!(searcher.search(&query, &Count)?, 1);
+///
+///     let json = serde_json::to_string(&QueryDescription::from_query(query.as_ref(), &schema)?)
+///         .unwrap();
+///     assert_eq!(serde_json::from_str::<QueryDescription>(&json).unwrap(), description);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryDescription {
+    /// Matches all documents, see `AllQuery`.
+    All,
+    /// Matches no document, see `EmptyQuery`.
+    Empty,
+    /// Matches the documents containing a term, see `TermQuery`.
+    Term {
+        /// Name of the field searched.
+        field: String,
+        /// Value of the term.
+        value: JsonValue,
+        /// Postings information used for scoring. Defaults to `freq`.
+        #[serde(default = "default_index_record_option")]
+        index_record_option: IndexRecordOption,
+    },
+    /// Matches the documents containing a phrase, see `PhraseQuery`.
+    Phrase {
+        /// Name of the text field searched.
+        field: String,
+        /// Terms of the phrase.
+        terms: Vec<String>,
+        /// Offsets of the terms in the phrase. Defaults to `0, 1, 2...`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        offsets: Option<Vec<usize>>,
+        /// Slop of the phrase. Defaults to `0`.
+        #[serde(default)]
+        slop: u32,
+    },
+    /// Matches the documents containing a term within a range, see `RangeQuery`.
+    ///
+    /// Bounds that are not given are unbounded.
+    Range {
+        /// Name of the field searched.
+        field: String,
+        /// Exclusive lower bound.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gt: Option<JsonValue>,
+        /// Inclusive lower bound.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gte: Option<JsonValue>,
+        /// Exclusive upper bound.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        lt: Option<JsonValue>,
+        /// Inclusive upper bound.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        lte: Option<JsonValue>,
+    },
+    /// Combination of queries, see `BooleanQuery`.
+    Boolean {
+        /// Subqueries, with their occurrence requirement.
+        clauses: Vec<(Occur, QueryDescription)>,
+        /// Minimum number of `Should` clauses to match. Defaults to `0`.
+        #[serde(default, skip_serializing_if = "is_zero")]
+        minimum_should_match: usize,
+    },
+    /// Multiplies the score of a query, see `BoostQuery`.
+    Boost {
+        /// Query boosted.
+        query: Box<QueryDescription>,
+        /// Boost factor.
+        boost: f32,
+    },
+    /// Matches the documents containing a term within a Levenshtein distance,
+    /// see `FuzzyTermQuery`.
+    Fuzzy {
+        /// Name of the text field searched.
+        field: String,
+        /// Text of the term.
+        value: String,
+        /// Levenshtein distance.
+        distance: u8,
+        /// Whether a transposition costs `1` or `2`. Defaults to `true`.
+        #[serde(default = "default_true")]
+        transposition_cost_one: bool,
+        /// Whether the term is matched against prefixes. Defaults to `false`.
+        #[serde(default)]
+        prefix: bool,
+    },
+    /// Matches the documents containing a term matching a regular expression,
+    /// see `RegexQuery`.
+    Regex {
+        /// Name of the text field searched.
+        field: String,
+        /// Regular expression.
+        pattern: String,
+    },
+    /// Matches the documents containing a term starting with a prefix, see `PrefixQuery`.
+    Prefix {
+        /// Name of the text field searched.
+        field: String,
+        /// Prefix of the terms.
+        value: String,
+        /// Maximum number of terms the prefix may expand to.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_expansions: Option<usize>,
+    },
+    /// Matches the documents containing a term matching a wildcard pattern,
+    /// see `WildcardQuery`.
+    Wildcard {
+        /// Name of the text field searched.
+        field: String,
+        /// Wildcard pattern.
+        pattern: String,
+        /// Maximum number of terms the pattern may expand to.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_expansions: Option<usize>,
+    },
+    /// Matches the documents containing any of a set of terms, see `TermSetQuery`.
+    TermSet {
+        /// Values of the terms, by field name.
+        terms: BTreeMap<String, Vec<JsonValue>>,
+    },
+    /// Matches the documents matching any of the subqueries, scored with the
+    /// maximum of their scores, see `DisjunctionMaxQuery`.
+    DisjunctionMax {
+        /// Subqueries.
+        disjuncts: Vec<QueryDescription>,
+        /// Factor applied to the scores of the other matching subqueries. Defaults to `0`.
+        #[serde(default)]
+        tie_breaker: f32,
+    },
+    /// Gives the same score to all of the documents matching a query, see `ConstScoreQuery`.
+    ConstScore {
+        /// Query matching the documents.
+        query: Box<QueryDescription>,
+        /// Score of the documents.
+        score: f32,
+    },
+    /// Multiplies the score of a query by factors computed from fast fields,
+    /// see `FunctionScoreQuery`.
+    FunctionScore {
+        /// Query scored.
+        query: Box<QueryDescription>,
+        /// Functions computing the factors.
+        functions: Vec<ScoreFunctionDescription>,
+    },
+    /// Matches the documents according to the positions of their terms, see `SpanQuery`.
+    Span(SpanQueryDescription),
+    /// Matches the documents containing a phrase, accepting several terms at each
+    /// of its positions, see `MultiPhraseQuery`.
+    MultiPhrase {
+        /// Name of the text field searched.
+        field: String,
+        /// Terms accepted at each position of the phrase.
+        terms: Vec<Vec<String>>,
+        /// Offsets of the positions in the phrase. Defaults to `0, 1, 2...`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        offsets: Option<Vec<usize>>,
+        /// Slop of the phrase. Defaults to `0`.
+        #[serde(default)]
+        slop: u32,
+    },
+    /// Matches the documents containing a phrase ending with a prefix,
+    /// see `PhrasePrefixQuery`.
+    PhrasePrefix {
+        /// Name of the text field searched.
+        field: String,
+        /// Terms of the phrase, the last one being the prefix.
+        terms: Vec<String>,
+        /// Offsets of the terms in the phrase. Defaults to `0, 1, 2...`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        offsets: Option<Vec<usize>>,
+        /// Slop of the phrase. Defaults to `0`.
+        #[serde(default)]
+        slop: u32,
+        /// Maximum number of terms the prefix may expand to. Defaults to `50`.
+        #[serde(default = "default_max_expansions")]
+        max_expansions: usize,
+    },
+    /// Matches the documents having a value for a field, see `ExistsQuery`.
+    Exists {
+        /// Name of the field.
+        field: String,
+    },
+    /// Matches the documents similar to a document or to a text, see `MoreLikeThisQuery`.
+    MoreLikeThis {
+        /// Names of the text fields the terms are taken from.
+        fields: Vec<String>,
+        /// Text the documents are similar to, if no `document` is given.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        text: Option<String>,
+        /// Segment ordinal and id of the document the documents are similar to,
+        /// if no `text` is given.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        document: Option<(u32, u32)>,
+        /// Minimum number of occurrences of a term in the source. Defaults to `2`.
+        #[serde(default = "default_min_term_freq")]
+        min_term_freq: usize,
+        /// Minimum number of documents a term must appear in. Defaults to `5`.
+        #[serde(default = "default_min_doc_freq")]
+        min_doc_freq: u64,
+        /// Maximum number of documents a term may appear in.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_doc_freq: Option<u64>,
+        /// Maximum number of terms searched. Defaults to `25`.
+        #[serde(default = "default_max_query_terms")]
+        max_query_terms: usize,
+    },
+    /// Caches the documents matching a query in the filter cache, see `CachedQuery`.
+    Cached {
+        /// Query matching the documents.
+        query: Box<QueryDescription>,
+        /// Key the documents are cached under.
+        key: String,
+    },
+    /// Matches the `k` documents of each segment whose vectors are the most
+    /// similar to a vector, see `VectorQuery`.
+    Vector {
+        /// Name of the vector field.
+        field: String,
+        /// Vector whose nearest neighbours are searched.
+        vector: Vec<f32>,
+        /// Number of nearest documents matched in each segment.
+        k: usize,
+        /// Similarity used to rank the documents. Defaults to `cosine`.
+        #[serde(default)]
+        similarity: VectorSimilarity,
+        /// Number of candidates collected in each segment. Defaults to `100`.
+        #[serde(default = "default_num_candidates")]
+        num_candidates: usize,
+        /// Query the search is restricted to.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filter: Option<Box<QueryDescription>>,
+    },
+}
+
+impl QueryDescription {
+    /// Builds the query described, resolving its field names and values with the schema.
+    pub fn to_query(&self, schema: &Schema) -> Result<Box<Query>> {
+        match *self {
+            QueryDescription::All => Ok(Box::new(AllQuery)),
+            QueryDescription::Empty => Ok(Box::new(EmptyQuery)),
+            QueryDescription::Term {
+                ref field,
+                ref value,
+                index_record_option,
+            } => {
+                let term = term_from_json(schema, get_field(schema, field)?, value)?;
+                Ok(Box::new(TermQuery::new(term, index_record_option)))
+            }
+            QueryDescription::Phrase {
+                ref field,
+                ref terms,
+                ref offsets,
+                slop,
+            } => {
+                let field = get_text_field(schema, field)?;
+                if terms.len() < 2 {
+                    return Err(TantivyError::InvalidArgument(
+                        "A phrase requires at least two terms".to_string(),
+                    ));
+                }
+                let terms_with_offsets = phrase_offsets(offsets, terms.len())?
+                    .into_iter()
+                    .zip(terms.iter())
+                    .map(|(offset, text)| (offset, Term::from_field_text(field, text)))
+                    .collect();
+                let mut phrase_query = PhraseQuery::new_with_offset(terms_with_offsets);
+                phrase_query.set_slop(slop);
+                Ok(Box::new(phrase_query))
+            }
+            QueryDescription::Range {
+                ref field,
+                ref gt,
+                ref gte,
+                ref lt,
+                ref lte,
+            } => {
+                let field = get_field(schema, field)?;
+                let value_type = schema.get_field_entry(field).field_type().value_type();
+                let lower = bound_from_json(schema, field, gt, gte)?;
+                let upper = bound_from_json(schema, field, lt, lte)?;
+                Ok(Box::new(RangeQuery::new_term_bounds(
+                    field, value_type, &lower, &upper,
+                )))
+            }
+            QueryDescription::Boolean {
+                ref clauses,
+                minimum_should_match,
+            } => {
+                let subqueries = clauses
+                    .iter()
+                    .map(|&(occur, ref description)| Ok((occur, description.to_query(schema)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                let mut boolean_query = BooleanQuery::from(subqueries);
+                boolean_query.set_minimum_should_match(minimum_should_match);
+                Ok(Box::new(boolean_query))
+            }
+            QueryDescription::Boost { ref query, boost } => {
+                Ok(Box::new(BoostQuery::new(query.to_query(schema)?, boost)))
+            }
+            QueryDescription::Fuzzy {
+                ref field,
+                ref value,
+                distance,
+                transposition_cost_one,
+                prefix,
+            } => {
+                let term = Term::from_field_text(get_text_field(schema, field)?, value);
+                if prefix {
+                    Ok(Box::new(FuzzyTermQuery::new_prefix(
+                        term,
+                        distance,
+                        transposition_cost_one,
+                    )))
+                } else {
+                    Ok(Box::new(FuzzyTermQuery::new(
+                        term,
+                        distance,
+                        transposition_cost_one,
+                    )))
+                }
+            }
+            QueryDescription::Regex {
+                ref field,
+                ref pattern,
+            } => {
+                let field = get_text_field(schema, field)?;
+                Ok(Box::new(RegexQuery::new(pattern.clone(), field)))
+            }
+            QueryDescription::Prefix {
+                ref field,
+                ref value,
+                max_expansions,
+            } => {
+                let prefix = Term::from_field_text(get_text_field(schema, field)?, value);
+                let mut prefix_query = PrefixQuery::new(prefix);
+                if let Some(max_expansions) = max_expansions {
+                    prefix_query.set_max_expansions(max_expansions);
+                }
+                Ok(Box::new(prefix_query))
+            }
+            QueryDescription::Wildcard {
+                ref field,
+                ref pattern,
+                max_expansions,
+            } => {
+                let field = get_text_field(schema, field)?;
+                let mut wildcard_query = WildcardQuery::new(pattern.clone(), field);
+                if let Some(max_expansions) = max_expansions {
+                    wildcard_query.set_max_expansions(max_expansions);
+                }
+                Ok(Box::new(wildcard_query))
+            }
+            QueryDescription::TermSet { ref terms } => {
+                let mut query_terms = Vec::new();
+                for (field_name, values) in terms {
+                    let field = get_field(schema, field_name)?;
+                    for value in values {
+                        query_terms.push(term_from_json(schema, field, value)?);
+                    }
+                }
+                Ok(Box::new(TermSetQuery::new(query_terms)))
+            }
+            QueryDescription::DisjunctionMax {
+                ref disjuncts,
+                tie_breaker,
+            } => {
+                let disjuncts = disjuncts
+                    .iter()
+                    .map(|description| description.to_query(schema))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Box::new(DisjunctionMaxQuery::with_tie_breaker(
+                    disjuncts,
+                    tie_breaker,
+                )))
+            }
+            QueryDescription::ConstScore { ref query, score } => Ok(Box::new(
+                ConstScoreQuery::new(query.to_query(schema)?, score),
+            )),
+            QueryDescription::FunctionScore {
+                ref query,
+                ref functions,
+            } => {
+                let functions = functions
+                    .iter()
+                    .map(|description| description.to_score_function(schema))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Box::new(FunctionScoreQuery::new(
+                    query.to_query(schema)?,
+                    functions,
+                )))
+            }
+            QueryDescription::Span(ref description) => {
+                Ok(Box::new(description.to_span_query(schema)?))
+            }
+            QueryDescription::MultiPhrase {
+                ref field,
+                ref terms,
+                ref offsets,
+                slop,
+            } => {
+                let field = get_text_field(schema, field)?;
+                if terms.len() < 2 || terms.iter().any(Vec::is_empty) {
+                    return Err(TantivyError::InvalidArgument(
+                        "A multi phrase requires at least two positions, with at least one term each"
+                            .to_string(),
+                    ));
+                }
+                let terms_with_offsets = phrase_offsets(offsets, terms.len())?
+                    .into_iter()
+                    .zip(terms.iter())
+                    .map(|(offset, texts)| {
+                        let terms = texts
+                            .iter()
+                            .map(|text| Term::from_field_text(field, text))
+                            .collect();
+                        (offset, terms)
+                    })
+                    .collect();
+                let mut multi_phrase_query = MultiPhraseQuery::new_with_offset(terms_with_offsets);
+                multi_phrase_query.set_slop(slop);
+                Ok(Box::new(multi_phrase_query))
+            }
+            QueryDescription::PhrasePrefix {
+                ref field,
+                ref terms,
+                ref offsets,
+                slop,
+                max_expansions,
+            } => {
+                let field = get_text_field(schema, field)?;
+                if terms.len() < 2 {
+                    return Err(TantivyError::InvalidArgument(
+                        "A phrase requires at least two terms".to_string(),
+                    ));
+                }
+                let terms_with_offsets = phrase_offsets(offsets, terms.len())?
+                    .into_iter()
+                    .zip(terms.iter())
+                    .map(|(offset, text)| (offset, Term::from_field_text(field, text)))
+                    .collect();
+                let mut phrase_prefix_query =
+                    PhrasePrefixQuery::new_with_offset(terms_with_offsets);
+                phrase_prefix_query.set_slop(slop);
+                phrase_prefix_query.set_max_expansions(max_expansions);
+                Ok(Box::new(phrase_prefix_query))
+            }
+            QueryDescription::Exists { ref field } => {
+                Ok(Box::new(ExistsQuery::new(get_field(schema, field)?)))
+            }
+            QueryDescription::MoreLikeThis {
+                ref fields,
+                ref text,
+                document,
+                min_term_freq,
+                min_doc_freq,
+                max_doc_freq,
+                max_query_terms,
+            } => {
+                let fields = fields
+                    .iter()
+                    .map(|field| get_text_field(schema, field))
+                    .collect::<Result<Vec<Field>>>()?;
+                let source = match (text.as_ref(), document) {
+                    (Some(text), None) => MoreLikeThisSource::Text(text.clone()),
+                    (None, Some((segment_ord, doc))) => {
+                        MoreLikeThisSource::Document(DocAddress(segment_ord, doc))
+                    }
+                    _ => {
+                        return Err(TantivyError::InvalidArgument(
+                            "A more like this query requires either a text or a document"
+                                .to_string(),
+                        ));
+                    }
+                };
+                let mut more_like_this_query = MoreLikeThisQuery::new(source, fields);
+                more_like_this_query.set_min_term_freq(min_term_freq);
+                more_like_this_query.set_min_doc_freq(min_doc_freq);
+                if let Some(max_doc_freq) = max_doc_freq {
+                    more_like_this_query.set_max_doc_freq(max_doc_freq);
+                }
+                more_like_this_query.set_max_query_terms(max_query_terms);
+                Ok(Box::new(more_like_this_query))
+            }
+            QueryDescription::Cached { ref query, ref key } => {
+                Ok(Box::new(CachedQuery::new(query.to_query(schema)?, key)))
+            }
+            QueryDescription::Vector {
+                ref field,
+                ref vector,
+                k,
+                similarity,
+                num_candidates,
+                ref filter,
+            } => {
+                let mut vector_query =
+                    VectorQuery::new(get_field(schema, field)?, vector.clone(), k);
+                vector_query.set_similarity(similarity);
+                vector_query.set_num_candidates(num_candidates);
+                if let Some(ref filter) = *filter {
+                    vector_query.set_filter(filter.to_query(schema)?);
+                }
+                Ok(Box::new(vector_query))
+            }
+        }
+    }
+
+    /// Describes a query.
+    ///
+    /// Returns an error if the query, or one of its subqueries,
+    /// is not one of the queries of `tantivy::query`.
+    pub fn from_query(query: &Query, schema: &Schema) -> Result<QueryDescription> {
+        if query.is::<AllQuery>() {
+            Ok(QueryDescription::All)
+        } else if query.is::<EmptyQuery>() {
+            Ok(QueryDescription::Empty)
+        } else if let Some(term_query) = query.downcast_ref::<TermQuery>() {
+            let term = term_query.term();
+            Ok(QueryDescription::Term {
+                field: schema.get_field_name(term.field()).to_string(),
+                value: term_to_json(schema, term)?,
+                index_record_option: term_query.index_record_option(),
+            })
+        } else if let Some(phrase_query) = query.downcast_ref::<PhraseQuery>() {
+            let terms_with_offsets = phrase_query.phrase_terms_with_offsets();
+            let offsets: Vec<usize> = terms_with_offsets
+                .iter()
+                .map(|&(offset, _)| offset)
+                .collect();
+            Ok(QueryDescription::Phrase {
+                field: schema.get_field_name(phrase_query.field()).to_string(),
+                terms: terms_with_offsets
+                    .iter()
+                    .map(|(_, term)| term.text().to_string())
+                    .collect(),
+                offsets: describe_offsets(offsets),
+                slop: phrase_query.slop(),
+            })
+        } else if let Some(range_query) = query.downcast_ref::<RangeQuery>() {
+            let (gt, gte) = bound_to_json(schema, range_query.left_bound())?;
+            let (lt, lte) = bound_to_json(schema, range_query.right_bound())?;
+            Ok(QueryDescription::Range {
+                field: schema.get_field_name(range_query.field()).to_string(),
+                gt,
+                gte,
+                lt,
+                lte,
+            })
+        } else if let Some(boolean_query) = query.downcast_ref::<BooleanQuery>() {
+            let clauses = boolean_query
+                .clauses()
+                .iter()
+                .map(|&(occur, ref subquery)| {
+                    Ok((
+                        occur,
+                        QueryDescription::from_query(subquery.as_ref(), schema)?,
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(QueryDescription::Boolean {
+                clauses,
+                minimum_should_match: boolean_query.minimum_should_match(),
+            })
+        } else if let Some(boost_query) = query.downcast_ref::<BoostQuery>() {
+            Ok(QueryDescription::Boost {
+                query: Box::new(QueryDescription::from_query(boost_query.query(), schema)?),
+                boost: boost_query.boost(),
+            })
+        } else if let Some(fuzzy_query) = query.downcast_ref::<FuzzyTermQuery>() {
+            let term = fuzzy_query.term();
+            Ok(QueryDescription::Fuzzy {
+                field: schema.get_field_name(term.field()).to_string(),
+                value: term.text().to_string(),
+                distance: fuzzy_query.distance(),
+                transposition_cost_one: fuzzy_query.transposition_cost_one(),
+                prefix: fuzzy_query.is_prefix(),
+            })
+        } else if let Some(regex_query) = query.downcast_ref::<RegexQuery>() {
+            Ok(QueryDescription::Regex {
+                field: schema.get_field_name(regex_query.field()).to_string(),
+                pattern: regex_query.pattern().to_string(),
+            })
+        } else if let Some(prefix_query) = query.downcast_ref::<PrefixQuery>() {
+            let prefix = prefix_query.prefix();
+            Ok(QueryDescription::Prefix {
+                field: schema.get_field_name(prefix.field()).to_string(),
+                value: prefix.text().to_string(),
+                max_expansions: prefix_query.max_expansions(),
+            })
+        } else if let Some(wildcard_query) = query.downcast_ref::<WildcardQuery>() {
+            Ok(QueryDescription::Wildcard {
+                field: schema.get_field_name(wildcard_query.field()).to_string(),
+                pattern: wildcard_query.pattern().to_string(),
+                max_expansions: wildcard_query.max_expansions(),
+            })
+        } else if let Some(term_set_query) = query.downcast_ref::<TermSetQuery>() {
+            let mut terms: BTreeMap<String, Vec<JsonValue>> = BTreeMap::new();
+            for term in term_set_query.terms() {
+                let field_name = schema.get_field_name(term.field()).to_string();
+                terms
+                    .entry(field_name)
+                    .or_default()
+                    .push(term_to_json(schema, term)?);
+            }
+            Ok(QueryDescription::TermSet { terms })
+        } else if let Some(disjunction_max_query) = query.downcast_ref::<DisjunctionMaxQuery>() {
+            let disjuncts = disjunction_max_query
+                .disjuncts()
+                .iter()
+                .map(|disjunct| QueryDescription::from_query(disjunct.as_ref(), schema))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(QueryDescription::DisjunctionMax {
+                disjuncts,
+                tie_breaker: disjunction_max_query.tie_breaker(),
+            })
+        } else if let Some(const_score_query) = query.downcast_ref::<ConstScoreQuery>() {
+            Ok(QueryDescription::ConstScore {
+                query: Box::new(QueryDescription::from_query(
+                    const_score_query.query(),
+                    schema,
+                )?),
+                score: const_score_query.score(),
+            })
+        } else if let Some(function_score_query) = query.downcast_ref::<FunctionScoreQuery>() {
+            let functions = function_score_query
+                .functions()
+                .iter()
+                .map(|function| ScoreFunctionDescription::from_score_function(function, schema))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(QueryDescription::FunctionScore {
+                query: Box::new(QueryDescription::from_query(
+                    function_score_query.query(),
+                    schema,
+                )?),
+                functions,
+            })
+        } else if let Some(span_query) = query.downcast_ref::<SpanQuery>() {
+            Ok(QueryDescription::Span(
+                SpanQueryDescription::from_span_query(span_query, schema)?,
+            ))
+        } else if let Some(multi_phrase_query) = query.downcast_ref::<MultiPhraseQuery>() {
+            let terms_with_offsets = multi_phrase_query.phrase_terms_with_offsets();
+            let offsets: Vec<usize> = terms_with_offsets
+                .iter()
+                .map(|&(offset, _)| offset)
+                .collect();
+            Ok(QueryDescription::MultiPhrase {
+                field: schema
+                    .get_field_name(multi_phrase_query.field())
+                    .to_string(),
+                terms: terms_with_offsets
+                    .iter()
+                    .map(|(_, terms)| terms.iter().map(|term| term.text().to_string()).collect())
+                    .collect(),
+                offsets: describe_offsets(offsets),
+                slop: multi_phrase_query.slop(),
+            })
+        } else if let Some(phrase_prefix_query) = query.downcast_ref::<PhrasePrefixQuery>() {
+            let mut terms_with_offsets = phrase_prefix_query.phrase_terms_with_offsets().to_vec();
+            terms_with_offsets.push(phrase_prefix_query.prefix_with_offset().clone());
+            let offsets: Vec<usize> = terms_with_offsets
+                .iter()
+                .map(|&(offset, _)| offset)
+                .collect();
+            Ok(QueryDescription::PhrasePrefix {
+                field: schema
+                    .get_field_name(phrase_prefix_query.field())
+                    .to_string(),
+                terms: terms_with_offsets
+                    .iter()
+                    .map(|(_, term)| term.text().to_string())
+                    .collect(),
+                offsets: describe_offsets(offsets),
+                slop: phrase_prefix_query.slop(),
+                max_expansions: phrase_prefix_query.max_expansions(),
+            })
+        } else if let Some(exists_query) = query.downcast_ref::<ExistsQuery>() {
+            Ok(QueryDescription::Exists {
+                field: schema.get_field_name(exists_query.field()).to_string(),
+            })
+        } else if let Some(more_like_this_query) = query.downcast_ref::<MoreLikeThisQuery>() {
+            let (text, document) = match *more_like_this_query.source() {
+                MoreLikeThisSource::Text(ref text) => (Some(text.clone()), None),
+                MoreLikeThisSource::Document(DocAddress(segment_ord, doc)) => {
+                    (None, Some((segment_ord, doc)))
+                }
+            };
+            Ok(QueryDescription::MoreLikeThis {
+                fields: more_like_this_query
+                    .fields()
+                    .iter()
+                    .map(|&field| schema.get_field_name(field).to_string())
+                    .collect(),
+                text,
+                document,
+                min_term_freq: more_like_this_query.min_term_freq(),
+                min_doc_freq: more_like_this_query.min_doc_freq(),
+                max_doc_freq: more_like_this_query.max_doc_freq(),
+                max_query_terms: more_like_this_query.max_query_terms(),
+            })
+        } else if let Some(cached_query) = query.downcast_ref::<CachedQuery>() {
+            Ok(QueryDescription::Cached {
+                query: Box::new(QueryDescription::from_query(cached_query.query(), schema)?),
+                key: cached_query.key().to_string(),
+            })
+        } else if let Some(vector_query) = query.downcast_ref::<VectorQuery>() {
+            Ok(QueryDescription::Vector {
+                field: schema.get_field_name(vector_query.field()).to_string(),
+                vector: vector_query.vector().to_vec(),
+                k: vector_query.k(),
+                similarity: vector_query.similarity(),
+                num_candidates: vector_query.num_candidates(),
+                filter: match vector_query.filter() {
+                    Some(filter) => Some(Box::new(QueryDescription::from_query(filter, schema)?)),
+                    None => None,
+                },
+            })
+        } else {
+            Err(TantivyError::InvalidArgument(format!(
+                "Query {:?} cannot be described",
+                query
+            )))
+        }
+    }
+}
+
+/// Serializable description of a `ScoreFunction`, see `QueryDescription::FunctionScore`.
+///
+/// Fields are referred to by their name. `ScoreFunction::Custom` cannot be described.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreFunctionDescription {
+    /// Decay of the distance between the value of a field and an origin,
+    /// see `ScoreFunction::Decay`.
+    Decay {
+        /// Shape of the decay.
+        shape: Decay,
+        /// Field name.
+        field: String,
+        /// Value for which the factor is `1`.
+        origin: f64,
+        /// Distance beyond the offset at which the factor is `decay`.
+        scale: f64,
+        /// Distance to `origin` under which the factor is `1`. Defaults to `0`.
+        #[serde(default)]
+        offset: f64,
+        /// Factor at `scale` beyond the offset, between `0` and `1`.
+        decay: f64,
+    },
+    /// Value of a field multiplied by a factor, see `ScoreFunction::FieldValueFactor`.
+    FieldValueFactor {
+        /// Field name.
+        field: String,
+        /// Factor applied to the value.
+        factor: f64,
+    },
+    /// `ln(1 + factor * value)`, see `ScoreFunction::Log1p`.
+    Log1p {
+        /// Field name.
+        field: String,
+        /// Factor applied to the value.
+        factor: f64,
+    },
+}
+
+impl ScoreFunctionDescription {
+    /// Builds the score function described, resolving the field names against the schema.
+    pub fn to_score_function(&self, schema: &Schema) -> Result<ScoreFunction> {
+        match *self {
+            ScoreFunctionDescription::Decay {
+                shape,
+                ref field,
+                origin,
+                scale,
+                offset,
+                decay,
+            } => {
+                let field = get_field(schema, field)?;
+                if scale <= 0f64 || decay <= 0f64 || decay >= 1f64 {
+                    return Err(TantivyError::InvalidArgument(format!(
+                        "Invalid decay function, with a scale of {} and a decay of {}",
+                        scale, decay
+                    )));
+                }
+                Ok(match shape {
+                    Decay::Linear => {
+                        ScoreFunction::linear_decay(field, origin, scale, offset, decay)
+                    }
+                    Decay::Exponential => {
+                        ScoreFunction::exponential_decay(field, origin, scale, offset, decay)
+                    }
+                    Decay::Gaussian => {
+                        ScoreFunction::gaussian_decay(field, origin, scale, offset, decay)
+                    }
+                })
+            }
+            ScoreFunctionDescription::FieldValueFactor { ref field, factor } => Ok(
+                ScoreFunction::field_value_factor(get_field(schema, field)?, factor),
+            ),
+            ScoreFunctionDescription::Log1p { ref field, factor } => {
+                Ok(ScoreFunction::log1p(get_field(schema, field)?, factor))
+            }
+        }
+    }
+
+    /// Describes a score function.
+    ///
+    /// Returns an error for `ScoreFunction::Custom`, as closures cannot be described.
+    pub fn from_score_function(
+        function: &ScoreFunction,
+        schema: &Schema,
+    ) -> Result<ScoreFunctionDescription> {
+        match *function {
+            ScoreFunction::Decay {
+                shape,
+                field,
+                origin,
+                scale,
+                offset,
+                decay,
+            } => Ok(ScoreFunctionDescription::Decay {
+                shape,
+                field: schema.get_field_name(field).to_string(),
+                origin,
+                scale,
+                offset,
+                decay,
+            }),
+            ScoreFunction::FieldValueFactor { field, factor } => {
+                Ok(ScoreFunctionDescription::FieldValueFactor {
+                    field: schema.get_field_name(field).to_string(),
+                    factor,
+                })
+            }
+            ScoreFunction::Log1p { field, factor } => Ok(ScoreFunctionDescription::Log1p {
+                field: schema.get_field_name(field).to_string(),
+                factor,
+            }),
+            ScoreFunction::Custom { .. } => Err(TantivyError::InvalidArgument(format!(
+                "Score function {:?} cannot be described",
+                function
+            ))),
+        }
+    }
+}
+
+/// Serializable description of a `SpanQuery`, see `QueryDescription::Span`.
+///
+/// Fields are referred to by their name, and must be text fields.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpanQueryDescription {
+    /// Matches the positions of a term, see `SpanQuery::Term`.
+    Term {
+        /// Name of the text field searched.
+        field: String,
+        /// Text of the term.
+        value: String,
+    },
+    /// Matches a span of each of the clauses, close to one another, see `SpanQuery::Near`.
+    Near {
+        /// Span queries matched.
+        clauses: Vec<SpanQueryDescription>,
+        /// Maximum number of positions between the spans of the clauses. Defaults to `0`.
+        #[serde(default)]
+        slop: u32,
+        /// Whether the spans must be in the order of the clauses. Defaults to `false`.
+        #[serde(default)]
+        in_order: bool,
+    },
+    /// Matches the spans of any of the clauses, see `SpanQuery::Or`.
+    Or {
+        /// Span queries matched.
+        clauses: Vec<SpanQueryDescription>,
+    },
+    /// Matches the spans of a span query not close to the spans of another one,
+    /// see `SpanQuery::Not`.
+    Not {
+        /// Span query matched.
+        include: Box<SpanQueryDescription>,
+        /// Span query excluded.
+        exclude: Box<SpanQueryDescription>,
+        /// Minimum distance from a preceding excluded span. Defaults to `0`.
+        #[serde(default)]
+        pre: u32,
+        /// Minimum distance to a following excluded span. Defaults to `0`.
+        #[serde(default)]
+        post: u32,
+    },
+    /// Matches the spans of a span query ending at most at a given position,
+    /// see `SpanQuery::First`.
+    First {
+        /// Span query matched.
+        query: Box<SpanQueryDescription>,
+        /// Position the spans must end before.
+        end: u32,
+    },
+}
+
+impl SpanQueryDescription {
+    /// Builds the span query described, resolving the field names against the schema.
+    pub fn to_span_query(&self, schema: &Schema) -> Result<SpanQuery> {
+        let to_span_queries = |clauses: &[SpanQueryDescription]| {
+            clauses
+                .iter()
+                .map(|clause| clause.to_span_query(schema))
+                .collect::<Result<Vec<_>>>()
+        };
+        match *self {
+            SpanQueryDescription::Term {
+                ref field,
+                ref value,
+            } => {
+                let field = get_text_field(schema, field)?;
+                Ok(SpanQuery::term(Term::from_field_text(field, value)))
+            }
+            SpanQueryDescription::Near {
+                ref clauses,
+                slop,
+                in_order,
+            } => Ok(SpanQuery::near(to_span_queries(clauses)?, slop, in_order)),
+            SpanQueryDescription::Or { ref clauses } => {
+                Ok(SpanQuery::or(to_span_queries(clauses)?))
+            }
+            SpanQueryDescription::Not {
+                ref include,
+                ref exclude,
+                pre,
+                post,
+            } => Ok(SpanQuery::not_within(
+                include.to_span_query(schema)?,
+                exclude.to_span_query(schema)?,
+                pre,
+                post,
+            )),
+            SpanQueryDescription::First { ref query, end } => {
+                Ok(SpanQuery::first(query.to_span_query(schema)?, end))
+            }
+        }
+    }
+
+    /// Describes a span query.
+    pub fn from_span_query(
+        span_query: &SpanQuery,
+        schema: &Schema,
+    ) -> Result<SpanQueryDescription> {
+        let from_span_queries = |clauses: &[SpanQuery]| {
+            clauses
+                .iter()
+                .map(|clause| SpanQueryDescription::from_span_query(clause, schema))
+                .collect::<Result<Vec<_>>>()
+        };
+        match *span_query {
+            SpanQuery::Term(ref term) => Ok(SpanQueryDescription::Term {
+                field: schema.get_field_name(term.field()).to_string(),
+                value: term.text().to_string(),
+            }),
+            SpanQuery::Near {
+                ref clauses,
+                slop,
+                in_order,
+            } => Ok(SpanQueryDescription::Near {
+                clauses: from_span_queries(clauses)?,
+                slop,
+                in_order,
+            }),
+            SpanQuery::Or(ref clauses) => Ok(SpanQueryDescription::Or {
+                clauses: from_span_queries(clauses)?,
+            }),
+            SpanQuery::Not {
+                ref include,
+                ref exclude,
+                pre,
+                post,
+            } => Ok(SpanQueryDescription::Not {
+                include: Box::new(SpanQueryDescription::from_span_query(include, schema)?),
+                exclude: Box::new(SpanQueryDescription::from_span_query(exclude, schema)?),
+                pre,
+                post,
+            }),
+            SpanQuery::First { ref query, end } => Ok(SpanQueryDescription::First {
+                query: Box::new(SpanQueryDescription::from_span_query(query, schema)?),
+                end,
+            }),
+        }
+    }
+}
+
+/// Returns the offsets of the terms of a phrase, `0, 1, 2...` if they are not given.
+fn phrase_offsets(offsets: &Option<Vec<usize>>, num_terms: usize) -> Result<Vec<usize>> {
+    match *offsets {
+        Some(ref offsets) if offsets.len() != num_terms => Err(TantivyError::InvalidArgument(
+            "A phrase requires as many offsets as terms".to_string(),
+        )),
+        Some(ref offsets) => Ok(offsets.clone()),
+        None => Ok((0..num_terms).collect()),
+    }
+}
+
+/// Returns the offsets of the terms of a phrase, or `None` if they are the default ones.
+fn describe_offsets(offsets: Vec<usize>) -> Option<Vec<usize>> {
+    if offsets.iter().cloned().eq(0..offsets.len()) {
+        None
+    } else {
+        Some(offsets)
+    }
+}
+
+fn get_field(schema: &Schema, field_name: &str) -> Result<Field> {
+    schema
+        .get_field(field_name)
+        .ok_or_else(|| TantivyError::SchemaError(format!("Unknown field {:?}", field_name)))
+}
+
+fn get_text_field(schema: &Schema, field_name: &str) -> Result<Field> {
+    let field = get_field(schema, field_name)?;
+    match *schema.get_field_entry(field).field_type() {
+        FieldType::Str(_) => Ok(field),
+        _ => Err(TantivyError::SchemaError(format!(
+            "Field {:?} is not a text field",
+            field_name
+        ))),
+    }
+}
+
+/// Builds the term of a field from its JSON value.
+fn term_from_json(schema: &Schema, field: Field, value: &JsonValue) -> Result<Term> {
+    let field_entry = schema.get_field_entry(field);
+    let invalid_value = || {
+        TantivyError::InvalidArgument(format!(
+            "Invalid value {} for field {:?}",
+            value,
+            field_entry.name()
+        ))
+    };
+    match *field_entry.field_type() {
+        FieldType::Str(_) => {
+            let text = value.as_str().ok_or_else(invalid_value)?;
+            Ok(Term::from_field_text(field, text))
+        }
+        FieldType::U64(_) => {
+            let val = value.as_u64().ok_or_else(invalid_value)?;
+            Ok(Term::from_field_u64(field, val))
+        }
+        FieldType::I64(_) => {
+            let val = value.as_i64().ok_or_else(invalid_value)?;
+            Ok(Term::from_field_i64(field, val))
+        }
+        FieldType::Date(_) => {
+            let date_str = value.as_str().ok_or_else(invalid_value)?;
+            let date = chrono::DateTime::parse_from_rfc3339(date_str)
+                .map_err(|_| invalid_value())?
+                .with_timezone(&chrono::Utc);
+            Ok(Term::from_field_date(field, &date))
+        }
+        FieldType::HierarchicalFacet => {
+            let facet_str = value.as_str().ok_or_else(invalid_value)?;
+            Ok(Term::from_facet(field, &Facet::from_text(facet_str)))
+        }
+        FieldType::Bytes | FieldType::Vector(_) => Err(TantivyError::SchemaError(format!(
+            "Field {:?} cannot be searched",
+            field_entry.name()
+        ))),
+    }
+}
+
+/// Returns the JSON value of a term, as accepted by `term_from_json`.
+fn term_to_json(schema: &Schema, term: &Term) -> Result<JsonValue> {
+    let field_entry = schema.get_field_entry(term.field());
+    match *field_entry.field_type() {
+        FieldType::Str(_) => Ok(JsonValue::from(term.text())),
+        FieldType::U64(_) => Ok(JsonValue::from(term.get_u64())),
+        FieldType::I64(_) => Ok(JsonValue::from(term.get_i64())),
+        FieldType::Date(_) => {
+            let date = chrono::Utc.timestamp(term.get_i64(), 0);
+            Ok(JsonValue::from(date.to_rfc3339()))
+        }
+        FieldType::HierarchicalFacet => {
+            let facet = Facet::from_encoded(term.value_bytes().to_vec())
+                .map_err(|_| TantivyError::InvalidArgument("Invalid facet term".to_string()))?;
+            Ok(JsonValue::from(facet.to_string()))
+        }
+        FieldType::Bytes | FieldType::Vector(_) => Err(TantivyError::SchemaError(format!(
+            "Field {:?} cannot be searched",
+            field_entry.name()
+        ))),
+    }
+}
+
+fn bound_from_json(
+    schema: &Schema,
+    field: Field,
+    excluded: &Option<JsonValue>,
+    included: &Option<JsonValue>,
+) -> Result<Bound<Term>> {
+    match (excluded.as_ref(), included.as_ref()) {
+        (Some(_), Some(_)) => Err(TantivyError::InvalidArgument(
+            "A range bound cannot be both inclusive and exclusive".to_string(),
+        )),
+        (Some(value), None) => Ok(Bound::Excluded(term_from_json(schema, field, value)?)),
+        (None, Some(value)) => Ok(Bound::Included(term_from_json(schema, field, value)?)),
+        (None, None) => Ok(Bound::Unbounded),
+    }
+}
+
+/// Returns the exclusive and inclusive JSON values of a bound.
+fn bound_to_json(
+    schema: &Schema,
+    bound: Bound<Term>,
+) -> Result<(Option<JsonValue>, Option<JsonValue>)> {
+    match bound {
+        Bound::Excluded(ref term) => Ok((Some(term_to_json(schema, term)?), None)),
+        Bound::Included(ref term) => Ok((None, Some(term_to_json(schema, term)?))),
+        Bound::Unbounded => Ok((None, None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryDescription;
+    use collector::Count;
+    use query::{
+        AllQuery, BooleanQuery, BoostQuery, CachedQuery, ConstScoreQuery, DisjunctionMaxQuery,
+        EmptyQuery, ExistsQuery, FunctionScoreQuery, FuzzyTermQuery, MoreLikeThisQuery,
+        MultiPhraseQuery, Occur, PhrasePrefixQuery, PhraseQuery, PrefixQuery, Query, QueryParser,
+        RangeQuery, RegexQuery, ScoreFunction, SpanQuery, TermQuery, TermSetQuery, VectorQuery,
+        WildcardQuery,
+    };
+    use schema::{Facet, IndexRecordOption, Schema, Term, FAST, INDEXED, STORED, TEXT};
+    use serde_json;
+    use std::ops::Bound;
+    use vector::VectorSimilarity;
+    use DocAddress;
+    use Index;
+    use Searcher;
+    use {Result, TantivyError};
+
+    fn make_schema() -> Schema {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_u64_field("year", INDEXED);
+        schema_builder.add_i64_field("balance", INDEXED);
+        schema_builder.add_date_field("date", INDEXED);
+        schema_builder.add_facet_field("category");
+        schema_builder.add_bytes_field("bytes");
+        schema_builder.add_u64_field("fast", FAST | STORED);
+        schema_builder.add_vector_field("embedding", 3);
+        schema_builder.build()
+    }
+
+    fn test_round_trip(query: Box<Query>, expected_json: &str) {
+        let schema = make_schema();
+        let description = QueryDescription::from_query(query.as_ref(), &schema).unwrap();
+        let json = serde_json::to_string(&description).unwrap();
+        assert_eq!(json, expected_json);
+        let deserialized: QueryDescription = serde_json::from_str(&json).unwrap();
+        let rebuilt_query = deserialized.to_query(&schema).unwrap();
+        assert_eq!(format!("{:?}", rebuilt_query), format!("{:?}", query));
+    }
+
+    #[test]
+    fn test_query_description_round_trip() {
+        let schema = make_schema();
+        let title = schema.get_field("title").unwrap();
+        let year = schema.get_field("year").unwrap();
+        let balance = schema.get_field("balance").unwrap();
+        let date = schema.get_field("date").unwrap();
+        let category = schema.get_field("category").unwrap();
+        let embedding = schema.get_field("embedding").unwrap();
+        let text_term = |text: &str| Term::from_field_text(title, text);
+        test_round_trip(
+            Box::new(TermQuery::new(text_term("rust"), IndexRecordOption::Basic)),
+            r#"{"term":{"field":"title","value":"rust","index_record_option":"basic"}}"#,
+        );
+        test_round_trip(
+            Box::new(TermQuery::new(
+                Term::from_field_i64(balance, -3),
+                IndexRecordOption::WithFreqs,
+            )),
+            r#"{"term":{"field":"balance","value":-3,"index_record_option":"freq"}}"#,
+        );
+        test_round_trip(
+            Box::new(TermQuery::new(
+                Term::from_facet(category, &Facet::from("/lang/rust")),
+                IndexRecordOption::Basic,
+            )),
+            r#"{"term":{"field":"category","value":"/lang/rust","index_record_option":"basic"}}"#,
+        );
+        let mut phrase_query = PhraseQuery::new(vec![text_term("a"), text_term("b")]);
+        phrase_query.set_slop(2);
+        test_round_trip(
+            Box::new(phrase_query),
+            r#"{"phrase":{"field":"title","terms":["a","b"],"slop":2}}"#,
+        );
+        test_round_trip(
+            Box::new(PhraseQuery::new_with_offset(vec![
+                (0, text_term("a")),
+                (2, text_term("b")),
+            ])),
+            r#"{"phrase":{"field":"title","terms":["a","b"],"offsets":[0,2],"slop":0}}"#,
+        );
+        test_round_trip(
+            Box::new(RangeQuery::new_u64_bounds(
+                year,
+                Bound::Included(1990),
+                Bound::Excluded(2000),
+            )),
+            r#"{"range":{"field":"year","gte":1990,"lt":2000}}"#,
+        );
+        test_round_trip(
+            Box::new(RangeQuery::new_term_bounds(
+                date,
+                schema.get_field_entry(date).field_type().value_type(),
+                &Bound::Excluded(Term::from_field_date(
+                    date,
+                    &"2019-03-01T00:00:00Z".parse().unwrap(),
+                )),
+                &Bound::Unbounded,
+            )),
+            r#"{"range":{"field":"date","gt":"2019-03-01T00:00:00+00:00"}}"#,
+        );
+        test_round_trip(
+            Box::new(BooleanQuery::from(vec![
+                (Occur::Must, Box::new(AllQuery) as Box<Query>),
+                (
+                    Occur::MustNot,
+                    Box::new(BoostQuery::new(Box::new(EmptyQuery), 0.5)),
+                ),
+            ])),
+            r#"{"boolean":{"clauses":[["must","all"],["must_not",{"boost":{"query":"empty","boost":0.5}}]]}}"#,
+        );
+        let mut boolean_query = BooleanQuery::from(vec![
+            (Occur::Should, Box::new(AllQuery) as Box<Query>),
+            (Occur::Should, Box::new(EmptyQuery)),
+        ]);
+        boolean_query.set_minimum_should_match(2);
+        test_round_trip(
+            Box::new(boolean_query),
+            r#"{"boolean":{"clauses":[["should","all"],["should","empty"]],"minimum_should_match":2}}"#,
+        );
+        test_round_trip(
+            Box::new(FuzzyTermQuery::new_prefix(text_term("rust"), 1, false)),
+            r#"{"fuzzy":{"field":"title","value":"rust","distance":1,"transposition_cost_one":false,"prefix":true}}"#,
+        );
+        test_round_trip(
+            Box::new(RegexQuery::new("ru.*".to_string(), title)),
+            r#"{"regex":{"field":"title","pattern":"ru.*"}}"#,
+        );
+        let mut prefix_query = PrefixQuery::new(text_term("ru"));
+        prefix_query.set_max_expansions(10);
+        test_round_trip(
+            Box::new(prefix_query),
+            r#"{"prefix":{"field":"title","value":"ru","max_expansions":10}}"#,
+        );
+        test_round_trip(
+            Box::new(WildcardQuery::new("r?st".to_string(), title)),
+            r#"{"wildcard":{"field":"title","pattern":"r?st"}}"#,
+        );
+        test_round_trip(
+            Box::new(TermSetQuery::new(vec![
+                Term::from_field_u64(year, 2019),
+                text_term("rust"),
+                text_term("python"),
+            ])),
+            r#"{"term_set":{"terms":{"title":["python","rust"],"year":[2019]}}}"#,
+        );
+        test_round_trip(
+            Box::new(DisjunctionMaxQuery::with_tie_breaker(
+                vec![
+                    Box::new(TermQuery::new(
+                        text_term("rust"),
+                        IndexRecordOption::WithFreqs,
+                    )),
+                    Box::new(AllQuery),
+                ],
+                0.5,
+            )),
+            r#"{"disjunction_max":{"disjuncts":[{"term":{"field":"title","value":"rust","index_record_option":"freq"}},"all"],"tie_breaker":0.5}}"#,
+        );
+        test_round_trip(
+            Box::new(BooleanQuery::from(vec![
+                (Occur::Should, Box::new(AllQuery) as Box<Query>),
+                (
+                    Occur::Filter,
+                    Box::new(ConstScoreQuery::new(Box::new(EmptyQuery), 2f32)),
+                ),
+            ])),
+            r#"{"boolean":{"clauses":[["should","all"],["filter",{"const_score":{"query":"empty","score":2.0}}]]}}"#,
+        );
+        test_round_trip(
+            Box::new(FunctionScoreQuery::new(
+                Box::new(AllQuery),
+                vec![
+                    ScoreFunction::gaussian_decay(year, 2019f64, 5f64, 1f64, 0.5),
+                    ScoreFunction::field_value_factor(year, 0.5),
+                    ScoreFunction::log1p(year, 2f64),
+                ],
+            )),
+            r#"{"function_score":{"query":"all","functions":[{"decay":{"shape":"gaussian","field":"year","origin":2019.0,"scale":5.0,"offset":1.0,"decay":0.5}},{"field_value_factor":{"field":"year","factor":0.5}},{"log1p":{"field":"year","factor":2.0}}]}}"#,
+        );
+        let span_term = |text: &str| SpanQuery::term(text_term(text));
+        test_round_trip(
+            Box::new(SpanQuery::first(
+                SpanQuery::not_within(
+                    SpanQuery::near(
+                        vec![
+                            span_term("alice"),
+                            SpanQuery::or(vec![span_term("bob"), span_term("carol")]),
+                        ],
+                        5,
+                        true,
+                    ),
+                    span_term("eve"),
+                    0,
+                    2,
+                ),
+                20,
+            )),
+            r#"{"span":{"first":{"query":{"not":{"include":{"near":{"clauses":[{"term":{"field":"title","value":"alice"}},{"or":{"clauses":[{"term":{"field":"title","value":"bob"}},{"term":{"field":"title","value":"carol"}}]}}],"slop":5,"in_order":true}},"exclude":{"term":{"field":"title","value":"eve"}},"pre":0,"post":2}},"end":20}}}"#,
+        );
+        test_round_trip(
+            Box::new(MultiPhraseQuery::new(vec![
+                vec![text_term("quick"), text_term("fast")],
+                vec![text_term("car")],
+            ])),
+            r#"{"multi_phrase":{"field":"title","terms":[["quick","fast"],["car"]],"slop":0}}"#,
+        );
+        let mut phrase_prefix_query = PhrasePrefixQuery::new_with_offset(vec![
+            (0, text_term("hello")),
+            (2, text_term("wor")),
+        ]);
+        phrase_prefix_query.set_slop(1);
+        phrase_prefix_query.set_max_expansions(10);
+        test_round_trip(
+            Box::new(phrase_prefix_query),
+            r#"{"phrase_prefix":{"field":"title","terms":["hello","wor"],"offsets":[0,2],"slop":1,"max_expansions":10}}"#,
+        );
+        test_round_trip(
+            Box::new(ExistsQuery::new(title)),
+            r#"{"exists":{"field":"title"}}"#,
+        );
+        let mut more_like_this_query =
+            MoreLikeThisQuery::with_document(DocAddress(1, 3), vec![title]);
+        more_like_this_query.set_max_doc_freq(100);
+        test_round_trip(
+            Box::new(more_like_this_query),
+            r#"{"more_like_this":{"fields":["title"],"document":[1,3],"min_term_freq":2,"min_doc_freq":5,"max_doc_freq":100,"max_query_terms":25}}"#,
+        );
+        test_round_trip(
+            Box::new(MoreLikeThisQuery::with_text("hello", vec![title])),
+            r#"{"more_like_this":{"fields":["title"],"text":"hello","min_term_freq":2,"min_doc_freq":5,"max_query_terms":25}}"#,
+        );
+        test_round_trip(
+            Box::new(CachedQuery::new(
+                Box::new(ExistsQuery::new(title)),
+                "has_title",
+            )),
+            r#"{"cached":{"query":{"exists":{"field":"title"}},"key":"has_title"}}"#,
+        );
+        let mut vector_query = VectorQuery::new(embedding, vec![1f32, 0f32, 0.5f32], 10);
+        vector_query.set_similarity(VectorSimilarity::DotProduct);
+        test_round_trip(
+            Box::new(vector_query),
+            r#"{"vector":{"field":"embedding","vector":[1.0,0.0,0.5],"k":10,"similarity":"dot_product","num_candidates":100}}"#,
+        );
+        let mut filtered_vector_query = VectorQuery::new(embedding, vec![1f32, 0f32, 0.5f32], 10);
+        filtered_vector_query.set_filter(Box::new(ExistsQuery::new(title)));
+        test_round_trip(
+            Box::new(filtered_vector_query),
+            r#"{"vector":{"field":"embedding","vector":[1.0,0.0,0.5],"k":10,"similarity":"cosine","num_candidates":100,"filter":{"exists":{"field":"title"}}}}"#,
+        );
+    }
+
+    #[test]
+    fn test_query_description_defaults() {
+        let description: QueryDescription = serde_json::from_str(
+            r#"{"fuzzy": {"field": "title", "value": "rust", "distance": 2}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            description,
+            QueryDescription::Fuzzy {
+                field: "title".to_string(),
+                value: "rust".to_string(),
+                distance: 2,
+                transposition_cost_one: true,
+                prefix: false,
+            }
+        );
+        let description: QueryDescription =
+            serde_json::from_str(r#"{"term": {"field": "title", "value": "rust"}}"#).unwrap();
+        let query = description.to_query(&make_schema()).unwrap();
+        let term_query = query.downcast_ref::<TermQuery>().unwrap();
+        assert_eq!(
+            term_query.index_record_option(),
+            IndexRecordOption::WithFreqs
+        );
+    }
+
+    fn to_query_err(json: &str) -> TantivyError {
+        let description: QueryDescription = serde_json::from_str(json).unwrap();
+        description.to_query(&make_schema()).err().unwrap()
+    }
+
+    #[test]
+    fn test_query_description_errors() {
+        assert_matches!(
+            to_query_err(r#"{"term": {"field": "unknown", "value": "a"}}"#),
+            TantivyError::SchemaError(_)
+        );
+        assert_matches!(
+            to_query_err(r#"{"term": {"field": "year", "value": "a"}}"#),
+            TantivyError::InvalidArgument(_)
+        );
+        assert_matches!(
+            to_query_err(r#"{"term": {"field": "balance", "value": 1.5}}"#),
+            TantivyError::InvalidArgument(_)
+        );
+        assert_matches!(
+            to_query_err(r#"{"regex": {"field": "year", "pattern": "a"}}"#),
+            TantivyError::SchemaError(_)
+        );
+        assert_matches!(
+            to_query_err(r#"{"phrase": {"field": "title", "terms": ["a"]}}"#),
+            TantivyError::InvalidArgument(_)
+        );
+        assert_matches!(
+            to_query_err(r#"{"range": {"field": "year", "gt": 1, "gte": 1}}"#),
+            TantivyError::InvalidArgument(_)
+        );
+        assert_matches!(
+            to_query_err(r#"{"term": {"field": "bytes", "value": "a"}}"#),
+            TantivyError::SchemaError(_)
+        );
+        assert_matches!(
+            to_query_err(
+                r#"{"function_score": {"query": "all", "functions": [
+                    {"decay": {"shape": "linear", "field": "year", "origin": 0, "scale": 1, "decay": 2}}
+                ]}}"#
+            ),
+            TantivyError::InvalidArgument(_)
+        );
+    }
+
+    #[derive(Clone, Debug)]
+    struct CustomQuery;
+
+    impl Query for CustomQuery {
+        fn weight(&self, _: &Searcher, _: bool) -> Result<Box<::query::Weight>> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_query_description_custom_query() {
+        let boolean_query =
+            BooleanQuery::from(vec![(Occur::Should, Box::new(CustomQuery) as Box<Query>)]);
+        assert_matches!(
+            QueryDescription::from_query(&boolean_query, &make_schema()),
+            Err(TantivyError::InvalidArgument(_))
+        );
+        let year = make_schema().get_field("year").unwrap();
+        let function_score_query = FunctionScoreQuery::new(
+            Box::new(AllQuery),
+            vec![ScoreFunction::custom(vec![year], |_| 1f32)],
+        );
+        assert_matches!(
+            QueryDescription::from_query(&function_score_query, &make_schema()),
+            Err(TantivyError::InvalidArgument(_))
+        );
+    }
+
+    #[test]
+    fn test_query_description_search() {
+        let schema = make_schema();
+        let title = schema.get_field("title").unwrap();
+        let year = schema.get_field("year").unwrap();
+        let index = Index::create_in_ram(schema.clone());
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(doc!(title=>"Rust in Action", year=>2019u64));
+        index_writer.add_document(doc!(title=>"Programming Rust", year=>2017u64));
+        index_writer.add_document(doc!(title=>"Programming Python", year=>2010u64));
+        index_writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let query = query_parser
+            .parse_query("+programming year:[2015 TO 2020] -\"in action\"~1 py*^2")
+            .unwrap();
+        let description = QueryDescription::from_query(query.as_ref(), &schema).unwrap();
+        let json = serde_json::to_string(&description).unwrap();
+        let rebuilt_query = serde_json::from_str::<QueryDescription>(&json)
+            .unwrap()
+            .to_query(&schema)
+            .unwrap();
+        assert_eq!(format!("{:?}", rebuilt_query), format!("{:?}", query));
+        assert_eq!(searcher.search(&rebuilt_query, &Count).unwrap(), 2);
+    }
+}
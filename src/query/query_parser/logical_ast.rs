@@ -8,7 +8,26 @@ use std::ops::Bound;
 #[derive(Clone)]
 pub enum LogicalLiteral {
     Term(Term),
-    Phrase(Vec<(usize, Term)>),
+    Fuzzy {
+        term: Term,
+        distance: u8,
+        prefix: bool,
+    },
+    Phrase(Vec<(usize, Term)>, u32),
+    Prefix {
+        prefix: Term,
+        max_expansions: usize,
+    },
+    Wildcard {
+        field: Field,
+        pattern: String,
+        max_expansions: usize,
+    },
+    Regex {
+        field: Field,
+        pattern: String,
+    },
+    Exists(Field),
     Range {
         field: Field,
         value_type: Type,
@@ -22,12 +41,15 @@ pub enum LogicalLiteral {
 pub enum LogicalAST {
     Clause(Vec<(Occur, LogicalAST)>),
     Leaf(Box<LogicalLiteral>),
+    Boost(Box<LogicalAST>, f32),
+    DisjunctionMax(Vec<LogicalAST>, f32),
 }
 
 fn occur_letter(occur: Occur) -> &'static str {
     match occur {
         Occur::Must => "+",
         Occur::MustNot => "-",
+        Occur::Filter => "#",
         Occur::Should => "",
     }
 }
@@ -48,6 +70,17 @@ impl fmt::Debug for LogicalAST {
                 }
                 Ok(())
             }
+            LogicalAST::Boost(ref ast, boost) => write!(formatter, "{:?}^{}", ast, boost),
+            LogicalAST::DisjunctionMax(ref disjuncts, tie_breaker) => {
+                write!(formatter, "max(")?;
+                for (i, disjunct) in disjuncts.iter().enumerate() {
+                    if i > 0 {
+                        write!(formatter, " | ")?;
+                    }
+                    write!(formatter, "{:?}", disjunct)?;
+                }
+                write!(formatter, ")~{}", tie_breaker)
+            }
             LogicalAST::Leaf(ref literal) => write!(formatter, "{:?}", literal),
         }
     }
@@ -63,7 +96,32 @@ impl fmt::Debug for LogicalLiteral {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match *self {
             LogicalLiteral::Term(ref term) => write!(formatter, "{:?}", term),
-            LogicalLiteral::Phrase(ref terms) => write!(formatter, "\"{:?}\"", terms),
+            LogicalLiteral::Fuzzy {
+                ref term,
+                distance,
+                prefix,
+            } => {
+                write!(formatter, "{:?}", term)?;
+                if prefix {
+                    write!(formatter, "*")?;
+                }
+                write!(formatter, "~{}", distance)
+            }
+            LogicalLiteral::Phrase(ref terms, slop) => {
+                write!(formatter, "\"{:?}\"", terms)?;
+                if slop > 0 {
+                    write!(formatter, "~{}", slop)?;
+                }
+                Ok(())
+            }
+            LogicalLiteral::Prefix { ref prefix, .. } => write!(formatter, "{:?}*", prefix),
+            LogicalLiteral::Wildcard {
+                field, ref pattern, ..
+            } => write!(formatter, "{:?}:{}", field, pattern),
+            LogicalLiteral::Regex { field, ref pattern } => {
+                write!(formatter, "{:?}:/{}/", field, pattern)
+            }
+            LogicalLiteral::Exists(field) => write!(formatter, "{:?}:*", field),
             LogicalLiteral::Range {
                 ref lower,
                 ref upper,
@@ -10,28 +10,286 @@ use combine::*;
 use query::occur::Occur;
 use query::query_parser::user_input_ast::UserInputBound;
 
+/// Levenshtein distance used for a fuzzy term when the
+/// distance is omitted, as in `word~`.
+const DEFAULT_FUZZY_DISTANCE: u32 = 1;
+
+/// Characters with a meaning in the query grammar.
+/// They have to be escaped with a backslash to be part of a term.
+const RESERVED_CHARS: &[char] = &[
+    '(', ')', '[', ']', '{', '}', '"', ':', '^', '~', '\\', '*', '?',
+];
+
+/// Characters that can be part of a term, but have to be escaped
+/// to start it.
+const RESERVED_START_CHARS: &[char] = &['+', '-', '/'];
+
+fn is_term_char(c: char) -> bool {
+    !c.is_whitespace() && !RESERVED_CHARS.contains(&c)
+}
+
+fn is_term_start_char(c: char) -> bool {
+    is_term_char(c) && !RESERVED_START_CHARS.contains(&c)
+}
+
+/// Characters acting as wildcards in a term:
+/// `*` matches any sequence of characters and `?` matches a single character.
+fn is_wildcard(c: char) -> bool {
+    c == '*' || c == '?'
+}
+
+/// Returns the operator spelled by `word`, if it is one of the reserved keywords.
+fn operator_keyword(word: &str) -> Option<&'static str> {
+    match word {
+        "OR" => Some("OR"),
+        "AND" => Some("AND"),
+        "NOT" => Some("NOT"),
+        _ => None,
+    }
+}
+
+/// A character of a term, as written in the query.
+#[derive(Clone, Copy)]
+enum TermChar {
+    Literal(char),
+    Escaped(char),
+    Wildcard(char),
+}
+
+parser! {
+    fn escaped_char[I]()(I) -> char
+    where [I: Stream<Item = char>] {
+        (char('\\'), any()).map(|(_, c)| c)
+    }
+}
+
 parser! {
     fn field[I]()(I) -> String
     where [I: Stream<Item = char>] {
-        (
+        let quoted = (
+            char('"'),
+            many1(escaped_char().or(satisfy(|c| c != '"' && c != '\\'))),
+            char('"'),
+        ).map(|(_, field_name, _)| field_name);
+        let unquoted = (
             letter(),
             many(satisfy(|c: char| c.is_alphanumeric() || c == '_')),
-        ).map(|(s1, s2): (char, String)| format!("{}{}", s1, s2))
+        ).map(|(s1, s2): (char, String)| format!("{}{}", s1, s2));
+        quoted.or(unquoted)
+    }
+}
+
+parser! {
+    fn term_chars[I]()(I) -> Vec<TermChar>
+    where [I: Stream<Item = char>] {
+        let term_char = |is_allowed: fn(char) -> bool| {
+            escaped_char()
+                .map(TermChar::Escaped)
+                .or(satisfy(is_wildcard).map(TermChar::Wildcard))
+                .or(satisfy(is_allowed).map(TermChar::Literal))
+        };
+        (term_char(is_term_start_char), many(term_char(is_term_char)))
+            .map(|(first, mut rest): (TermChar, Vec<TermChar>)| {
+                rest.insert(0, first);
+                rest
+            })
     }
 }
 
 parser! {
     fn word[I]()(I) -> String
     where [I: Stream<Item = char>] {
-        many1(satisfy(char::is_alphanumeric))
-               .and_then(|s: String| {
-                   match s.as_str() {
-                     "OR" => Err(StreamErrorFor::<I>::unexpected_static_message("OR")),
-                     "AND" => Err(StreamErrorFor::<I>::unexpected_static_message("AND")),
-                     "NOT" => Err(StreamErrorFor::<I>::unexpected_static_message("NOT")),
-                     _ => Ok(s)
-                   }
-               })
+        term_chars()
+            .and_then(|term_chars: Vec<TermChar>| {
+                match TermValue::from_term_chars(term_chars, None) {
+                    Ok(TermValue::Text { phrase, .. }) => Ok(phrase),
+                    Ok(_) => Err(StreamErrorFor::<I>::unexpected_static_message("wildcard")),
+                    Err(message) => Err(StreamErrorFor::<I>::unexpected_static_message(message)),
+                }
+            })
+    }
+}
+
+/// Value of a literal, before it is bound to a field.
+enum TermValue {
+    Text {
+        phrase: String,
+        slop: u32,
+    },
+    Fuzzy {
+        term: String,
+        distance: u32,
+        prefix: bool,
+    },
+    Wildcard(String),
+    Regex(String),
+}
+
+impl TermValue {
+    /// Classifies a term, possibly containing wildcards and followed by a fuzzy distance.
+    ///
+    /// A single trailing `*` followed by a fuzzy distance is a fuzzy prefix,
+    /// any other mix of wildcards and fuzzy distance is rejected.
+    /// In the pattern of a wildcard term, the literal `*`, `?` and `\\`
+    /// characters are escaped with a backslash.
+    fn from_term_chars(
+        term_chars: Vec<TermChar>,
+        distance_opt: Option<u32>,
+    ) -> Result<TermValue, &'static str> {
+        let literal_text = |term_chars: &[TermChar]| -> String {
+            term_chars
+                .iter()
+                .filter_map(|&term_char| match term_char {
+                    TermChar::Literal(c) | TermChar::Escaped(c) => Some(c),
+                    TermChar::Wildcard(_) => None,
+                })
+                .collect()
+        };
+        let (mut num_wildcards, mut num_escaped_chars) = (0, 0);
+        for term_char in &term_chars {
+            match *term_char {
+                TermChar::Wildcard(_) => num_wildcards += 1,
+                TermChar::Escaped(_) => num_escaped_chars += 1,
+                TermChar::Literal(_) => {}
+            }
+        }
+        if num_wildcards == 0 {
+            let text = literal_text(&term_chars);
+            if num_escaped_chars == 0 {
+                if let Some(operator) = operator_keyword(&text) {
+                    return Err(operator);
+                }
+            }
+            return Ok(match distance_opt {
+                Some(distance) => TermValue::Fuzzy {
+                    term: text,
+                    distance,
+                    prefix: false,
+                },
+                None => TermValue::Text {
+                    phrase: text,
+                    slop: 0,
+                },
+            });
+        }
+        match (distance_opt, term_chars.split_last()) {
+            (Some(distance), Some((TermChar::Wildcard('*'), prefix_chars)))
+                if num_wildcards == 1 && !prefix_chars.is_empty() =>
+            {
+                Ok(TermValue::Fuzzy {
+                    term: literal_text(prefix_chars),
+                    distance,
+                    prefix: true,
+                })
+            }
+            (Some(_), _) => Err("wildcard in fuzzy term"),
+            (None, _) => {
+                let mut pattern = String::with_capacity(term_chars.len());
+                for term_char in term_chars {
+                    match term_char {
+                        TermChar::Literal(c) | TermChar::Escaped(c) => {
+                            if is_wildcard(c) || c == '\\' {
+                                pattern.push('\\');
+                            }
+                            pattern.push(c);
+                        }
+                        TermChar::Wildcard(c) => pattern.push(c),
+                    }
+                }
+                Ok(TermValue::Wildcard(pattern))
+            }
+        }
+    }
+
+    fn into_leaf(self, field_name: Option<String>) -> UserInputLeaf {
+        match self {
+            TermValue::Text { phrase, slop } => UserInputLeaf::from(UserInputLiteral {
+                field_name,
+                phrase,
+                slop,
+            }),
+            TermValue::Fuzzy {
+                term,
+                distance,
+                prefix,
+            } => UserInputLeaf::Fuzzy {
+                field_name,
+                term,
+                distance,
+                prefix,
+            },
+            TermValue::Wildcard(pattern) => UserInputLeaf::Wildcard {
+                field_name,
+                pattern,
+            },
+            TermValue::Regex(pattern) => UserInputLeaf::Regex {
+                field_name,
+                pattern,
+            },
+        }
+    }
+}
+
+parser! {
+    fn fuzzy_distance[I]()(I) -> u32
+    where [I: Stream<Item = char>] {
+        (char('~'), optional(many1(digit())))
+            .and_then(|(_, distance_opt): (char, Option<String>)| {
+                match distance_opt {
+                    Some(distance_str) => distance_str
+                        .parse::<u32>()
+                        .map_err(|_| StreamErrorFor::<I>::unexpected_static_message("distance")),
+                    None => Ok(DEFAULT_FUZZY_DISTANCE),
+                }
+            })
+    }
+}
+
+parser! {
+    fn slop[I]()(I) -> u32
+    where [I: Stream<Item = char>] {
+        (char('~'), many1(digit()))
+            .and_then(|(_, slop_str): (char, String)| {
+                slop_str
+                    .parse::<u32>()
+                    .map_err(|_| StreamErrorFor::<I>::unexpected_static_message("slop"))
+            })
+    }
+}
+
+parser! {
+    fn regex_pattern[I]()(I) -> String
+    where [I: Stream<Item = char>] {
+        let escaped_slash = attempt(string("\\/")).map(|_| "/".to_string());
+        let pattern_char = satisfy(|c| c != '/').map(|c: char| c.to_string());
+        (
+            char('/'),
+            many1(escaped_slash.or(pattern_char)),
+            char('/'),
+        ).map(|(_, pattern_chunks, _): (char, Vec<String>, char)| pattern_chunks.concat())
+    }
+}
+
+parser! {
+    fn term_val[I]()(I) -> TermValue
+    where [I: Stream<Item = char>] {
+        let phrase = (
+            char('"'),
+            many1(escaped_char().or(satisfy(|c| c != '"' && c != '\\'))),
+            char('"'),
+            optional(slop()),
+        )
+            .map(|(_, phrase, _, slop_opt)| TermValue::Text {
+                phrase,
+                slop: slop_opt.unwrap_or(0),
+            });
+        let word_or_fuzzy = (term_chars(), optional(attempt(fuzzy_distance())))
+            .and_then(|(term_chars, distance_opt)| {
+                TermValue::from_term_chars(term_chars, distance_opt)
+                    .map_err(StreamErrorFor::<I>::unexpected_static_message)
+            });
+        let regex = regex_pattern().map(TermValue::Regex);
+        phrase.or(regex).or(word_or_fuzzy)
     }
 }
 
@@ -39,23 +297,15 @@ parser! {
     fn literal[I]()(I) -> UserInputLeaf
     where [I: Stream<Item = char>]
     {
-        let term_val = || {
-            let phrase = (char('"'), many1(satisfy(|c| c != '"')), char('"')).map(|(_, s, _)| s);
-            phrase.or(word())
-        };
-        let term_val_with_field = negative_number().or(term_val());
+        let term_val_with_field = negative_number()
+            .map(|phrase| TermValue::Text { phrase, slop: 0 })
+            .or(term_val());
         let term_query =
-            (field(), char(':'), term_val_with_field).map(|(field_name, _, phrase)| UserInputLiteral {
-                field_name: Some(field_name),
-                phrase,
-            });
-        let term_default_field = term_val().map(|phrase| UserInputLiteral {
-            field_name: None,
-            phrase,
-        });
+            (field(), char(':'), term_val_with_field)
+                .map(|(field_name, _, term_value)| term_value.into_leaf(Some(field_name)));
+        let term_default_field = term_val().map(|term_value| term_value.into_leaf(None));
         attempt(term_query)
             .or(term_default_field)
-            .map(UserInputLeaf::from)
     }
 }
 
@@ -79,7 +329,7 @@ parser! {
     fn range[I]()(I) -> UserInputLeaf
     where [I: Stream<Item = char>] {
         let term_val = || {
-            word().or(negative_number()).or(char('*').map(|_| "*".to_string()))
+            attempt(word()).or(negative_number()).or(char('*').map(|_| "*".to_string()))
         };
         let lower_bound = {
             let excl = (char('{'), term_val()).map(|(_, w)| UserInputBound::Exclusive(w));
@@ -112,7 +362,14 @@ parser! {
          (char('-'), leaf()).map(|(_, expr)| expr.unary(Occur::MustNot) )
         .or((char('+'), leaf()).map(|(_, expr)| expr.unary(Occur::Must) ))
         .or((char('('), parse_to_ast(), char(')')).map(|(_, expr, _)| expr))
-        .or(char('*').map(|_| UserInputAST::from(UserInputLeaf::All) ))
+        .or(attempt(
+            char('*')
+                .skip(not_followed_by(
+                    satisfy(|c: char| is_term_char(c) || is_wildcard(c) || c == '\\')
+                ))
+                .map(|_| UserInputAST::from(UserInputLeaf::All))
+            )
+        )
         .or(attempt(
             (string("NOT"), spaces1(), leaf()).map(|(_, _, expr)| expr.unary(Occur::MustNot))
             )
@@ -125,6 +382,42 @@ parser! {
     }
 }
 
+parser! {
+    fn positive_float_number[I]()(I) -> f32
+    where [I: Stream<Item = char>] {
+        (many1(digit()), optional((char('.'), many1(digit()))))
+            .map(|(int_part, decimal_part_opt): (String, Option<(char, String)>)| {
+                let mut float_str = int_part;
+                if let Some((chr, decimal_str)) = decimal_part_opt {
+                    float_str.push(chr);
+                    float_str.push_str(&decimal_str);
+                }
+                float_str.parse::<f32>().unwrap()
+            })
+    }
+}
+
+parser! {
+    fn boost[I]()(I) -> f32
+    where [I: Stream<Item = char>] {
+        (char('^'), positive_float_number())
+            .map(|(_, boost)| boost)
+    }
+}
+
+parser! {
+    fn boosted_leaf[I]()(I) -> UserInputAST
+    where [I: Stream<Item = char>] {
+        (leaf(), optional(boost()))
+            .map(|(leaf, boost_opt)|
+                match boost_opt {
+                    Some(boost) => UserInputAST::Boost(Box::new(leaf), boost),
+                    None => leaf
+                }
+            )
+    }
+}
+
 enum BinaryOperand {
     Or,
     And,
@@ -163,7 +456,7 @@ parser! {
         (
             attempt(
                 chainl1(
-                    leaf().map(Element::SingleEl),
+                    boosted_leaf().map(Element::SingleEl),
                     binary_operand().map(|op: BinaryOperand|
                         move |left: Element, right: Element| {
                             let mut dnf = left.into_dnf();
@@ -199,7 +492,7 @@ parser! {
                 })
             )
             .or(
-                sep_by(leaf(), spaces())
+                sep_by(boosted_leaf(), spaces())
                 .map(|subqueries: Vec<UserInputAST>| {
                     if subqueries.len() == 1 {
                         subqueries.into_iter().next().unwrap()
@@ -213,6 +506,38 @@ parser! {
     }
 }
 
+/// Splits a query into its top-level leaves, for the lenient mode of the query parser.
+///
+/// Each element is the source text of a leaf, together with its AST if it could be parsed.
+/// A leaf that cannot be parsed spans up to the next whitespace.
+pub fn parse_to_leaves_lenient(query: &str) -> Vec<(&str, Option<UserInputAST>)> {
+    let mut leaves = Vec::new();
+    let mut remaining = query.trim_start();
+    while !remaining.is_empty() {
+        let parsed_leaf_opt = match boosted_leaf().parse(remaining) {
+            Ok((ast, rest))
+                if rest.len() < remaining.len()
+                    && (rest.is_empty() || rest.starts_with(char::is_whitespace)) =>
+            {
+                Some((ast, remaining.len() - rest.len()))
+            }
+            _ => None,
+        };
+        let (ast_opt, len) = match parsed_leaf_opt {
+            Some((ast, len)) => (Some(ast), len),
+            None => {
+                let len = remaining
+                    .find(char::is_whitespace)
+                    .unwrap_or(remaining.len());
+                (None, len)
+            }
+        };
+        leaves.push((&remaining[..len], ast_opt));
+        remaining = remaining[len..].trim_start();
+    }
+    leaves
+}
+
 #[cfg(test)]
 mod test {
 
@@ -228,6 +553,139 @@ mod test {
         assert!(parse_to_ast().parse(query).is_err());
     }
 
+    #[test]
+    fn test_positive_float_number() {
+        fn valid_parse(float_str: &str, expected_val: f32, expected_remaining: &str) {
+            let (val, remaining) = positive_float_number().parse(float_str).unwrap();
+            assert_eq!(val, expected_val);
+            assert_eq!(remaining, expected_remaining);
+        }
+        fn error_parse(float_str: &str) {
+            assert!(positive_float_number().parse(float_str).is_err());
+        }
+        valid_parse("1.0", 1.0f32, "");
+        valid_parse("1", 1.0f32, "");
+        valid_parse("0.234234 aaa", 0.234234f32, " aaa");
+        error_parse(".3332");
+        error_parse("1.");
+        error_parse("-1.");
+    }
+
+    #[test]
+    fn test_parse_query_to_ast_boost() {
+        test_parse_query_to_ast_helper("a^2", "(\"a\")^2");
+        test_parse_query_to_ast_helper("title:a^0.5", "(title:\"a\")^0.5");
+        test_parse_query_to_ast_helper("\"a b\"^3", "(\"a b\")^3");
+        test_parse_query_to_ast_helper("(a b)^2.5", "((\"a\" \"b\"))^2.5");
+        test_parse_query_to_ast_helper("a^1", "(\"a\")^1");
+        test_parse_query_to_ast_helper(
+            "title:rust^3 body:rust",
+            "((title:\"rust\")^3 body:\"rust\")",
+        );
+        test_parse_query_to_ast_helper("a^2 AND b", "(+((\"a\")^2) +(\"b\"))");
+        test_is_parse_err("a^");
+        test_is_parse_err("a^-2");
+    }
+
+    #[test]
+    fn test_parse_query_to_ast_fuzzy() {
+        test_parse_query_to_ast_helper("word~", "\"word\"~1");
+        test_parse_query_to_ast_helper("word~1", "\"word\"~1");
+        test_parse_query_to_ast_helper("title:word~2", "title:\"word\"~2");
+        test_parse_query_to_ast_helper("word*~1", "\"word\"*~1");
+        test_parse_query_to_ast_helper("word~2^3", "(\"word\"~2)^3");
+        test_parse_query_to_ast_helper("+word~ -other", "(+(\"word\"~1) -(\"other\"))");
+        test_parse_query_to_ast_helper("word~12", "\"word\"~12");
+    }
+
+    #[test]
+    fn test_parse_query_to_ast_wildcard() {
+        test_parse_query_to_ast_helper("rus*", "rus*");
+        test_parse_query_to_ast_helper("title:r?st*", "title:r?st*");
+        test_parse_query_to_ast_helper("*ust", "*ust");
+        test_parse_query_to_ast_helper("* rus*", "(* rus*)");
+        test_parse_query_to_ast_helper("title:*", "title:*");
+        test_parse_query_to_ast_helper("rus*^2", "(rus*)^2");
+        test_parse_query_to_ast_helper("-rus* AND a", "(+(-(rus*)) +(\"a\"))");
+        test_is_parse_err("r?st~1");
+    }
+
+    #[test]
+    fn test_parse_query_to_ast_regex() {
+        test_parse_query_to_ast_helper("title:/ab.*c/", "title:/ab.*c/");
+        test_parse_query_to_ast_helper("/ab c/", "/ab c/");
+        test_parse_query_to_ast_helper("/a\\/b/", "/a/b/");
+        test_parse_query_to_ast_helper("/a\\db/", "/a\\db/");
+        test_parse_query_to_ast_helper("+title:/a[bc]/^2 d", "((+(title:/a[bc]/))^2 \"d\")");
+        test_is_parse_err("//");
+        test_is_parse_err("/abc");
+    }
+
+    #[test]
+    fn test_parse_to_leaves_lenient() {
+        let leaves_str = |query: &str| -> Vec<String> {
+            parse_to_leaves_lenient(query)
+                .into_iter()
+                .map(|(text, ast_opt)| match ast_opt {
+                    Some(ast) => format!("{:?}", ast),
+                    None => format!("<{}>", text),
+                })
+                .collect()
+        };
+        assert!(leaves_str("").is_empty());
+        assert_eq!(leaves_str(" a  title:b "), vec!["\"a\"", "title:\"b\""]);
+        assert_eq!(leaves_str("c^ lang"), vec!["<c^>", "\"lang\""]);
+        assert_eq!(leaves_str("foo: bar"), vec!["<foo:>", "\"bar\""]);
+        assert_eq!(leaves_str("\"a b"), vec!["<\"a>", "\"b\""]);
+        assert_eq!(leaves_str("(a b"), vec!["<(a>", "\"b\""]);
+        assert_eq!(
+            leaves_str("-(a b)^2 AND"),
+            vec!["(-((\"a\" \"b\")))^2", "<AND>"]
+        );
+    }
+
+    #[test]
+    fn test_parse_query_to_ast_term_chars() {
+        test_parse_query_to_ast_helper("c#", "\"c#\"");
+        test_parse_query_to_ast_helper("c++ e-mail", "(\"c++\" \"e-mail\")");
+        test_parse_query_to_ast_helper("title:a+b.c", "title:\"a+b.c\"");
+        test_parse_query_to_ast_helper("-e-mail", "-(\"e-mail\")");
+        test_parse_query_to_ast_helper("title:[a-b TO c]", "title:[\"a-b\" TO \"c\"]");
+    }
+
+    #[test]
+    fn test_parse_query_to_ast_escape() {
+        test_parse_query_to_ast_helper("@user\\:matrix.org", "\"@user:matrix.org\"");
+        test_parse_query_to_ast_helper("\\-a", "\"-a\"");
+        test_parse_query_to_ast_helper("\\(a\\)\\^2", "\"(a)^2\"");
+        test_parse_query_to_ast_helper("a\\ b", "\"a b\"");
+        test_parse_query_to_ast_helper("\\AND", "\"AND\"");
+        test_parse_query_to_ast_helper("\"a \\\"b\\\"\"", "\"a \"b\"\"");
+        test_parse_query_to_ast_helper("a\\*b*", "a\\*b*");
+        test_parse_query_to_ast_helper("a\\*~1", "\"a*\"~1");
+        test_parse_query_to_ast_helper("a\\\\?", "a\\\\?");
+    }
+
+    #[test]
+    fn test_parse_query_to_ast_quoted_field() {
+        test_parse_query_to_ast_helper("\"content.body\":hello", "content.body:\"hello\"");
+        test_parse_query_to_ast_helper("\"a\\\"b\":c", "a\"b:\"c\"");
+        test_parse_query_to_ast_helper("\"a b\"", "\"a b\"");
+        test_parse_query_to_ast_helper(
+            "\"content.body\":[a TO b]",
+            "content.body:[\"a\" TO \"b\"]",
+        );
+    }
+
+    #[test]
+    fn test_parse_query_to_ast_slop() {
+        test_parse_query_to_ast_helper("\"a b\"~0", "\"a b\"");
+        test_parse_query_to_ast_helper("\"a b\"~2", "\"a b\"~2");
+        test_parse_query_to_ast_helper("title:\"quick fox\"~3", "title:\"quick fox\"~3");
+        test_parse_query_to_ast_helper("\"a b\"~2^3", "(\"a b\"~2)^3");
+        test_is_parse_err("\"a b\"~");
+    }
+
     #[test]
     fn test_parse_query_to_ast_not_op() {
         assert_eq!(
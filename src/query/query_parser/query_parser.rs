@@ -1,5 +1,5 @@
 use super::logical_ast::*;
-use super::query_grammar::parse_to_ast;
+use super::query_grammar::{parse_to_ast, parse_to_leaves_lenient};
 use super::user_input_ast::*;
 use combine::Parser;
 use core::Index;
@@ -7,19 +7,29 @@ use query::occur::compose_occur;
 use query::query_parser::logical_ast::LogicalAST;
 use query::AllQuery;
 use query::BooleanQuery;
+use query::BoostQuery;
+use query::DisjunctionMaxQuery;
 use query::EmptyQuery;
+use query::ExistsQuery;
+use query::FuzzyTermQuery;
 use query::Occur;
 use query::PhraseQuery;
+use query::PrefixQuery;
 use query::Query;
 use query::RangeQuery;
+use query::RegexQuery;
 use query::TermQuery;
+use query::WildcardQuery;
 use schema::IndexRecordOption;
 use schema::{Field, Schema};
-use schema::{FieldType, Term};
+use schema::{FieldType, Term, Type};
 use std::borrow::Cow;
+use std::cmp;
+use std::collections::HashMap;
 use std::num::ParseIntError;
 use std::ops::Bound;
 use std::str::FromStr;
+use tantivy_fst::Regex;
 use tokenizer::TokenizerManager;
 
 /// Possible error that may happen when parsing a query.
@@ -52,8 +62,24 @@ pub enum QueryParserError {
     RangeMustNotHavePhrase,
     /// The format for the date field is not RFC 3339 compliant.
     DateFormatError(chrono::ParseError),
+    /// A fuzzy, prefix, wildcard or regex term was requested on a field
+    /// that is not a text field.
+    FieldNotText(String),
+    /// The Levenshtein distance requested for a fuzzy term is larger
+    /// than 2, the largest distance supported by `FuzzyTermQuery`.
+    FuzzyDistanceTooLarge(u32),
+    /// The regular expression of a regex term is invalid.
+    /// The two argument strings are the regular expression and the error
+    /// reported while compiling it.
+    InvalidRegex(String, String),
 }
 
+/// Largest Levenshtein distance accepted for a fuzzy term, e.g. `word~2`.
+const MAX_FUZZY_DISTANCE: u8 = 2;
+
+/// Default number of terms a prefix or wildcard term may expand to.
+const DEFAULT_MAX_EXPANSIONS: usize = 1_024;
+
 impl From<ParseIntError> for QueryParserError {
     fn from(err: ParseIntError) -> QueryParserError {
         QueryParserError::ExpectedInt(err)
@@ -84,6 +110,17 @@ fn trim_ast(logical_ast: LogicalAST) -> Option<LogicalAST> {
                 Some(LogicalAST::Clause(trimmed_children))
             }
         }
+        LogicalAST::Boost(ast, boost) => {
+            trim_ast(*ast).map(|trimmed_ast| LogicalAST::Boost(Box::new(trimmed_ast), boost))
+        }
+        LogicalAST::DisjunctionMax(disjuncts, tie_breaker) => {
+            let trimmed_disjuncts = disjuncts.into_iter().flat_map(trim_ast).collect::<Vec<_>>();
+            if trimmed_disjuncts.is_empty() {
+                None
+            } else {
+                Some(LogicalAST::DisjunctionMax(trimmed_disjuncts, tie_breaker))
+            }
+        }
         _ => Some(logical_ast),
     }
 }
@@ -125,10 +162,19 @@ fn trim_ast(logical_ast: LogicalAST) -> Option<LogicalAST> {
 ///
 /// * must terms: By prepending a term by a `+`, a term can be made required for the search.
 ///
+/// * special characters: Terms can contain any character but whitespace and
+///   `( ) [ ] { } " : ^ ~ \ * ?`, and cannot start with `+`, `-` or `/`. e.g. `c++` and `e-mail`
+///   are terms, passed as is to the field tokenizer. Any character, whitespace included,
+///   becomes part of a term or phrase when escaped with a backslash, e.g. `@user\:matrix.org`.
+///   Field names that are not made of letters, digits and `_` can be quoted,
+///   e.g. `"content.body":hello`.
+///
 ///
 /// * phrase terms: Quoted terms become phrase searches on fields that have positions indexed.
 ///   e.g., `title:"Barack Obama"` will only find documents that have "barack" immediately followed
-///   by "obama".
+///   by "obama". A phrase followed by `~` and a number, e.g. `"barack president"~2`,
+///   also matches when its terms are up to that many positions away from each other,
+///   including out of order.
 ///
 /// * range terms: Range searches can be done by specifying the start and end bound. These can be
 ///   inclusive or exclusive. e.g., `title:[a TO c}` will find all documents whose title contains
@@ -139,12 +185,36 @@ fn trim_ast(logical_ast: LogicalAST) -> Option<LogicalAST> {
 ///
 /// *  all docs query: A plain `*` will match all documents in the index.
 ///
+/// * fuzzy terms: A term followed by `~` and an optional Levenshtein distance matches
+///   all of the terms within that distance. e.g. `title:diary~1` matches `dairy`.
+///   The distance defaults to `1` and may not exceed `2`. Writing `dia*~1` makes the term
+///   match the prefix of indexed terms. Fuzzy terms are normalized by the field tokenizer.
+///
+/// * prefix and wildcard terms: In a term, `*` matches any sequence of characters and `?`
+///   matches exactly one character. e.g. `title:rus*` matches `rust` and `rusty`, and
+///   `title:r?st*` also matches `roster`. The parts between wildcards are normalized by the field
+///   tokenizer. Each of these terms matches at most the first 1024 terms of the dictionary,
+///   see [`set_max_expansions`](#method.set_max_expansions).
+///
+/// * regex terms: A regular expression between slashes, e.g. `title:/dia.*y/`, matches the
+///   indexed terms it fully matches. It is not normalized by the field tokenizer, and a `/`
+///   inside of it is written `\/`.
+///
+/// * boosting: A term, a phrase or a parenthesised group can be followed by `^` and a
+///   positive number to multiply its score. e.g. `title:rust^3 body:rust` ranks matches
+///   in the title three times higher than matches in the body.
+///
 #[derive(Clone)]
 pub struct QueryParser {
     schema: Schema,
     default_fields: Vec<Field>,
     conjunction_by_default: bool,
     tokenizer_manager: TokenizerManager,
+    max_expansions: usize,
+    minimum_should_match: usize,
+    disjunction_max_tie_breaker: Option<f32>,
+    field_boosts: HashMap<Field, f32>,
+    field_tokenizers: HashMap<Field, String>,
 }
 
 impl QueryParser {
@@ -162,6 +232,11 @@ impl QueryParser {
             default_fields,
             tokenizer_manager,
             conjunction_by_default: false,
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
+            minimum_should_match: 0,
+            disjunction_max_tie_breaker: None,
+            field_boosts: HashMap::new(),
+            field_tokenizers: HashMap::new(),
         }
     }
 
@@ -182,19 +257,174 @@ impl QueryParser {
         self.conjunction_by_default = true;
     }
 
+    /// Sets the minimum number of optional top-level clauses a document has to match.
+    ///
+    /// By default, the query `happy tax payer` matches the documents containing
+    /// any of the three terms. After calling `.set_minimum_should_match(2)`,
+    /// it only matches the documents containing at least two of them.
+    ///
+    /// The value is capped to the number of optional clauses in the query,
+    /// so that `happy` alone still matches the documents containing `happy`.
+    /// It has no effect on required clauses, e.g. in conjunction mode.
+    pub fn set_minimum_should_match(&mut self, minimum_should_match: usize) {
+        self.minimum_should_match = minimum_should_match;
+    }
+
+    /// Combines the scores of a term searched in several default fields with
+    /// a `DisjunctionMaxQuery` rather than summing them.
+    ///
+    /// By default, `rust` is searched as `title:rust OR body:rust`, and a document
+    /// containing `rust` in both fields gets the sum of both scores. After calling
+    /// `.set_disjunction_max_for_default_fields(0.1)`, it gets the maximum of both
+    /// scores, plus `0.1` times the other one.
+    pub fn set_disjunction_max_for_default_fields(&mut self, tie_breaker: f32) {
+        self.disjunction_max_tie_breaker = Some(tie_breaker);
+    }
+
+    /// Boosts the score of the matches in the given field.
+    ///
+    /// Every term, phrase or range searched in this field, whether the field
+    /// is given explicitly or is one of the default fields, has its score
+    /// multiplied by `boost`. With the default fields `title` and `body`, a
+    /// boost of `2` on `title` makes `rust` rank a match in the title twice
+    /// as high as a match in the body.
+    pub fn set_field_boost(&mut self, field: Field, boost: f32) {
+        self.field_boosts.insert(field, boost);
+    }
+
+    /// Uses the tokenizer registered as `tokenizer_name` in the tokenizer manager
+    /// to analyze the text searched in the given field, instead of the tokenizer
+    /// declared in the field's `TextFieldIndexing`.
+    ///
+    /// This makes it possible to use a different analyzer at query time,
+    /// e.g. one expanding synonyms. The field has to be an indexed text field.
+    pub fn set_field_tokenizer(&mut self, field: Field, tokenizer_name: &str) {
+        self.field_tokenizers
+            .insert(field, tokenizer_name.to_string());
+    }
+
+    /// Sets the maximum number of terms a prefix or wildcard term,
+    /// e.g. `rus*`, may expand to in each segment.
+    ///
+    /// Documents only containing terms beyond this limit, in lexicographic order,
+    /// are not matched. Defaults to 1024.
+    pub fn set_max_expansions(&mut self, max_expansions: usize) {
+        self.max_expansions = max_expansions;
+    }
+
     /// Parse a query
     ///
     /// Note that `parse_query` returns an error if the input
     /// is not a valid query.
     ///
-    /// There is currently no lenient mode for the query parser
-    /// which makes it a bad choice for a public/broad user search engine.
-    ///
-    /// Implementing a lenient mode for this query parser is tracked
-    /// in [Issue 5](https://github.com/fulmicoton/tantivy/issues/5)
+    /// For a public/broad user search engine, prefer
+    /// [`parse_query_lenient`](#method.parse_query_lenient).
     pub fn parse_query(&self, query: &str) -> Result<Box<Query>, QueryParserError> {
         let logical_ast = self.parse_query_to_logical_ast(query)?;
-        Ok(convert_to_query(logical_ast))
+        Ok(self.build_query(logical_ast))
+    }
+
+    /// Parse a query, recovering from the errors in the user input.
+    ///
+    /// Unlike `parse_query`, this never fails. When the query is not valid,
+    /// each of its top-level terms, phrases or groups is parsed on its own.
+    /// The ones that cannot be parsed, or that cannot be searched
+    /// (e.g. because of an unknown field), are searched as plain text in
+    /// the default fields instead. e.g. `c++ foo: (rust` is searched
+    /// like `c foo rust`.
+    ///
+    /// The query is returned together with the problems encountered along the way.
+    /// An empty list of errors means that `parse_query` would have returned
+    /// the same query.
+    pub fn parse_query_lenient(&self, query: &str) -> (Box<Query>, Vec<QueryParserError>) {
+        let (logical_ast, errors) = self.parse_query_to_logical_ast_lenient(query);
+        (self.build_query(logical_ast), errors)
+    }
+
+    /// Converts the logical AST into a query, and applies the minimum number
+    /// of optional clauses to match to its top-level clauses.
+    fn build_query(&self, logical_ast: LogicalAST) -> Box<Query> {
+        let mut query = convert_to_query(logical_ast);
+        if self.minimum_should_match > 0 {
+            if let Some(boolean_query) = query.downcast_mut::<BooleanQuery>() {
+                let num_should_clauses = boolean_query
+                    .clauses()
+                    .iter()
+                    .filter(|&&(occur, _)| occur == Occur::Should)
+                    .count();
+                boolean_query.set_minimum_should_match(cmp::min(
+                    self.minimum_should_match,
+                    num_should_clauses,
+                ));
+            }
+        }
+        query
+    }
+
+    /// Parse the user query into an AST, recovering from errors.
+    fn parse_query_to_logical_ast_lenient(
+        &self,
+        query: &str,
+    ) -> (LogicalAST, Vec<QueryParserError>) {
+        if let Ok((user_input_ast, remaining)) = parse_to_ast().parse(query) {
+            if remaining.trim().is_empty() {
+                if let Ok(logical_ast) = self.compute_logical_ast(user_input_ast) {
+                    return (logical_ast, Vec::new());
+                }
+            }
+        }
+        let default_occur = self.default_occur();
+        let mut errors = Vec::new();
+        let mut clauses: Vec<(Occur, LogicalAST)> = Vec::new();
+        for (text, user_input_ast_opt) in parse_to_leaves_lenient(query) {
+            let (occur, ast) = match user_input_ast_opt
+                .ok_or(QueryParserError::SyntaxError)
+                .and_then(|user_input_ast| self.compute_logical_ast_with_occur(user_input_ast))
+            {
+                Ok(occur_and_ast) => occur_and_ast,
+                Err(error) => {
+                    errors.push(error);
+                    if is_operator_keyword(text) {
+                        continue;
+                    }
+                    (
+                        Occur::Should,
+                        self.compute_logical_ast_for_text(text, &mut errors),
+                    )
+                }
+            };
+            clauses.push((compose_occur(default_occur, occur), ast));
+        }
+        if clauses.len() == 1 && clauses[0].0 == Occur::MustNot {
+            errors.push(QueryParserError::AllButQueryForbidden);
+            clauses.clear();
+        }
+        (LogicalAST::Clause(clauses), errors)
+    }
+
+    /// Searches the terms of a text as plain terms in the default fields,
+    /// ignoring the fields the text cannot be searched in.
+    fn compute_logical_ast_for_text(
+        &self,
+        text: &str,
+        errors: &mut Vec<QueryParserError>,
+    ) -> LogicalAST {
+        if self.default_fields.is_empty() {
+            errors.push(QueryParserError::NoDefaultFieldDeclared);
+        }
+        let mut asts: Vec<LogicalAST> = Vec::new();
+        for &field in &self.default_fields {
+            match self.compute_terms_for_string(field, text) {
+                Ok(terms) => {
+                    for (_, term) in terms {
+                        let ast = LogicalAST::from(LogicalLiteral::Term(term));
+                        asts.push(self.boost_field(field, ast));
+                    }
+                }
+                Err(error) => errors.push(error),
+            }
+        }
+        LogicalAST::Clause(asts.into_iter().map(|ast| (Occur::Should, ast)).collect())
     }
 
     /// Parse the user query into an AST.
@@ -215,10 +445,19 @@ impl QueryParser {
         &self,
         user_input_ast: UserInputAST,
     ) -> Result<LogicalAST, QueryParserError> {
+        let mut is_clause = false;
+        if let UserInputAST::Clause(_) = user_input_ast {
+            is_clause = true;
+        }
         let (occur, ast) = self.compute_logical_ast_with_occur(user_input_ast)?;
         if occur == Occur::MustNot {
             return Err(QueryParserError::AllButQueryForbidden);
         }
+        if self.minimum_should_match > 0 && !is_clause {
+            // A single term is searched through a disjunction over the default fields,
+            // which must not be mistaken for the top-level clauses of the query.
+            return Ok(LogicalAST::Clause(vec![(Occur::Should, ast)]));
+        }
         Ok(ast)
     }
 
@@ -228,12 +467,20 @@ impl QueryParser {
         phrase: &str,
     ) -> Result<Vec<(usize, Term)>, QueryParserError> {
         let field_entry = self.schema.get_field_entry(field);
-        let field_type = field_entry.field_type();
-        if !field_type.is_indexed() {
+        if !field_entry.field_type().is_indexed() {
             let field_name = field_entry.name().to_string();
             return Err(QueryParserError::FieldNotIndexed(field_name));
         }
-        match *field_type {
+        self.compute_terms_for_value(field, phrase)
+    }
+
+    /// Converts the given text into the terms of a field, whether the field is indexed or not.
+    fn compute_terms_for_value(
+        &self,
+        field: Field,
+        phrase: &str,
+    ) -> Result<Vec<(usize, Term)>, QueryParserError> {
+        match *self.schema.get_field_entry(field).field_type() {
             FieldType::I64(_) => {
                 let val: i64 = i64::from_str(phrase)?;
                 let term = Term::from_field_i64(field, val);
@@ -251,69 +498,206 @@ impl QueryParser {
                 let term = Term::from_field_u64(field, val);
                 Ok(vec![(0, term)])
             }
-            FieldType::Str(ref str_options) => {
-                if let Some(option) = str_options.get_indexing_options() {
-                    let mut tokenizer =
-                        self.tokenizer_manager
-                            .get(option.tokenizer())
-                            .ok_or_else(|| {
-                                QueryParserError::UnknownTokenizer(
-                                    field_entry.name().to_string(),
-                                    option.tokenizer().to_string(),
-                                )
-                            })?;
-                    let mut terms: Vec<(usize, Term)> = Vec::new();
-                    let mut token_stream = tokenizer.token_stream(phrase);
-                    token_stream.process(&mut |token| {
-                        let term = Term::from_field_text(field, &token.text);
-                        terms.push((token.position, term));
-                    });
-                    if terms.is_empty() {
-                        Ok(vec![])
-                    } else if terms.len() == 1 {
-                        Ok(terms)
-                    } else {
-                        let field_entry = self.schema.get_field_entry(field);
-                        let field_type = field_entry.field_type();
-                        if let Some(index_record_option) = field_type.get_index_record_option() {
-                            if index_record_option.has_positions() {
-                                Ok(terms)
-                            } else {
-                                let fieldname = self.schema.get_field_name(field).to_string();
-                                Err(QueryParserError::FieldDoesNotHavePositionsIndexed(
-                                    fieldname,
-                                ))
-                            }
+            FieldType::Str(_) => {
+                let terms = self.tokenize(field, phrase)?;
+                if terms.len() <= 1 {
+                    Ok(terms)
+                } else {
+                    let field_entry = self.schema.get_field_entry(field);
+                    let field_type = field_entry.field_type();
+                    if let Some(index_record_option) = field_type.get_index_record_option() {
+                        if index_record_option.has_positions() {
+                            Ok(terms)
                         } else {
                             let fieldname = self.schema.get_field_name(field).to_string();
-                            Err(QueryParserError::FieldNotIndexed(fieldname))
+                            Err(QueryParserError::FieldDoesNotHavePositionsIndexed(
+                                fieldname,
+                            ))
                         }
+                    } else {
+                        let fieldname = self.schema.get_field_name(field).to_string();
+                        Err(QueryParserError::FieldNotIndexed(fieldname))
                     }
-                } else {
-                    // This should have been seen earlier really.
-                    Err(QueryParserError::FieldNotIndexed(
-                        field_entry.name().to_string(),
-                    ))
                 }
             }
             FieldType::HierarchicalFacet => Ok(vec![(0, Term::from_field_text(field, phrase))]),
-            FieldType::Bytes => {
+            FieldType::Bytes | FieldType::Vector(_) => {
                 let field_name = self.schema.get_field_name(field).to_string();
                 Err(QueryParserError::FieldNotIndexed(field_name))
             }
         }
     }
 
+    /// Runs the given text through the tokenizer of a text field, i.e. the
+    /// tokenizer set with `set_field_tokenizer` or else the one used for indexing.
+    ///
+    /// Returns the resulting terms together with their positions.
+    fn tokenize(&self, field: Field, text: &str) -> Result<Vec<(usize, Term)>, QueryParserError> {
+        let field_entry = self.schema.get_field_entry(field);
+        let indexing_options_opt = match *field_entry.field_type() {
+            FieldType::Str(ref str_options) => str_options.get_indexing_options(),
+            _ => None,
+        };
+        let option = indexing_options_opt
+            .ok_or_else(|| QueryParserError::FieldNotIndexed(field_entry.name().to_string()))?;
+        let tokenizer_name = self
+            .field_tokenizers
+            .get(&field)
+            .map(String::as_str)
+            .unwrap_or_else(|| option.tokenizer());
+        let tokenizer = self.tokenizer_manager.get(tokenizer_name).ok_or_else(|| {
+            QueryParserError::UnknownTokenizer(
+                field_entry.name().to_string(),
+                tokenizer_name.to_string(),
+            )
+        })?;
+        let mut terms: Vec<(usize, Term)> = Vec::new();
+        let mut token_stream = tokenizer.token_stream(text);
+        token_stream.process(&mut |token| {
+            let term = Term::from_field_text(field, &token.text);
+            terms.push((token.position, term));
+        });
+        Ok(terms)
+    }
+
+    fn compute_logical_ast_for_fuzzy(
+        &self,
+        field: Field,
+        text: &str,
+        distance: u32,
+        prefix: bool,
+    ) -> Result<Vec<LogicalLiteral>, QueryParserError> {
+        let field_entry = self.schema.get_field_entry(field);
+        if field_entry.field_type().value_type() != Type::Str {
+            return Err(QueryParserError::FieldNotText(
+                field_entry.name().to_string(),
+            ));
+        }
+        if distance > u32::from(MAX_FUZZY_DISTANCE) {
+            return Err(QueryParserError::FuzzyDistanceTooLarge(distance));
+        }
+        let literals = self
+            .tokenize(field, text)?
+            .into_iter()
+            .map(|(_, term)| LogicalLiteral::Fuzzy {
+                term,
+                distance: distance as u8,
+                prefix,
+            })
+            .collect();
+        Ok(literals)
+    }
+
+    fn compute_logical_ast_for_wildcard(
+        &self,
+        field: Field,
+        pattern: &str,
+    ) -> Result<LogicalLiteral, QueryParserError> {
+        let field_entry = self.schema.get_field_entry(field);
+        if field_entry.field_type().value_type() != Type::Str {
+            return Err(QueryParserError::FieldNotText(
+                field_entry.name().to_string(),
+            ));
+        }
+        let mut segments = split_wildcards(pattern);
+        // Only the normalizations preserving the length of a segment, like lowercasing,
+        // are applied, so that the punctuation of the pattern is not lost.
+        for &mut (ref mut segment, _) in &mut segments {
+            if !segment.is_empty() {
+                let terms = self.tokenize(field, segment)?;
+                if let [(_, ref term)] = terms[..] {
+                    if term.text().chars().count() == segment.chars().count() {
+                        *segment = term.text().to_string();
+                    }
+                }
+            }
+        }
+        if let [(ref prefix_text, Some('*')), (ref suffix, None)] = segments[..] {
+            if suffix.is_empty() {
+                let prefix = Term::from_field_text(field, prefix_text);
+                return Ok(LogicalLiteral::Prefix {
+                    prefix,
+                    max_expansions: self.max_expansions,
+                });
+            }
+        }
+        let mut normalized_pattern = String::with_capacity(pattern.len());
+        for (segment, wildcard_opt) in segments {
+            for c in segment.chars() {
+                if c == '*' || c == '?' || c == '\\' {
+                    normalized_pattern.push('\\');
+                }
+                normalized_pattern.push(c);
+            }
+            normalized_pattern.extend(wildcard_opt);
+        }
+        Ok(LogicalLiteral::Wildcard {
+            field,
+            pattern: normalized_pattern,
+            max_expansions: self.max_expansions,
+        })
+    }
+
+    fn compute_logical_ast_for_regex(
+        &self,
+        field: Field,
+        pattern: &str,
+    ) -> Result<LogicalLiteral, QueryParserError> {
+        let field_entry = self.schema.get_field_entry(field);
+        match *field_entry.field_type() {
+            FieldType::Str(ref str_options) => {
+                if str_options.get_indexing_options().is_none() {
+                    return Err(QueryParserError::FieldNotIndexed(
+                        field_entry.name().to_string(),
+                    ));
+                }
+            }
+            _ => {
+                return Err(QueryParserError::FieldNotText(
+                    field_entry.name().to_string(),
+                ))
+            }
+        }
+        Regex::new(pattern).map_err(|regex_error| {
+            QueryParserError::InvalidRegex(pattern.to_string(), regex_error.to_string())
+        })?;
+        Ok(LogicalLiteral::Regex {
+            field,
+            pattern: pattern.to_string(),
+        })
+    }
+
     fn compute_logical_ast_for_leaf(
         &self,
         field: Field,
         phrase: &str,
+        slop: u32,
     ) -> Result<Option<LogicalLiteral>, QueryParserError> {
         let terms = self.compute_terms_for_string(field, phrase)?;
         match &terms[..] {
             [] => Ok(None),
             [(_, term)] => Ok(Some(LogicalLiteral::Term(term.clone()))),
-            _ => Ok(Some(LogicalLiteral::Phrase(terms.clone()))),
+            _ => Ok(Some(LogicalLiteral::Phrase(terms.clone(), slop))),
+        }
+    }
+
+    /// Wraps the AST of a leaf searching `field` into the boost
+    /// configured for this field, if any.
+    fn boost_field(&self, field: Field, ast: LogicalAST) -> LogicalAST {
+        match self.field_boosts.get(&field) {
+            Some(&boost) => LogicalAST::Boost(Box::new(ast), boost),
+            None => ast,
+        }
+    }
+
+    /// Combines the ASTs searching the same leaf in each of its fields.
+    fn combine_field_asts(&self, mut asts: Vec<LogicalAST>) -> LogicalAST {
+        if asts.len() == 1 {
+            return asts.pop().unwrap();
+        }
+        match self.disjunction_max_tie_breaker {
+            Some(tie_breaker) => LogicalAST::DisjunctionMax(asts, tie_breaker),
+            None => LogicalAST::Clause(asts.into_iter().map(|ast| (Occur::Should, ast)).collect()),
         }
     }
 
@@ -333,7 +717,13 @@ impl QueryParser {
         if bound.term_str() == "*" {
             return Ok(Bound::Unbounded);
         }
-        let terms = self.compute_terms_for_string(field, bound.term_str())?;
+        let field_entry = self.schema.get_field_entry(field);
+        // Ranges over a fast field which is not indexed are served by the fast field.
+        let terms = if !field_entry.is_indexed() && field_entry.is_int_fast() {
+            self.compute_terms_for_value(field, bound.term_str())?
+        } else {
+            self.compute_terms_for_string(field, bound.term_str())?
+        };
         if terms.len() != 1 {
             return Err(QueryParserError::RangeMustNotHavePhrase);
         }
@@ -380,6 +770,10 @@ impl QueryParser {
                     self.compute_logical_ast_with_occur(*subquery)?;
                 Ok((compose_occur(left_occur, right_occur), logical_sub_queries))
             }
+            UserInputAST::Boost(ast, boost) => {
+                let (occur, ast_without_boost) = self.compute_logical_ast_with_occur(*ast)?;
+                Ok((occur, LogicalAST::Boost(Box::new(ast_without_boost), boost)))
+            }
             UserInputAST::Leaf(leaf) => {
                 let result_ast = self.compute_logical_ast_from_leaf(*leaf)?;
                 Ok((Occur::Should, result_ast))
@@ -411,16 +805,77 @@ impl QueryParser {
                 };
                 let mut asts: Vec<LogicalAST> = Vec::new();
                 for (field, phrase) in term_phrases {
-                    if let Some(ast) = self.compute_logical_ast_for_leaf(field, &phrase)? {
-                        asts.push(LogicalAST::Leaf(Box::new(ast)));
+                    if let Some(ast) =
+                        self.compute_logical_ast_for_leaf(field, &phrase, literal.slop)?
+                    {
+                        asts.push(self.boost_field(field, LogicalAST::Leaf(Box::new(ast))));
                     }
                 }
-                let result_ast: LogicalAST = if asts.len() == 1 {
-                    asts.into_iter().next().unwrap()
-                } else {
-                    LogicalAST::Clause(asts.into_iter().map(|ast| (Occur::Should, ast)).collect())
-                };
-                Ok(result_ast)
+                Ok(self.combine_field_asts(asts))
+            }
+            UserInputLeaf::Fuzzy {
+                field_name,
+                term,
+                distance,
+                prefix,
+            } => {
+                let fields = self.resolved_fields(&field_name)?;
+                let mut asts: Vec<LogicalAST> = Vec::new();
+                for &field in fields.iter() {
+                    let mut field_asts: Vec<LogicalAST> = self
+                        .compute_logical_ast_for_fuzzy(field, &term, distance, prefix)?
+                        .into_iter()
+                        .map(|literal| self.boost_field(field, LogicalAST::from(literal)))
+                        .collect();
+                    match field_asts.len() {
+                        0 => {}
+                        1 => asts.extend(field_asts.pop()),
+                        _ => asts.push(LogicalAST::Clause(
+                            field_asts
+                                .into_iter()
+                                .map(|ast| (Occur::Should, ast))
+                                .collect(),
+                        )),
+                    }
+                }
+                Ok(self.combine_field_asts(asts))
+            }
+            UserInputLeaf::Wildcard {
+                field_name,
+                pattern,
+            } => {
+                let fields = self.resolved_fields(&field_name)?;
+                let mut asts: Vec<LogicalAST> = Vec::new();
+                for &field in fields.iter() {
+                    // `field:*` matches the documents having any value for the field,
+                    // whatever its type.
+                    let literal = if pattern == "*" {
+                        let field_entry = self.schema.get_field_entry(field);
+                        let is_bytes = *field_entry.field_type() == FieldType::Bytes;
+                        if !field_entry.is_indexed() && !field_entry.is_int_fast() && !is_bytes {
+                            return Err(QueryParserError::FieldNotIndexed(
+                                field_entry.name().to_string(),
+                            ));
+                        }
+                        LogicalLiteral::Exists(field)
+                    } else {
+                        self.compute_logical_ast_for_wildcard(field, &pattern)?
+                    };
+                    asts.push(self.boost_field(field, LogicalAST::from(literal)));
+                }
+                Ok(self.combine_field_asts(asts))
+            }
+            UserInputLeaf::Regex {
+                field_name,
+                pattern,
+            } => {
+                let fields = self.resolved_fields(&field_name)?;
+                let mut asts: Vec<LogicalAST> = Vec::new();
+                for &field in fields.iter() {
+                    let literal = self.compute_logical_ast_for_regex(field, &pattern)?;
+                    asts.push(self.boost_field(field, LogicalAST::from(literal)));
+                }
+                Ok(self.combine_field_asts(asts))
             }
             UserInputLeaf::All => Ok(LogicalAST::Leaf(Box::new(LogicalLiteral::All))),
             UserInputLeaf::Range {
@@ -429,41 +884,89 @@ impl QueryParser {
                 upper,
             } => {
                 let fields = self.resolved_fields(&field)?;
-                let mut clauses = fields
+                let clauses = fields
                     .iter()
                     .map(|&field| {
                         let field_entry = self.schema.get_field_entry(field);
                         let value_type = field_entry.field_type().value_type();
-                        Ok(LogicalAST::Leaf(Box::new(LogicalLiteral::Range {
+                        let range = LogicalLiteral::Range {
                             field,
                             value_type,
                             lower: self.resolve_bound(field, &lower)?,
                             upper: self.resolve_bound(field, &upper)?,
-                        })))
+                        };
+                        Ok(self.boost_field(field, LogicalAST::from(range)))
                     })
                     .collect::<Result<Vec<_>, QueryParserError>>()?;
-                let result_ast = if clauses.len() == 1 {
-                    clauses.pop().unwrap()
-                } else {
-                    LogicalAST::Clause(
-                        clauses
-                            .into_iter()
-                            .map(|clause| (Occur::Should, clause))
-                            .collect(),
-                    )
-                };
-                Ok(result_ast)
+                Ok(self.combine_field_asts(clauses))
             }
         }
     }
 }
 
+/// Returns true if the text is one of the boolean operators of the query grammar.
+fn is_operator_keyword(text: &str) -> bool {
+    text == "AND" || text == "OR" || text == "NOT"
+}
+
+/// Splits a wildcard pattern into its unescaped literal segments,
+/// each followed by the wildcard ending it, if any.
+fn split_wildcards(pattern: &str) -> Vec<(String, Option<char>)> {
+    let mut segments = Vec::new();
+    let mut segment = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => segment.extend(chars.next()),
+            '*' | '?' => {
+                segments.push((segment.clone(), Some(c)));
+                segment.clear();
+            }
+            _ => segment.push(c),
+        }
+    }
+    segments.push((segment, None));
+    segments
+}
+
 fn convert_literal_to_query(logical_literal: LogicalLiteral) -> Box<Query> {
     match logical_literal {
         LogicalLiteral::Term(term) => Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs)),
-        LogicalLiteral::Phrase(term_with_offsets) => {
-            Box::new(PhraseQuery::new_with_offset(term_with_offsets))
+        LogicalLiteral::Fuzzy {
+            term,
+            distance,
+            prefix,
+        } => {
+            if prefix {
+                Box::new(FuzzyTermQuery::new_prefix(term, distance, true))
+            } else {
+                Box::new(FuzzyTermQuery::new(term, distance, true))
+            }
+        }
+        LogicalLiteral::Phrase(term_with_offsets, slop) => {
+            let mut phrase_query = PhraseQuery::new_with_offset(term_with_offsets);
+            phrase_query.set_slop(slop);
+            Box::new(phrase_query)
+        }
+        LogicalLiteral::Prefix {
+            prefix,
+            max_expansions,
+        } => {
+            let mut prefix_query = PrefixQuery::new(prefix);
+            prefix_query.set_max_expansions(max_expansions);
+            Box::new(prefix_query)
         }
+        LogicalLiteral::Wildcard {
+            field,
+            pattern,
+            max_expansions,
+        } => {
+            let mut wildcard_query = WildcardQuery::new(pattern, field);
+            wildcard_query.set_max_expansions(max_expansions);
+            Box::new(wildcard_query)
+        }
+        LogicalLiteral::Regex { field, pattern } => Box::new(RegexQuery::new(pattern, field)),
+        LogicalLiteral::Exists(field) => Box::new(ExistsQuery::new(field)),
         LogicalLiteral::Range {
             field,
             value_type,
@@ -489,6 +992,17 @@ fn convert_to_query(logical_ast: LogicalAST) -> Box<Query> {
             );
             Box::new(BooleanQuery::from(occur_subqueries))
         }
+        Some(LogicalAST::Boost(ast, boost)) => {
+            let query = convert_to_query(*ast);
+            Box::new(BoostQuery::new(query, boost))
+        }
+        Some(LogicalAST::DisjunctionMax(disjuncts, tie_breaker)) => {
+            let disjuncts = disjuncts.into_iter().map(convert_to_query).collect();
+            Box::new(DisjunctionMaxQuery::with_tie_breaker(
+                disjuncts,
+                tie_breaker,
+            ))
+        }
         Some(LogicalAST::Leaf(trimmed_logical_literal)) => {
             convert_literal_to_query(*trimmed_logical_literal)
         }
@@ -501,11 +1015,22 @@ mod test {
     use super::super::logical_ast::*;
     use super::QueryParser;
     use super::QueryParserError;
+    use collector::TopDocs;
+    use query::BoostQuery;
+    use query::DisjunctionMaxQuery;
+    use query::ExistsQuery;
+    use query::FuzzyTermQuery;
+    use query::PhraseQuery;
+    use query::PrefixQuery;
     use query::Query;
+    use query::RegexQuery;
+    use query::WildcardQuery;
     use schema::Field;
     use schema::{IndexRecordOption, TextFieldIndexing, TextOptions};
     use schema::{Schema, Term, INDEXED, STORED, STRING, TEXT};
+    use tests::assert_nearly_equals;
     use tokenizer::{LowerCaser, SimpleTokenizer, StopWordFilter, Tokenizer, TokenizerManager};
+    use DocAddress;
     use Index;
 
     fn make_query_parser() -> QueryParser {
@@ -715,6 +1240,529 @@ mod test {
         test_parse_query_to_logical_ast_helper("*", "*", false);
     }
 
+    #[test]
+    pub fn test_parse_query_to_ast_boost() {
+        test_parse_query_to_logical_ast_helper(
+            "title:toto^2",
+            "Term([0, 0, 0, 0, 116, 111, 116, 111])^2",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "title:toto^0.5 titi",
+            "(Term([0, 0, 0, 0, 116, 111, 116, 111])^0.5 \
+             (Term([0, 0, 0, 0, 116, 105, 116, 105]) \
+             Term([0, 0, 0, 1, 116, 105, 116, 105])))",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "+(title:a title:b)^2",
+            "(Term([0, 0, 0, 0, 97]) Term([0, 0, 0, 0, 98]))^2",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "-title:a^2 titi",
+            "(-Term([0, 0, 0, 0, 97])^2 \
+             (Term([0, 0, 0, 0, 116, 105, 116, 105]) \
+             Term([0, 0, 0, 1, 116, 105, 116, 105])))",
+            false,
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_to_ast_slop() {
+        test_parse_query_to_logical_ast_helper(
+            "title:\"a b\"~2",
+            "\"[(0, Term([0, 0, 0, 0, 97])), \
+             (1, Term([0, 0, 0, 0, 98]))]\"~2",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper("title:\"a\"~2", "Term([0, 0, 0, 0, 97])", false);
+        let query_parser = make_query_parser();
+        let query = query_parser.parse_query("title:\"a b\"~3").unwrap();
+        let phrase_query = query.downcast_ref::<PhraseQuery>().unwrap();
+        assert_eq!(phrase_query.slop(), 3);
+    }
+
+    #[test]
+    pub fn test_parse_query_to_ast_fuzzy() {
+        test_parse_query_to_logical_ast_helper(
+            "title:Toto~",
+            "Term([0, 0, 0, 0, 116, 111, 116, 111])~1",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "toto~2",
+            "(Term([0, 0, 0, 0, 116, 111, 116, 111])~2 \
+             Term([0, 0, 0, 1, 116, 111, 116, 111])~2)",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "title:toto*~1",
+            "Term([0, 0, 0, 0, 116, 111, 116, 111])*~1",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper("with_stop_words:the~1", "<emptyclause>", false);
+    }
+
+    #[test]
+    pub fn test_parse_query_fuzzy_errors() {
+        let query_parser = make_query_parser();
+        assert_eq!(
+            query_parser.parse_query("title:toto~3").unwrap_err(),
+            QueryParserError::FuzzyDistanceTooLarge(3)
+        );
+        assert_eq!(
+            query_parser.parse_query("signed:2~1").unwrap_err(),
+            QueryParserError::FieldNotText("signed".to_string())
+        );
+        assert_eq!(
+            query_parser
+                .parse_query("notindexed_text:toto~1")
+                .unwrap_err(),
+            QueryParserError::FieldNotIndexed("notindexed_text".to_string())
+        );
+        let query = query_parser.parse_query("title:toto~1").unwrap();
+        assert!(query.is::<FuzzyTermQuery>());
+    }
+
+    #[test]
+    pub fn test_parse_query_fuzzy_search() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(doc!(title=>"The Diary of Muadib"));
+        index_writer.add_document(doc!(title=>"A Dairy Cow"));
+        index_writer.add_document(doc!(title=>"The Name of the Wind"));
+        index_writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let count = |query_str: &str| {
+            let query = query_parser.parse_query(query_str).unwrap();
+            query.count(&searcher).unwrap()
+        };
+        assert_eq!(count("Diary"), 1);
+        assert_eq!(count("Diary~1"), 2);
+        assert_eq!(count("title:Diar~"), 1);
+        assert_eq!(count("Dia*~1"), 2);
+    }
+
+    #[test]
+    pub fn test_parse_query_to_ast_wildcard() {
+        test_parse_query_to_logical_ast_helper(
+            "title:Toto*",
+            "Term([0, 0, 0, 0, 116, 111, 116, 111])*",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "toto*",
+            "(Term([0, 0, 0, 0, 116, 111, 116, 111])* \
+             Term([0, 0, 0, 1, 116, 111, 116, 111])*)",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper("title:T?to*", "Field(0):t?to*", false);
+        test_parse_query_to_logical_ast_helper("title:*Toto", "Field(0):*toto", false);
+        test_parse_query_to_logical_ast_helper("title:*", "Field(0):*", false);
+    }
+
+    #[test]
+    pub fn test_parse_query_wildcard_errors() {
+        let query_parser = make_query_parser();
+        assert_eq!(
+            query_parser.parse_query("signed:2*").unwrap_err(),
+            QueryParserError::FieldNotText("signed".to_string())
+        );
+        assert_eq!(
+            query_parser
+                .parse_query("notindexed_text:to?o")
+                .unwrap_err(),
+            QueryParserError::FieldNotIndexed("notindexed_text".to_string())
+        );
+        assert!(query_parser
+            .parse_query("title:toto*")
+            .unwrap()
+            .is::<PrefixQuery>());
+        assert!(query_parser
+            .parse_query("title:to?o")
+            .unwrap()
+            .is::<WildcardQuery>());
+    }
+
+    #[test]
+    pub fn test_parse_query_exists() {
+        let query_parser = make_query_parser();
+        assert!(query_parser
+            .parse_query("title:*")
+            .unwrap()
+            .is::<ExistsQuery>());
+        assert!(query_parser
+            .parse_query("signed:*")
+            .unwrap()
+            .is::<ExistsQuery>());
+        assert_eq!(
+            query_parser.parse_query("notindexed_text:*").unwrap_err(),
+            QueryParserError::FieldNotIndexed("notindexed_text".to_string())
+        );
+        test_parse_query_to_logical_ast_helper(
+            "title:a -text:*",
+            "(Term([0, 0, 0, 0, 97]) -Field(1):*)",
+            false,
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_wildcard_search() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(doc!(title=>"Rust"));
+        index_writer.add_document(doc!(title=>"Rusty roster"));
+        index_writer.add_document(doc!(title=>"Rest"));
+        index_writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let mut query_parser = QueryParser::for_index(&index, vec![title]);
+        {
+            let count = |query_str: &str| {
+                let query = query_parser.parse_query(query_str).unwrap();
+                query.count(&searcher).unwrap()
+            };
+            assert_eq!(count("Rus*"), 2);
+            assert_eq!(count("r?st"), 2);
+            assert_eq!(count("title:r?st*"), 3);
+            assert_eq!(count("*st"), 2);
+            assert_eq!(count("*"), 3);
+            assert_eq!(count("title:*"), 3);
+        }
+        query_parser.set_max_expansions(1);
+        let query = query_parser.parse_query("r*").unwrap();
+        assert_eq!(query.count(&searcher).unwrap(), 1);
+    }
+
+    #[test]
+    pub fn test_parse_query_to_ast_regex() {
+        test_parse_query_to_logical_ast_helper("title:/to.*o/", "Field(0):/to.*o/", false);
+        test_parse_query_to_logical_ast_helper(
+            "/To[t]o/",
+            "(Field(0):/To[t]o/ Field(1):/To[t]o/)",
+            false,
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_regex_errors() {
+        let query_parser = make_query_parser();
+        assert_matches!(
+            query_parser.parse_query("title:/to[to/"),
+            Err(QueryParserError::InvalidRegex(ref pattern, _)) if pattern == "to[to"
+        );
+        assert_eq!(
+            query_parser.parse_query("signed:/2/").unwrap_err(),
+            QueryParserError::FieldNotText("signed".to_string())
+        );
+        assert_eq!(
+            query_parser
+                .parse_query("notindexed_text:/toto/")
+                .unwrap_err(),
+            QueryParserError::FieldNotIndexed("notindexed_text".to_string())
+        );
+        assert!(query_parser
+            .parse_query("title:/to.o/")
+            .unwrap()
+            .is::<RegexQuery>());
+    }
+
+    #[test]
+    pub fn test_parse_query_regex_search() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(doc!(title=>"The Diary of Muadib"));
+        index_writer.add_document(doc!(title=>"A Dairy Cow"));
+        index_writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let count = |query_str: &str| {
+            let query = query_parser.parse_query(query_str).unwrap();
+            query.count(&searcher).unwrap()
+        };
+        assert_eq!(count("title:/d[ai]+ry/"), 2);
+        assert_eq!(count("/dia.*/"), 1);
+        assert_eq!(count("/dia/"), 0);
+        assert_eq!(count("/Dia.*/"), 0);
+    }
+
+    #[test]
+    pub fn test_parse_query_field_boost() {
+        let mut query_parser = make_query_parser();
+        let title = query_parser.schema.get_field("title").unwrap();
+        query_parser.set_field_boost(title, 2.0);
+        let logical_ast = query_parser.parse_query_to_logical_ast("toto").unwrap();
+        assert_eq!(
+            format!("{:?}", logical_ast),
+            "(Term([0, 0, 0, 0, 116, 111, 116, 111])^2 \
+             Term([0, 0, 0, 1, 116, 111, 116, 111]))"
+        );
+        let logical_ast = query_parser
+            .parse_query_to_logical_ast("title:toto* text:[a TO b]")
+            .unwrap();
+        assert_eq!(
+            format!("{:?}", logical_ast),
+            "(Term([0, 0, 0, 0, 116, 111, 116, 111])*^2 \
+             (Included(Term([0, 0, 0, 1, 97])) TO Included(Term([0, 0, 0, 1, 98]))))"
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_field_boost_search() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(doc!(title=>"rust", body=>"a b"));
+        index_writer.add_document(doc!(title=>"a b", body=>"rust"));
+        index_writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let mut query_parser = QueryParser::for_index(&index, vec![title, body]);
+        let top_doc = |query_parser: &QueryParser| {
+            let query = query_parser.parse_query("rust").unwrap();
+            let top_docs = searcher.search(&query, &TopDocs::with_limit(1)).unwrap();
+            top_docs[0].1
+        };
+        query_parser.set_field_boost(title, 2.0);
+        assert_eq!(top_doc(&query_parser), DocAddress(0, 0));
+        query_parser.set_field_boost(title, 1.0);
+        query_parser.set_field_boost(body, 2.0);
+        assert_eq!(top_doc(&query_parser), DocAddress(0, 1));
+    }
+
+    #[test]
+    pub fn test_parse_query_minimum_should_match() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(doc!(title=>"happy", body=>"tax"));
+        index_writer.add_document(doc!(title=>"happy tax payer"));
+        index_writer.add_document(doc!(body=>"payer"));
+        index_writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let mut query_parser = QueryParser::for_index(&index, vec![title, body]);
+        let search = |query_parser: &QueryParser, query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            let mut docs: Vec<u32> = searcher
+                .search(&query, &TopDocs::with_limit(10))
+                .unwrap()
+                .into_iter()
+                .map(|(_, doc_address)| doc_address.doc())
+                .collect();
+            docs.sort();
+            docs
+        };
+        assert_eq!(search(&query_parser, "happy tax payer"), vec![0, 1, 2]);
+        query_parser.set_minimum_should_match(2);
+        assert_eq!(search(&query_parser, "happy tax payer"), vec![0, 1]);
+        assert_eq!(search(&query_parser, "happy"), vec![0, 1]);
+        assert_eq!(search(&query_parser, "+payer happy"), vec![1]);
+        query_parser.set_minimum_should_match(3);
+        assert_eq!(search(&query_parser, "happy tax payer"), vec![1]);
+        assert_eq!(search(&query_parser, "happy payer"), vec![1]);
+        let (query, errors) = query_parser.parse_query_lenient("happy tax payer^");
+        assert_eq!(errors.len(), 1);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(10)).unwrap();
+        assert_eq!(top_docs.len(), 1);
+    }
+
+    #[test]
+    pub fn test_parse_query_disjunction_max() {
+        let mut query_parser = make_query_parser();
+        query_parser.set_disjunction_max_for_default_fields(0.1);
+        let logical_ast = query_parser
+            .parse_query_to_logical_ast("a title:b")
+            .unwrap();
+        assert_eq!(
+            format!("{:?}", logical_ast),
+            "(max(Term([0, 0, 0, 0, 97]) | Term([0, 0, 0, 1, 97]))~0.1 Term([0, 0, 0, 0, 98]))"
+        );
+        let query = query_parser.parse_query("a").unwrap();
+        let disjunction_max_query = query.downcast_ref::<DisjunctionMaxQuery>().unwrap();
+        assert_eq!(disjunction_max_query.disjuncts().len(), 2);
+        assert_nearly_equals(disjunction_max_query.tie_breaker(), 0.1);
+    }
+
+    #[test]
+    pub fn test_parse_query_disjunction_max_scores() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(doc!(title=>"rust", body=>"rust rust"));
+        index_writer.add_document(doc!(title=>"rust rust rust"));
+        index_writer.add_document(doc!(body=>"python"));
+        index_writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let mut query_parser = QueryParser::for_index(&index, vec![title, body]);
+        let score = |query_parser: &QueryParser, query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            searcher.explain(&query, DocAddress(0, 0)).unwrap().value()
+        };
+        let title_score = score(&query_parser, "title:rust");
+        let body_score = score(&query_parser, "body:rust");
+        assert_nearly_equals(score(&query_parser, "rust"), title_score + body_score);
+        query_parser.set_disjunction_max_for_default_fields(0f32);
+        assert_nearly_equals(score(&query_parser, "rust"), title_score.max(body_score));
+        query_parser.set_disjunction_max_for_default_fields(0.5);
+        assert_nearly_equals(
+            score(&query_parser, "rust"),
+            title_score.max(body_score) + 0.5 * title_score.min(body_score),
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_field_tokenizer() {
+        let mut query_parser = make_query_parser();
+        let title = query_parser.schema.get_field("title").unwrap();
+        test_parse_query_to_logical_ast_helper(
+            "title:\"A b\"",
+            "\"[(0, Term([0, 0, 0, 0, 97])), (1, Term([0, 0, 0, 0, 98]))]\"",
+            false,
+        );
+        query_parser.set_field_tokenizer(title, "raw");
+        let logical_ast = query_parser
+            .parse_query_to_logical_ast("title:\"A b\"")
+            .unwrap();
+        assert_eq!(
+            format!("{:?}", logical_ast),
+            "Term([0, 0, 0, 0, 65, 32, 98])"
+        );
+        query_parser.set_field_tokenizer(title, "unknown");
+        assert_eq!(
+            query_parser.parse_query("title:a").unwrap_err(),
+            QueryParserError::UnknownTokenizer("title".to_string(), "unknown".to_string())
+        );
+    }
+
+    fn test_parse_query_lenient_helper(
+        query: &str,
+        expected: &str,
+        expected_errors: Vec<QueryParserError>,
+    ) {
+        let query_parser = make_query_parser();
+        let (logical_ast, errors) = query_parser.parse_query_to_logical_ast_lenient(query);
+        assert_eq!(format!("{:?}", logical_ast), expected);
+        assert_eq!(errors, expected_errors);
+    }
+
+    #[test]
+    pub fn test_parse_query_lenient() {
+        test_parse_query_lenient_helper(
+            "title:a -title:b",
+            "(Term([0, 0, 0, 0, 97]) -Term([0, 0, 0, 0, 98]))",
+            vec![],
+        );
+        test_parse_query_lenient_helper(
+            "c^ title:b",
+            "((Term([0, 0, 0, 0, 99]) Term([0, 0, 0, 1, 99])) Term([0, 0, 0, 0, 98]))",
+            vec![QueryParserError::SyntaxError],
+        );
+        test_parse_query_lenient_helper(
+            "title:a AND",
+            "(Term([0, 0, 0, 0, 97]))",
+            vec![QueryParserError::SyntaxError],
+        );
+        test_parse_query_lenient_helper(
+            "\"a title:b",
+            "((Term([0, 0, 0, 0, 97]) Term([0, 0, 0, 1, 97])) Term([0, 0, 0, 0, 98]))",
+            vec![QueryParserError::SyntaxError],
+        );
+        test_parse_query_lenient_helper(
+            "boujou:a +title:b",
+            "((Term([0, 0, 0, 0, 98, 111, 117, 106, 111, 117]) Term([0, 0, 0, 0, 97]) \
+             Term([0, 0, 0, 1, 98, 111, 117, 106, 111, 117]) Term([0, 0, 0, 1, 97])) \
+             +Term([0, 0, 0, 0, 98]))",
+            vec![QueryParserError::FieldDoesNotExist("boujou".to_string())],
+        );
+        test_parse_query_lenient_helper(
+            "-title:a",
+            "<emptyclause>",
+            vec![QueryParserError::AllButQueryForbidden],
+        );
+        test_parse_query_lenient_helper(
+            "title:",
+            "((Term([0, 0, 0, 0, 116, 105, 116, 108, 101]) \
+             Term([0, 0, 0, 1, 116, 105, 116, 108, 101])))",
+            vec![QueryParserError::SyntaxError],
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_lenient_search() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(doc!(title=>"Programming in C++"));
+        index_writer.add_document(doc!(title=>"Programming in Rust"));
+        index_writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let (query, errors) = query_parser.parse_query_lenient("c++ (");
+        assert_eq!(errors, vec![QueryParserError::SyntaxError]);
+        assert_eq!(query.count(&searcher).unwrap(), 1);
+        let (query, errors) = query_parser.parse_query_lenient("+rust");
+        assert!(errors.is_empty());
+        assert_eq!(query.count(&searcher).unwrap(), 1);
+    }
+
+    #[test]
+    pub fn test_parse_query_special_chars_search() {
+        let mut schema_builder = Schema::builder();
+        let body = schema_builder.add_text_field("content.body", TEXT);
+        let sender = schema_builder.add_text_field("sender", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(doc!(body=>"a e-mail about c#", sender=>"@user:matrix.org"));
+        index_writer.add_document(doc!(body=>"what is a*?", sender=>"@other:matrix.org"));
+        index_writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let query_parser = QueryParser::for_index(&index, vec![body]);
+        let count = |query_str: &str| {
+            let query = query_parser.parse_query(query_str).unwrap();
+            query.count(&searcher).unwrap()
+        };
+        assert_eq!(count("\"content.body\":e-mail"), 1);
+        assert_eq!(count("c#"), 1);
+        assert_eq!(count("sender:@user\\:matrix.org"), 1);
+        assert_eq!(count("sender:\\@*"), 2);
+        assert_eq!(count("sender:@o?her\\:*"), 1);
+        assert_eq!(count("sender:@user\\:matrix\\*"), 0);
+    }
+
+    #[test]
+    pub fn test_parse_query_to_ast_escaped_wildcard() {
+        test_parse_query_to_logical_ast_helper(
+            "title:a\\*b*",
+            "Term([0, 0, 0, 0, 97, 42, 98])*",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper("title:a\\*?", "Field(0):a\\*?", false);
+    }
+
+    #[test]
+    pub fn test_parse_query_boost_yields_boost_query() {
+        let query_parser = make_query_parser();
+        let query = query_parser.parse_query("title:toto^2").unwrap();
+        let boost_query = query
+            .downcast_ref::<BoostQuery>()
+            .expect("expected a BoostQuery");
+        assert_eq!(boost_query.boost(), 2f32);
+        assert!(query_parser.parse_query("with_stop_words:the^2").is_ok());
+    }
+
     #[test]
     pub fn test_query_parser_field_does_not_exist() {
         let query_parser = make_query_parser();
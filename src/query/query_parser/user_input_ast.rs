@@ -5,6 +5,20 @@ use query::Occur;
 
 pub enum UserInputLeaf {
     Literal(UserInputLiteral),
+    Fuzzy {
+        field_name: Option<String>,
+        term: String,
+        distance: u32,
+        prefix: bool,
+    },
+    Wildcard {
+        field_name: Option<String>,
+        pattern: String,
+    },
+    Regex {
+        field_name: Option<String>,
+        pattern: String,
+    },
     All,
     Range {
         field: Option<String>,
@@ -17,6 +31,39 @@ impl Debug for UserInputLeaf {
     fn fmt(&self, formatter: &mut Formatter) -> Result<(), fmt::Error> {
         match self {
             UserInputLeaf::Literal(literal) => literal.fmt(formatter),
+            UserInputLeaf::Fuzzy {
+                ref field_name,
+                ref term,
+                distance,
+                prefix,
+            } => {
+                if let Some(ref field_name) = field_name {
+                    write!(formatter, "{}:", field_name)?;
+                }
+                write!(formatter, "\"{}\"", term)?;
+                if *prefix {
+                    write!(formatter, "*")?;
+                }
+                write!(formatter, "~{}", distance)
+            }
+            UserInputLeaf::Wildcard {
+                ref field_name,
+                ref pattern,
+            } => {
+                if let Some(ref field_name) = field_name {
+                    write!(formatter, "{}:", field_name)?;
+                }
+                write!(formatter, "{}", pattern)
+            }
+            UserInputLeaf::Regex {
+                ref field_name,
+                ref pattern,
+            } => {
+                if let Some(ref field_name) = field_name {
+                    write!(formatter, "{}:", field_name)?;
+                }
+                write!(formatter, "/{}/", pattern)
+            }
             UserInputLeaf::Range {
                 ref field,
                 ref lower,
@@ -38,14 +85,19 @@ impl Debug for UserInputLeaf {
 pub struct UserInputLiteral {
     pub field_name: Option<String>,
     pub phrase: String,
+    pub slop: u32,
 }
 
 impl fmt::Debug for UserInputLiteral {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match self.field_name {
-            Some(ref field_name) => write!(formatter, "{}:\"{}\"", field_name, self.phrase),
-            None => write!(formatter, "\"{}\"", self.phrase),
+            Some(ref field_name) => write!(formatter, "{}:\"{}\"", field_name, self.phrase)?,
+            None => write!(formatter, "\"{}\"", self.phrase)?,
+        }
+        if self.slop > 0 {
+            write!(formatter, "~{}", self.slop)?;
         }
+        Ok(())
     }
 }
 
@@ -84,6 +136,7 @@ pub enum UserInputAST {
     //    Should(Box<UserInputAST>),
     //    Must(Box<UserInputAST>),
     Leaf(Box<UserInputLeaf>),
+    Boost(Box<UserInputAST>, f32),
 }
 
 impl UserInputAST {
@@ -182,6 +235,7 @@ impl fmt::Debug for UserInputAST {
                 write!(formatter, "{}({:?})", occur.to_char(), subquery)
             }
             UserInputAST::Leaf(ref subquery) => write!(formatter, "{:?}", subquery),
+            UserInputAST::Boost(ref leaf, boost) => write!(formatter, "({:?})^{}", leaf, boost),
         }
     }
 }
@@ -1,15 +1,19 @@
 use common::BitSet;
 use core::Searcher;
 use core::SegmentReader;
+use docset::{DocSet, SkipResult};
 use error::TantivyError;
 use query::BitSetDocSet;
 use query::ConstScorer;
-use query::{Query, Scorer, Weight};
+use query::FastFieldRangeWeight;
+use query::{does_not_match, Explanation};
+use query::{Query, QueryCacheKey, Scorer, Weight};
 use schema::Type;
 use schema::{Field, IndexRecordOption, Term};
 use std::collections::Bound;
 use std::ops::Range;
 use termdict::{TermDictionary, TermStreamer};
+use DocId;
 use Result;
 
 fn map_bound<TFrom, TTo, Transform: Fn(&TFrom) -> TTo>(
@@ -33,6 +37,14 @@ fn map_bound<TFrom, TTo, Transform: Fn(&TFrom) -> TTo>(
 /// The current implement will iterate over the terms within the range
 /// and append all of the document cross into a `BitSet`.
 ///
+/// If the field is a `u64`, `i64` or date fast field which is not indexed,
+/// the fast field values of the documents are scanned instead. The segments
+/// whose values are all out of the range are skipped altogether.
+///
+/// Unlike the indexed terms, a single-valued fast field has a value for every
+/// document: the documents without a value read back as `0` (the epoch for a date
+/// field), so that a range containing `0` also matches them.
+///
 /// # Example
 ///
 /// ```rust
@@ -233,12 +245,40 @@ impl Query for RangeQuery {
             );
             return Err(TantivyError::SchemaError(err_msg));
         }
+        let field_entry = schema.get_field_entry(self.field);
+        if !field_entry.is_indexed() && field_entry.is_int_fast() {
+            return Ok(Box::new(FastFieldRangeWeight::new(
+                self.field,
+                &self.left_bound,
+                &self.right_bound,
+            )));
+        }
         Ok(Box::new(RangeWeight {
             field: self.field,
             left_bound: self.left_bound.clone(),
             right_bound: self.right_bound.clone(),
         }))
     }
+
+    fn cache_key(&self) -> Option<QueryCacheKey> {
+        let mut cache_key = QueryCacheKey::new("range");
+        cache_key.push_u64(u64::from(self.field.0));
+        cache_key.push_u64(self.value_type as u64);
+        for bound in &[&self.left_bound, &self.right_bound] {
+            match **bound {
+                Bound::Included(ref bytes) => {
+                    cache_key.push_u64(0);
+                    cache_key.push(bytes);
+                }
+                Bound::Excluded(ref bytes) => {
+                    cache_key.push_u64(1);
+                    cache_key.push(bytes);
+                }
+                Bound::Unbounded => cache_key.push_u64(2),
+            }
+        }
+        Some(cache_key)
+    }
 }
 
 pub struct RangeWeight {
@@ -286,17 +326,29 @@ impl Weight for RangeWeight {
         let doc_bitset = BitSetDocSet::from(doc_bitset);
         Ok(Box::new(ConstScorer::new(doc_bitset)))
     }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut scorer = self.scorer(reader)?;
+        if scorer.skip_next(doc) == SkipResult::Reached {
+            Ok(Explanation::new("RangeQuery", 1f32))
+        } else {
+            Err(does_not_match(doc))
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::RangeQuery;
+    use chrono::{TimeZone, Utc};
     use collector::Count;
-    use schema::{Document, Field, Schema, INDEXED};
+    use query::QueryParser;
+    use schema::{Cardinality, Document, Field, IntOptions, Schema, Type, FAST, INDEXED};
     use std::collections::Bound;
     use Index;
     use Result;
+    use Term;
 
     #[test]
     fn test_range_query_simple() {
@@ -387,4 +439,117 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_range_query_fast_field() {
+        let mut schema_builder = Schema::builder();
+        let indexed_field = schema_builder.add_i64_field("indexed", INDEXED);
+        let fast_field = schema_builder.add_i64_field("fast", FAST);
+        let multi_field = schema_builder.add_u64_field(
+            "multi",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        let date_field = schema_builder.add_date_field(
+            "date",
+            IntOptions::default().set_fast(Cardinality::SingleValue),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 6_000_000).unwrap();
+            // Three segments, the values of the last one all being greater than `2_000`.
+            for segment in 0..3 {
+                let start = if segment == 2 { 2_000i64 } else { -1_000i64 };
+                for val in start..start + 1_500 {
+                    let date = Utc.timestamp(val * 3_600, 0);
+                    index_writer.add_document(doc!(
+                        indexed_field => val,
+                        fast_field => val,
+                        multi_field => val as u64 % 7,
+                        multi_field => val as u64 % 11,
+                        date_field => date
+                    ));
+                }
+                index_writer.commit().unwrap();
+            }
+            index_writer.delete_term(Term::from_field_i64(indexed_field, 100));
+            index_writer.commit().unwrap();
+        }
+        let reader = index.reader().unwrap();
+        let searcher = reader.searcher();
+        assert_eq!(searcher.segment_readers().len(), 3);
+        let count = |range_query: RangeQuery| searcher.search(&range_query, &Count).unwrap();
+
+        let ranges = vec![
+            (Bound::Included(-10), Bound::Excluded(10)),
+            (Bound::Excluded(-1_000), Bound::Included(499)),
+            (Bound::Included(0), Bound::Unbounded),
+            (Bound::Unbounded, Bound::Included(3_499)),
+            (Bound::Unbounded, Bound::Unbounded),
+            (Bound::Included(3_500), Bound::Unbounded),
+            (Bound::Included(5), Bound::Excluded(5)),
+        ];
+        for (left_bound, right_bound) in ranges {
+            let expected = count(RangeQuery::new_i64_bounds(
+                indexed_field,
+                left_bound,
+                right_bound,
+            ));
+            assert_eq!(
+                count(RangeQuery::new_i64_bounds(
+                    fast_field,
+                    left_bound,
+                    right_bound
+                )),
+                expected
+            );
+        }
+        assert_eq!(count(RangeQuery::new_i64(fast_field, 99..101)), 2);
+        assert_eq!(count(RangeQuery::new_i64(fast_field, 2_000..2_001)), 1);
+
+        // Documents having either `val % 7` or `val % 11` in `9..11`.
+        let expected = (-1_000i64..500)
+            .chain(-1_000i64..500)
+            .chain(2_000i64..3_500)
+            .filter(|&val| val != 100)
+            .filter(|&val| {
+                let val = val as u64;
+                (9..11).contains(&(val % 7)) || (9..11).contains(&(val % 11))
+            })
+            .count();
+        assert_eq!(count(RangeQuery::new_u64(multi_field, 9..11)), expected);
+        assert_eq!(count(RangeQuery::new_u64(multi_field, 11..100)), 0);
+
+        let date_range = RangeQuery::new_term_bounds(
+            date_field,
+            Type::Date,
+            &Bound::Included(Term::from_field_date(date_field, &Utc.timestamp(0, 0))),
+            &Bound::Excluded(Term::from_field_date(
+                date_field,
+                &Utc.ymd(1970, 1, 2).and_hms(0, 0, 0),
+            )),
+        );
+        assert_eq!(count(date_range), 2 * 24);
+
+        let query_parser = QueryParser::for_index(&index, vec![]);
+        let query = query_parser.parse_query("fast:[-10 TO 10}").unwrap();
+        assert_eq!(query.count(&searcher).unwrap(), 2 * 20);
+    }
+
+    #[test]
+    fn test_range_query_fast_field_missing_value() {
+        let mut schema_builder = Schema::builder();
+        let fast_field = schema_builder.add_i64_field("fast", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(fast_field => 5i64));
+            index_writer.add_document(Document::new());
+            index_writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let count = |range_query: RangeQuery| searcher.search(&range_query, &Count).unwrap();
+        // the document without a value reads back as `0`.
+        assert_eq!(count(RangeQuery::new_i64(fast_field, -1..1)), 1);
+        assert_eq!(count(RangeQuery::new_i64(fast_field, 1..10)), 1);
+        assert_eq!(count(RangeQuery::new_i64(fast_field, -10..10)), 2);
+    }
 }
@@ -69,6 +69,16 @@ impl RegexQuery {
         }
     }
 
+    /// The regular expression searched by this query.
+    pub fn pattern(&self) -> &str {
+        &self.regex_pattern
+    }
+
+    /// The field searched by this query.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
     fn specialized_weight(&self) -> Result<AutomatonWeight<Regex>> {
         let automaton = Regex::new(&self.regex_pattern)
             .map_err(|_| TantivyError::InvalidArgument(self.regex_pattern.clone()))?;
@@ -78,3 +78,43 @@ impl ScoreCombiner for SumWithCoordsCombiner {
         self.score
     }
 }
+
+/// Keeps the maximum of the scores of different scorers,
+/// and adds the other scores multiplied by a tie breaker.
+///
+/// With a tie breaker of `0`, the score is the maximum score.
+/// With a tie breaker of `1`, the score is the sum of the scores.
+#[derive(Default, Clone, Copy)]
+pub struct DisjunctionMaxCombiner {
+    tie_breaker: Score,
+    max: Score,
+    sum: Score,
+}
+
+impl DisjunctionMaxCombiner {
+    /// Creates a `DisjunctionMaxCombiner` with the given tie breaker.
+    pub fn with_tie_breaker(tie_breaker: Score) -> DisjunctionMaxCombiner {
+        DisjunctionMaxCombiner {
+            tie_breaker,
+            max: 0f32,
+            sum: 0f32,
+        }
+    }
+}
+
+impl ScoreCombiner for DisjunctionMaxCombiner {
+    fn update<TScorer: Scorer>(&mut self, scorer: &mut TScorer) {
+        let score = scorer.score();
+        self.max = Score::max(self.max, score);
+        self.sum += score;
+    }
+
+    fn clear(&mut self) {
+        self.max = 0f32;
+        self.sum = 0f32;
+    }
+
+    fn score(&self) -> Score {
+        self.max + (self.sum - self.max) * self.tie_breaker
+    }
+}
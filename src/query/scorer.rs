@@ -21,6 +21,22 @@ pub trait Scorer: downcast_rs::Downcast + DocSet + 'static {
             callback(self.doc(), self.score());
         }
     }
+
+    /// Iterates through all of the documents matched by the `Scorer`
+    /// and pushes the documents with a score strictly greater than
+    /// `threshold` to the callback.
+    ///
+    /// The callback returns the new threshold. It can only increase, and
+    /// documents that cannot beat it may be skipped without being scored.
+    fn for_each_pruning(&mut self, threshold: Score, callback: &mut FnMut(DocId, Score) -> Score) {
+        let mut threshold = threshold;
+        while self.advance() {
+            let score = self.score();
+            if score > threshold {
+                threshold = callback(self.doc(), score);
+            }
+        }
+    }
 }
 
 impl_downcast!(Scorer);
@@ -34,6 +50,11 @@ impl Scorer for Box<Scorer> {
         let scorer = self.deref_mut();
         scorer.for_each(callback);
     }
+
+    fn for_each_pruning(&mut self, threshold: Score, callback: &mut FnMut(DocId, Score) -> Score) {
+        let scorer = self.deref_mut();
+        scorer.for_each_pruning(threshold, callback);
+    }
 }
 
 /// Wraps a `DocSet` and simply returns a constant `Scorer`.
@@ -90,6 +111,6 @@ impl<TDocSet: DocSet> DocSet for ConstScorer<TDocSet> {
 
 impl<TDocSet: DocSet + 'static> Scorer for ConstScorer<TDocSet> {
     fn score(&mut self) -> Score {
-        1f32
+        self.score
     }
 }
@@ -0,0 +1,186 @@
+use super::{fieldnorm, sum_idf_explanations};
+use super::{CollectionStatistics, Similarity, SimilarityWeight};
+use fieldnorm::FieldNormReader;
+use query::Explanation;
+use Score;
+
+const DEFAULT_K1: f32 = 1.2;
+const DEFAULT_B: f32 = 0.75;
+
+pub(crate) fn idf(doc_freq: u64, doc_count: u64) -> f32 {
+    let x = ((doc_count - doc_freq) as f32 + 0.5) / (doc_freq as f32 + 0.5);
+    (1f32 + x).ln()
+}
+
+fn idf_explanation(doc_freq: u64, doc_count: u64) -> Explanation {
+    let mut explanation = Explanation::new(
+        "idf, computed as log(1 + (N - n + 0.5) / (n + 0.5))",
+        idf(doc_freq, doc_count),
+    );
+    explanation.add_const("n, number of docs containing this term", doc_freq as f32);
+    explanation.add_const("N, total number of docs", doc_count as f32);
+    explanation
+}
+
+/// Okapi BM25 similarity.
+///
+/// The score of a document is `(k1 + 1) * idf * tf`, with
+/// `tf = freq / (freq + k1 * (1 - b + b * dl / avgdl))`, where `freq` is the
+/// term frequency, `dl` the length of the field and `avgdl` its average length.
+///
+/// `k1` controls the saturation of the term frequency, and `b` the normalization
+/// by the length of the field. They default to `1.2` and `0.75`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BM25Similarity {
+    k1: f32,
+    b: f32,
+}
+
+impl BM25Similarity {
+    /// Creates a BM25 similarity with the given parameters.
+    pub fn new(k1: f32, b: f32) -> BM25Similarity {
+        BM25Similarity { k1, b }
+    }
+
+    /// Returns the term frequency saturation parameter.
+    pub fn k1(&self) -> f32 {
+        self.k1
+    }
+
+    /// Returns the length normalization parameter.
+    pub fn b(&self) -> f32 {
+        self.b
+    }
+}
+
+impl Default for BM25Similarity {
+    fn default() -> BM25Similarity {
+        BM25Similarity::new(DEFAULT_K1, DEFAULT_B)
+    }
+}
+
+impl Similarity for BM25Similarity {
+    fn weight(
+        &self,
+        collection_statistics: &CollectionStatistics,
+        doc_freqs: &[u64],
+    ) -> Box<SimilarityWeight> {
+        let doc_count = collection_statistics.doc_count();
+        let idf_explanation = sum_idf_explanations(
+            doc_freqs
+                .iter()
+                .map(|&doc_freq| idf_explanation(doc_freq, doc_count))
+                .collect(),
+        );
+        Box::new(BM25Weight::new(
+            *self,
+            idf_explanation,
+            collection_statistics.average_fieldnorm(),
+        ))
+    }
+}
+
+struct BM25Weight {
+    similarity: BM25Similarity,
+    idf_explanation: Explanation,
+    weight: f32,
+    cache: [f32; 256],
+    average_fieldnorm: f32,
+}
+
+impl BM25Weight {
+    fn new(
+        similarity: BM25Similarity,
+        idf_explanation: Explanation,
+        average_fieldnorm: f32,
+    ) -> BM25Weight {
+        let BM25Similarity { k1, b } = similarity;
+        let mut cache = [0f32; 256];
+        for (fieldnorm_id, cache_mut) in cache.iter_mut().enumerate() {
+            *cache_mut = k1 * (1f32 - b + b * fieldnorm(fieldnorm_id as u8) / average_fieldnorm);
+        }
+        BM25Weight {
+            similarity,
+            weight: idf_explanation.value() * (1f32 + k1),
+            idf_explanation,
+            cache,
+            average_fieldnorm,
+        }
+    }
+}
+
+impl SimilarityWeight for BM25Weight {
+    #[inline(always)]
+    fn score_with_freq(&self, fieldnorm_id: u8, term_freq: f32) -> Score {
+        let norm = self.cache[fieldnorm_id as usize];
+        self.weight * term_freq / (term_freq + norm)
+    }
+
+    fn explain(&self, fieldnorm_id: u8, term_freq: f32) -> Explanation {
+        let BM25Similarity { k1, b } = self.similarity;
+        let norm = self.cache[fieldnorm_id as usize];
+        let mut tf_explanation = Explanation::new(
+            "tf, computed as freq / (freq + k1 * (1 - b + b * dl / avgdl))",
+            term_freq / (term_freq + norm),
+        );
+        tf_explanation.add_const("freq, occurrences of term within document", term_freq);
+        tf_explanation.add_const("k1, term saturation parameter", k1);
+        tf_explanation.add_const("b, length normalization parameter", b);
+        tf_explanation.add_const(
+            "dl, length of field",
+            FieldNormReader::id_to_fieldnorm(fieldnorm_id) as f32,
+        );
+        tf_explanation.add_const("avgdl, average length of field", self.average_fieldnorm);
+
+        let mut explanation = Explanation::new(
+            "BM25, computed as (k1 + 1) * idf * tf, from:",
+            self.score_with_freq(fieldnorm_id, term_freq),
+        );
+        explanation.add_const("k1 + 1", k1 + 1f32);
+        explanation.add_detail(self.idf_explanation.clone());
+        explanation.add_detail(tf_explanation);
+        explanation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::idf;
+    use super::BM25Similarity;
+    use query::similarity::{CollectionStatistics, Similarity};
+    use std::f32;
+    use tests::assert_nearly_equals;
+
+    #[test]
+    fn test_idf() {
+        assert_nearly_equals(idf(1, 2), f32::consts::LN_2);
+    }
+
+    #[test]
+    fn test_bm25_parameters() {
+        let collection_statistics = CollectionStatistics::new(10, 4f32);
+        let default_weight = BM25Similarity::default().weight(&collection_statistics, &[2]);
+        let idf_value = idf(2, 10);
+        // fieldnorm ids below 24 are the fieldnorms themselves.
+        assert_nearly_equals(
+            default_weight.score(4, 1),
+            idf_value * 2.2 * 1f32 / (1f32 + 1.2),
+        );
+        let without_length_normalization =
+            BM25Similarity::new(2f32, 0f32).weight(&collection_statistics, &[2]);
+        assert_nearly_equals(
+            without_length_normalization.score(1, 2),
+            without_length_normalization.score(8, 2),
+        );
+        assert_nearly_equals(
+            without_length_normalization.score(8, 2),
+            idf_value * 3f32 * 2f32 / (2f32 + 2f32),
+        );
+        let explanation = without_length_normalization.explain(8, 2f32);
+        assert_nearly_equals(
+            explanation.value(),
+            without_length_normalization.score(8, 2),
+        );
+    }
+}
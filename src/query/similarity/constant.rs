@@ -0,0 +1,51 @@
+use super::{CollectionStatistics, Similarity, SimilarityWeight};
+use query::Explanation;
+use Score;
+
+/// Similarity giving the same score to all of the matching documents,
+/// regardless of the term frequency and of the length of the field.
+///
+/// This is useful for boolean matches, such as tags or identifiers,
+/// whose frequency in a document is not relevant.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConstantSimilarity {
+    score: Score,
+}
+
+impl ConstantSimilarity {
+    /// Creates a similarity giving the score `score` to all of the matching documents.
+    pub fn new(score: Score) -> ConstantSimilarity {
+        ConstantSimilarity { score }
+    }
+
+    /// Returns the score given to the matching documents.
+    pub fn score(&self) -> Score {
+        self.score
+    }
+}
+
+impl Default for ConstantSimilarity {
+    fn default() -> ConstantSimilarity {
+        ConstantSimilarity::new(1f32)
+    }
+}
+
+impl Similarity for ConstantSimilarity {
+    fn weight(
+        &self,
+        _collection_statistics: &CollectionStatistics,
+        _doc_freqs: &[u64],
+    ) -> Box<SimilarityWeight> {
+        Box::new(*self)
+    }
+}
+
+impl SimilarityWeight for ConstantSimilarity {
+    fn score_with_freq(&self, _fieldnorm_id: u8, _term_freq: f32) -> Score {
+        self.score
+    }
+
+    fn explain(&self, _fieldnorm_id: u8, _term_freq: f32) -> Explanation {
+        Explanation::new("constant score", self.score)
+    }
+}
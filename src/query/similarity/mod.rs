@@ -0,0 +1,296 @@
+mod bm25;
+mod constant;
+mod similarity_manager;
+mod tfidf;
+
+pub(crate) use self::bm25::idf;
+pub use self::bm25::BM25Similarity;
+pub use self::constant::ConstantSimilarity;
+pub use self::similarity_manager::SimilarityManager;
+pub use self::tfidf::TfIdfSimilarity;
+
+use fieldnorm::FieldNormReader;
+use query::Explanation;
+use std::sync::Arc;
+use Result;
+use Score;
+use Searcher;
+use Term;
+
+/// Statistics of a field over all of the segments of a `Searcher`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CollectionStatistics {
+    doc_count: u64,
+    average_fieldnorm: f32,
+}
+
+impl CollectionStatistics {
+    /// Creates the statistics of a field.
+    pub fn new(doc_count: u64, average_fieldnorm: f32) -> CollectionStatistics {
+        CollectionStatistics {
+            doc_count,
+            average_fieldnorm,
+        }
+    }
+
+    /// Returns the number of documents, including those without the field.
+    pub fn doc_count(&self) -> u64 {
+        self.doc_count
+    }
+
+    /// Returns the average number of tokens of the field in a document.
+    pub fn average_fieldnorm(&self) -> f32 {
+        self.average_fieldnorm
+    }
+}
+
+/// A `Similarity` defines how the documents matching a set of terms of a field are scored.
+///
+/// For a set of terms and the statistics of their field over the segments of a `Searcher`,
+/// a `Similarity` computes a [`SimilarityWeight`](./trait.SimilarityWeight.html), scoring
+/// each document from its term frequency and its fieldnorm.
+///
+/// Tantivy ships with the following similarities, registered in the
+/// [`SimilarityManager`](./struct.SimilarityManager.html) of each `Index`:
+///
+///  * `default` and `bm25` : [`BM25Similarity`](./struct.BM25Similarity.html).
+///  * `tfidf` : [`TfIdfSimilarity`](./struct.TfIdfSimilarity.html).
+///  * `constant` : [`ConstantSimilarity`](./struct.ConstantSimilarity.html).
+///
+/// The similarity of a text field is chosen by name in the schema, with
+/// [`TextFieldIndexing::set_similarity`](../schema/struct.TextFieldIndexing.html#method.set_similarity),
+/// and can be overridden on a `Searcher` with
+/// [`Searcher::with_similarity`](../struct.Searcher.html#method.with_similarity).
+///
+/// Scores must be non-decreasing with the term frequency and non-increasing
+/// with the fieldnorm, as the maximum score of a block of postings is computed from
+/// its maximum term frequency and its minimum fieldnorm to skip documents.
+pub trait Similarity: Send + Sync + 'static {
+    /// Computes the weight of a set of terms, given the statistics
+    /// of their field and the number of documents containing each of them.
+    fn weight(
+        &self,
+        collection_statistics: &CollectionStatistics,
+        doc_freqs: &[u64],
+    ) -> Box<SimilarityWeight>;
+}
+
+/// Scores the documents matching a set of terms, as computed by a
+/// [`Similarity`](./trait.Similarity.html).
+pub trait SimilarityWeight: Send + Sync + 'static {
+    /// Returns the score of a document given its fieldnorm id, see
+    /// [`FieldNormReader`](../fieldnorm/struct.FieldNormReader.html),
+    /// and its term frequency.
+    ///
+    /// The term frequency may be fractional, as computed for sloppy phrase matches.
+    fn score_with_freq(&self, fieldnorm_id: u8, term_freq: f32) -> Score;
+
+    /// Explains the score computed by `.score_with_freq(...)`.
+    fn explain(&self, fieldnorm_id: u8, term_freq: f32) -> Explanation;
+
+    /// Same as `.score_with_freq(...)`, for an integer term frequency.
+    fn score(&self, fieldnorm_id: u8, term_freq: u32) -> Score {
+        self.score_with_freq(fieldnorm_id, term_freq as f32)
+    }
+}
+
+/// Returns the length of a field given its fieldnorm id.
+fn fieldnorm(fieldnorm_id: u8) -> f32 {
+    FieldNormReader::id_to_fieldnorm(fieldnorm_id) as f32
+}
+
+/// Returns the weight used when scoring is disabled, scoring all of the documents `0`.
+pub(crate) fn disabled_weight() -> Arc<SimilarityWeight> {
+    Arc::from(ConstantSimilarity::new(0f32).weight(&CollectionStatistics::new(0, 0f32), &[]))
+}
+
+/// Computes the weight of a set of terms of the same field,
+/// with the similarity of the field in the searcher.
+pub(crate) fn weight_for_terms(
+    searcher: &Searcher,
+    terms: &[Term],
+) -> Result<Arc<SimilarityWeight>> {
+    assert!(!terms.is_empty(), "Scoring requires at least one term");
+    let field = terms[0].field();
+    for term in &terms[1..] {
+        assert_eq!(
+            term.field(),
+            field,
+            "All terms must belong to the same field."
+        );
+    }
+
+    let mut total_num_tokens = 0u64;
+    let mut total_num_docs = 0u64;
+    for segment_reader in searcher.segment_readers() {
+        let inverted_index = segment_reader.inverted_index(field);
+        total_num_tokens += inverted_index.total_num_tokens();
+        total_num_docs += u64::from(segment_reader.max_doc());
+    }
+    let average_fieldnorm = total_num_tokens as f32 / total_num_docs as f32;
+    let collection_statistics = CollectionStatistics::new(total_num_docs, average_fieldnorm);
+    let doc_freqs: Vec<u64> = terms.iter().map(|term| searcher.doc_freq(term)).collect();
+    let similarity = searcher.similarity(field)?;
+    Ok(Arc::from(
+        similarity.weight(&collection_statistics, &doc_freqs),
+    ))
+}
+
+/// Combines the explanations of the idf of several terms, by summing them.
+fn sum_idf_explanations(mut idf_explanations: Vec<Explanation>) -> Explanation {
+    if idf_explanations.len() == 1 {
+        return idf_explanations.pop().unwrap();
+    }
+    let idf = idf_explanations.iter().map(Explanation::value).sum::<f32>();
+    let mut idf_explanation = Explanation::new("idf, sum of the idf of the terms", idf);
+    for term_idf_explanation in idf_explanations {
+        idf_explanation.add_detail(term_idf_explanation);
+    }
+    idf_explanation
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{BM25Similarity, ConstantSimilarity, TfIdfSimilarity};
+    use collector::{Count, TopDocs};
+    use query::{Query, TermQuery};
+    use schema::{IndexRecordOption, Schema, TextFieldIndexing, TextOptions, TEXT};
+    use std::sync::Arc;
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use Index;
+    use Term;
+
+    #[test]
+    fn test_similarity_per_field() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let tags = schema_builder.add_text_field(
+            "tags",
+            TextOptions::default().set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_index_option(IndexRecordOption::WithFreqs)
+                    .set_similarity("constant"),
+            ),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(title=>"a b", tags=>"a a b"));
+            index_writer.add_document(doc!(title=>"a a c d", tags=>"a"));
+            index_writer.add_document(doc!(title=>"b", tags=>"b"));
+            index_writer.commit().unwrap();
+        }
+        let reader = index.reader().unwrap();
+        let searcher = reader.searcher();
+        let term_query = |field, text| -> Box<Query> {
+            Box::new(TermQuery::new(
+                Term::from_field_text(field, text),
+                IndexRecordOption::WithFreqs,
+            ))
+        };
+        let score = |searcher: &::Searcher, query: &Query, doc| {
+            searcher.explain(query, DocAddress(0, doc)).unwrap().value()
+        };
+
+        let title_query = term_query(title, "a");
+        let bm25_score = score(&searcher, title_query.as_ref(), 0);
+        assert_nearly_equals(bm25_score, 0.49917623);
+        let tags_query = term_query(tags, "a");
+        assert_nearly_equals(score(&searcher, tags_query.as_ref(), 0), 1f32);
+        assert_nearly_equals(score(&searcher, tags_query.as_ref(), 1), 1f32);
+
+        let bm25_searcher =
+            searcher.with_similarity(title, Arc::new(BM25Similarity::new(1.2, 0f32)));
+        let bm25_without_length_score = score(&bm25_searcher, title_query.as_ref(), 0);
+        assert!(bm25_without_length_score < bm25_score);
+        let top_docs = bm25_searcher
+            .search(title_query.as_ref(), &TopDocs::with_limit(2))
+            .unwrap();
+        assert_eq!(top_docs[0].1, DocAddress(0, 1));
+
+        let tfidf_searcher = bm25_searcher.with_similarity(tags, Arc::new(TfIdfSimilarity));
+        assert_nearly_equals(
+            score(&tfidf_searcher, title_query.as_ref(), 0),
+            bm25_without_length_score,
+        );
+        let tfidf_a_a_b = score(&tfidf_searcher, tags_query.as_ref(), 0);
+        let tfidf_a = score(&tfidf_searcher, tags_query.as_ref(), 1);
+        assert_nearly_equals(tfidf_a_a_b / tfidf_a, (2f32 / 3f32).sqrt());
+
+        let constant_searcher =
+            tfidf_searcher.with_similarity(title, Arc::new(ConstantSimilarity::new(3f32)));
+        let top_docs = constant_searcher
+            .search(title_query.as_ref(), &TopDocs::with_limit(2))
+            .unwrap();
+        assert_nearly_equals(top_docs[0].0, 3f32);
+        assert_nearly_equals(top_docs[1].0, 3f32);
+
+        assert_nearly_equals(score(&searcher, title_query.as_ref(), 0), bm25_score);
+        assert_nearly_equals(score(&searcher, tags_query.as_ref(), 0), 1f32);
+    }
+
+    #[test]
+    fn test_similarity_override_is_per_search() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(title=>"a b"));
+            index_writer.commit().unwrap();
+        }
+        let reader = index.reader().unwrap();
+        let query = TermQuery::new(
+            Term::from_field_text(title, "a"),
+            IndexRecordOption::WithFreqs,
+        );
+        let bm25_score = {
+            let searcher = reader.searcher();
+            let constant_searcher =
+                searcher.with_similarity(title, Arc::new(ConstantSimilarity::new(3f32)));
+            let constant_score = constant_searcher
+                .explain(&query, DocAddress(0, 0))
+                .unwrap()
+                .value();
+            assert_nearly_equals(constant_score, 3f32);
+            searcher.explain(&query, DocAddress(0, 0)).unwrap().value()
+        };
+        let searcher = reader.searcher();
+        let score = searcher.explain(&query, DocAddress(0, 0)).unwrap().value();
+        assert_nearly_equals(score, bm25_score);
+        assert!(score < 3f32);
+    }
+
+    #[test]
+    fn test_unknown_similarity() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexing_options(TextFieldIndexing::default().set_similarity("unknown")),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(title=>"a"));
+            index_writer.commit().unwrap();
+        }
+        let term_query =
+            TermQuery::new(Term::from_field_text(title, "a"), IndexRecordOption::Basic);
+        let searcher = index.reader().unwrap().searcher();
+        assert!(searcher
+            .search(&term_query, &TopDocs::with_limit(1))
+            .is_err());
+        assert_eq!(searcher.search(&term_query, &Count).unwrap(), 1);
+
+        index
+            .similarities()
+            .register("unknown", ConstantSimilarity::new(2f32));
+        let top_docs = searcher
+            .search(&term_query, &TopDocs::with_limit(1))
+            .unwrap();
+        assert_nearly_equals(top_docs[0].0, 2f32);
+    }
+}
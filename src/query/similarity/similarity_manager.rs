@@ -0,0 +1,51 @@
+use super::{BM25Similarity, ConstantSimilarity, Similarity, TfIdfSimilarity};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// The similarity manager serves as a store for
+/// all of the similarities the fields of a schema may refer to by name.
+///
+/// By default, it is populated with the following similarities.
+///
+///  * `default` : BM25 with `k1 = 1.2` and `b = 0.75`.
+///  * `bm25` : same as `default`.
+///  * `tfidf` : the classic TF-IDF.
+///  * `constant` : scores all of the matching documents `1`.
+#[derive(Clone)]
+pub struct SimilarityManager {
+    similarities: Arc<RwLock<HashMap<String, Arc<Similarity>>>>,
+}
+
+impl SimilarityManager {
+    /// Registers a new similarity associated with a given name.
+    pub fn register<S: Similarity>(&self, similarity_name: &str, similarity: S) {
+        self.similarities
+            .write()
+            .expect("Acquiring the lock should never fail")
+            .insert(similarity_name.to_string(), Arc::new(similarity));
+    }
+
+    /// Accessing a similarity given its name.
+    pub fn get(&self, similarity_name: &str) -> Option<Arc<Similarity>> {
+        self.similarities
+            .read()
+            .expect("Acquiring the lock should never fail")
+            .get(similarity_name)
+            .cloned()
+    }
+}
+
+impl Default for SimilarityManager {
+    /// Creates a `SimilarityManager` prepopulated with
+    /// the similarities shipped with `tantivy`.
+    fn default() -> SimilarityManager {
+        let manager = SimilarityManager {
+            similarities: Arc::new(RwLock::new(HashMap::new())),
+        };
+        manager.register("default", BM25Similarity::default());
+        manager.register("bm25", BM25Similarity::default());
+        manager.register("tfidf", TfIdfSimilarity);
+        manager.register("constant", ConstantSimilarity::default());
+        manager
+    }
+}
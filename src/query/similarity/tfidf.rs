@@ -0,0 +1,105 @@
+use super::{fieldnorm, sum_idf_explanations};
+use super::{CollectionStatistics, Similarity, SimilarityWeight};
+use query::Explanation;
+use Score;
+
+fn idf(doc_freq: u64, doc_count: u64) -> f32 {
+    1f32 + ((doc_count as f32 + 1f32) / (doc_freq as f32 + 1f32)).ln()
+}
+
+fn idf_explanation(doc_freq: u64, doc_count: u64) -> Explanation {
+    let mut explanation = Explanation::new(
+        "idf, computed as 1 + log((N + 1) / (n + 1))",
+        idf(doc_freq, doc_count),
+    );
+    explanation.add_const("n, number of docs containing this term", doc_freq as f32);
+    explanation.add_const("N, total number of docs", doc_count as f32);
+    explanation
+}
+
+/// Returns the length normalization factor of a field, `1 / sqrt(dl)`.
+fn length_norm(fieldnorm_id: u8) -> f32 {
+    1f32 / fieldnorm(fieldnorm_id).max(1f32).sqrt()
+}
+
+/// Classic TF-IDF similarity.
+///
+/// The score of a document is `sqrt(freq) * idf² / sqrt(dl)`, with
+/// `idf = 1 + log((N + 1) / (n + 1))`, where `freq` is the term frequency,
+/// `dl` the length of the field, `N` the number of documents and `n` the
+/// number of documents containing the term.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TfIdfSimilarity;
+
+impl Similarity for TfIdfSimilarity {
+    fn weight(
+        &self,
+        collection_statistics: &CollectionStatistics,
+        doc_freqs: &[u64],
+    ) -> Box<SimilarityWeight> {
+        let doc_count = collection_statistics.doc_count();
+        let idf_explanation = sum_idf_explanations(
+            doc_freqs
+                .iter()
+                .map(|&doc_freq| idf_explanation(doc_freq, doc_count))
+                .collect(),
+        );
+        let idf = idf_explanation.value();
+        let mut cache = [0f32; 256];
+        for (fieldnorm_id, cache_mut) in cache.iter_mut().enumerate() {
+            *cache_mut = idf * idf * length_norm(fieldnorm_id as u8);
+        }
+        Box::new(TfIdfWeight {
+            idf_explanation,
+            cache,
+        })
+    }
+}
+
+struct TfIdfWeight {
+    idf_explanation: Explanation,
+    cache: [f32; 256],
+}
+
+impl SimilarityWeight for TfIdfWeight {
+    #[inline(always)]
+    fn score_with_freq(&self, fieldnorm_id: u8, term_freq: f32) -> Score {
+        term_freq.sqrt() * self.cache[fieldnorm_id as usize]
+    }
+
+    fn explain(&self, fieldnorm_id: u8, term_freq: f32) -> Explanation {
+        let mut tf_explanation = Explanation::new("tf, computed as sqrt(freq)", term_freq.sqrt());
+        tf_explanation.add_const("freq, occurrences of term within document", term_freq);
+        let mut norm_explanation =
+            Explanation::new("norm, computed as 1 / sqrt(dl)", length_norm(fieldnorm_id));
+        norm_explanation.add_const("dl, length of field", fieldnorm(fieldnorm_id));
+
+        let mut explanation = Explanation::new(
+            "TF-IDF, computed as tf * idf * idf * norm, from:",
+            self.score_with_freq(fieldnorm_id, term_freq),
+        );
+        explanation.add_detail(tf_explanation);
+        explanation.add_detail(self.idf_explanation.clone());
+        explanation.add_detail(norm_explanation);
+        explanation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::TfIdfSimilarity;
+    use query::similarity::{CollectionStatistics, Similarity};
+    use tests::assert_nearly_equals;
+
+    #[test]
+    fn test_tfidf() {
+        let collection_statistics = CollectionStatistics::new(9, 4f32);
+        let weight = TfIdfSimilarity.weight(&collection_statistics, &[4]);
+        let idf = 1f32 + 2f32.ln();
+        assert_nearly_equals(weight.score(4, 1), idf * idf / 2f32);
+        assert_nearly_equals(weight.score(16, 9), 3f32 * idf * idf / 4f32);
+        assert_nearly_equals(weight.explain(16, 9f32).value(), weight.score(16, 9));
+        assert!(weight.score(0, 1).is_finite());
+    }
+}
@@ -0,0 +1,213 @@
+mod span_query;
+mod span_weight;
+mod spans;
+
+pub use self::span_query::SpanQuery;
+pub use self::span_weight::SpanWeight;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use collector::tests::TestCollector;
+    use collector::Count;
+    use core::Index;
+    use error::TantivyError;
+    use query::{PhraseQuery, Query};
+    use schema::{Field, Schema, Term, STRING, TEXT};
+    use std::collections::BTreeSet;
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use DocId;
+
+    fn create_index(texts: &[&'static str]) -> (Index, Field) {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            for &text in texts {
+                index_writer.add_document(doc!(text_field=>text));
+            }
+            assert!(index_writer.commit().is_ok());
+        }
+        (index, text_field)
+    }
+
+    fn matching_docs(index: &Index, query: &SpanQuery) -> Vec<DocId> {
+        let searcher = index.reader().unwrap().searcher();
+        let test_fruits = searcher
+            .search(query, &TestCollector)
+            .expect("search should succeed");
+        assert_eq!(
+            searcher.search(query, &Count).unwrap(),
+            test_fruits.docs().len()
+        );
+        test_fruits
+            .docs()
+            .iter()
+            .map(|doc_address| doc_address.1)
+            .collect()
+    }
+
+    #[test]
+    pub fn test_span_near_query() {
+        let (index, text_field) =
+            create_index(&["a b c d", "a x b", "b a", "a x y z b", "b b", "c d a x b"]);
+        let term = |text: &str| SpanQuery::term(Term::from_field_text(text_field, text));
+        let near = |texts: &[&str], slop: u32, in_order: bool| {
+            let clauses = texts.iter().map(|text| term(text)).collect();
+            matching_docs(&index, &SpanQuery::near(clauses, slop, in_order))
+        };
+        assert_eq!(near(&["a", "b"], 0, true), vec![0]);
+        assert_eq!(near(&["a", "b"], 1, true), vec![0, 1, 5]);
+        assert_eq!(near(&["a", "b"], 3, true), vec![0, 1, 3, 5]);
+        assert_eq!(near(&["a", "b"], 0, false), vec![0, 2]);
+        assert_eq!(near(&["b", "a"], 1, false), vec![0, 1, 2, 5]);
+        assert_eq!(near(&["b", "b"], 0, true), vec![4]);
+        assert_eq!(near(&["a", "b", "c"], 0, true), vec![0]);
+        assert!(near(&["a", "missing"], 10, false).is_empty());
+
+        // "c d", followed by "a b" with at most one position in between.
+        let nested = SpanQuery::near(
+            vec![
+                SpanQuery::near(vec![term("c"), term("d")], 0, true),
+                SpanQuery::near(vec![term("a"), term("b")], 1, true),
+            ],
+            0,
+            true,
+        );
+        assert_eq!(matching_docs(&index, &nested), vec![5]);
+    }
+
+    #[test]
+    pub fn test_span_or_not_first_queries() {
+        let (index, text_field) = create_index(&["a b c d", "d c b a", "x y", "a x d"]);
+        let term = |text: &str| SpanQuery::term(Term::from_field_text(text_field, text));
+        let a_or_x = SpanQuery::or(vec![term("a"), term("x"), term("missing")]);
+        assert_eq!(matching_docs(&index, &a_or_x), vec![0, 1, 2, 3]);
+        let a_or_x_then_d = SpanQuery::near(vec![a_or_x.clone(), term("d")], 0, true);
+        assert_eq!(matching_docs(&index, &a_or_x_then_d), vec![3]);
+
+        assert_eq!(
+            matching_docs(&index, &SpanQuery::first(term("a"), 1)),
+            vec![0, 3]
+        );
+        assert_eq!(
+            matching_docs(&index, &SpanQuery::first(term("a"), 4)),
+            vec![0, 1, 3]
+        );
+        let first_d = SpanQuery::first(SpanQuery::near(vec![term("x"), term("d")], 0, true), 2);
+        assert!(matching_docs(&index, &first_d).is_empty());
+
+        assert_eq!(
+            matching_docs(&index, &SpanQuery::not(term("d"), term("missing"))),
+            vec![0, 1, 3]
+        );
+        // "d" not directly preceded by "c".
+        let not_c_d = SpanQuery::not_within(term("d"), term("c"), 1, 0);
+        assert_eq!(matching_docs(&index, &not_c_d), vec![1, 3]);
+        // "a" not directly followed by "b".
+        let not_a_b = SpanQuery::not_within(term("a"), term("b"), 0, 1);
+        assert_eq!(matching_docs(&index, &not_a_b), vec![1, 3]);
+        let not_a_or_x = SpanQuery::not(a_or_x, term("x"));
+        assert_eq!(matching_docs(&index, &not_a_or_x), vec![0, 1, 3]);
+    }
+
+    #[test]
+    pub fn test_span_query_score() {
+        let (index, text_field) = create_index(&["a b c", "a b c a b", "a x b", "a x b a b"]);
+        let searcher = index.reader().unwrap().searcher();
+        let terms = vec![
+            Term::from_field_text(text_field, "a"),
+            Term::from_field_text(text_field, "b"),
+        ];
+        let span_clauses = terms.iter().cloned().map(SpanQuery::term).collect();
+        let near_query = SpanQuery::near(span_clauses, 1, true);
+        let mut phrase_query = PhraseQuery::new(terms);
+        phrase_query.set_slop(1);
+        let score = |query: &Query, doc: DocId| {
+            searcher.explain(query, DocAddress(0, doc)).unwrap().value()
+        };
+        for doc in 0..4 {
+            assert_nearly_equals(score(&near_query, doc), score(&phrase_query, doc));
+        }
+        assert!(score(&near_query, 1) > score(&near_query, 0));
+        assert!(score(&near_query, 2) < score(&near_query, 0));
+
+        let scores = searcher
+            .search(&near_query, &TestCollector)
+            .unwrap()
+            .scores()
+            .to_vec();
+        for (doc, &score) in scores.iter().enumerate() {
+            let explanation = searcher
+                .explain(&near_query, DocAddress(0, doc as DocId))
+                .unwrap();
+            assert_nearly_equals(score, explanation.value());
+        }
+        let explanation = searcher.explain(&near_query, DocAddress(0, 2)).unwrap();
+        let tf_explanation = &explanation.details()[0].details()[2];
+        assert_nearly_equals(tf_explanation.details()[0].value(), 0.5);
+    }
+
+    #[test]
+    pub fn test_span_query_errors() {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let id_field = schema_builder.add_text_field("id", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(text_field=>"a b", id_field=>"a"));
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let text_term = SpanQuery::term(Term::from_field_text(text_field, "a"));
+        let id_term = SpanQuery::term(Term::from_field_text(id_field, "a"));
+        let mixed_fields = SpanQuery::near(vec![text_term.clone(), id_term.clone()], 1, false);
+        match searcher.search(&mixed_fields, &Count) {
+            Err(TantivyError::InvalidArgument(_)) => {}
+            _ => panic!("Should have returned an error"),
+        }
+        if let TantivyError::SchemaError(ref msg) = searcher
+            .search(&SpanQuery::first(id_term, 1), &Count)
+            .unwrap_err()
+        {
+            assert_eq!(
+                "Applied span query on field \"id\", which does not have positions indexed",
+                msg.as_str()
+            );
+        } else {
+            panic!("Should have returned an error");
+        }
+        assert_eq!(
+            searcher
+                .search(&SpanQuery::near(vec![], 1, false), &Count)
+                .unwrap(),
+            0
+        );
+
+        let mut terms = BTreeSet::new();
+        SpanQuery::not(
+            text_term,
+            SpanQuery::term(Term::from_field_text(text_field, "b")),
+        )
+        .query_terms(&mut terms);
+        assert_eq!(terms.len(), 1);
+    }
+
+    #[test]
+    pub fn test_span_query_with_deletes() {
+        let (index, text_field) = create_index(&["a b", "a c b", "a b"]);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.delete_term(Term::from_field_text(text_field, "c"));
+            assert!(index_writer.commit().is_ok());
+        }
+        let term = |text: &str| SpanQuery::term(Term::from_field_text(text_field, text));
+        let near_query = SpanQuery::near(vec![term("a"), term("b")], 1, true);
+        assert_eq!(matching_docs(&index, &near_query), vec![0, 2]);
+    }
+}
@@ -0,0 +1,302 @@
+use super::spans::{FirstSpans, NearSpans, NotSpans, OrSpans, Spans, TermSpans};
+use super::SpanWeight;
+use core::SegmentReader;
+use error::TantivyError;
+use query::similarity;
+use query::EmptyWeight;
+use query::Query;
+use query::Weight;
+use schema::IndexRecordOption;
+use schema::{Field, Term};
+use std::collections::BTreeSet;
+use Result;
+use Searcher;
+
+/// `SpanQuery` matches the documents according to the positions
+/// of their terms, e.g. "alice within 5 words of bob, in order"
+/// or "rust within the first 20 words".
+///
+/// A span query matches spans of positions within a document,
+/// and is built by combining the spans of other span queries:
+///
+/// - `SpanQuery::Term` matches the positions of a term.
+/// - `SpanQuery::Near` matches the spans made of a span of each of its clauses, separated by at most `slop` positions, and in the order of the clauses if `in_order` is true.
+/// - `SpanQuery::Or` matches the spans of any of its clauses.
+/// - `SpanQuery::Not` matches the spans of `include` not overlapping the spans of `exclude`, nor following them by less than `pre` positions or preceding them by less than `post` positions.
+/// - `SpanQuery::First` matches the spans of `query` ending at most at position `end`.
+///
+/// All of the terms of a span query must belong to the same field,
+/// which must have its positions indexed.
+///
+/// The documents are scored with BM25, using the sum of the sloppy frequencies
+/// of their spans as term frequency: a span with a slop of `n` counts for `1 / (1 + n)`,
+/// so that documents with more and tighter matches rank higher.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::collector::Count;
+/// use tantivy::query::SpanQuery;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{Index, Result, Term};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = Schema::builder();
+///     let body = schema_builder.add_text_field("body", TEXT);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(body => "alice sent a message to bob"));
+///         index_writer.add_document(doc!(body => "bob sent a message to alice"));
+///         index_writer.add_document(doc!(body => "alice, carol, dave, eve, frank, grace and bob"));
+///         index_writer.commit()?;
+///     }
+///     let searcher = index.reader()?.searcher();
+///
+///     let term = |text: &str| SpanQuery::term(Term::from_field_text(body, text));
+///     let alice_then_bob = SpanQuery::near(vec![term("alice"), term("bob")], 5, true);
+///     assert_eq!(searcher.search(&alice_then_bob, &Count)?, 1);
+///     let alice_near_bob = SpanQuery::near(vec![term("alice"), term("bob")], 5, false);
+///     assert_eq!(searcher.search(&alice_near_bob, &Count)?, 2);
+///     let alice_first = SpanQuery::first(term("alice"), 1);
+///     assert_eq!(searcher.search(&alice_first, &Count)?, 2);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpanQuery {
+    /// Matches the positions of a term.
+    Term(Term),
+    /// Matches the spans made of a span of each of the clauses, close to one another.
+    Near {
+        /// Span queries matched.
+        clauses: Vec<SpanQuery>,
+        /// Maximum number of positions between the spans of the clauses.
+        slop: u32,
+        /// Whether the spans must be in the order of the clauses.
+        in_order: bool,
+    },
+    /// Matches the spans of any of the clauses.
+    Or(Vec<SpanQuery>),
+    /// Matches the spans of a span query not close to the spans of another one.
+    Not {
+        /// Span query matched.
+        include: Box<SpanQuery>,
+        /// Span query whose spans must not overlap the matched spans.
+        exclude: Box<SpanQuery>,
+        /// Minimum number of positions between an excluded span and a following matched span.
+        pre: u32,
+        /// Minimum number of positions between a matched span and a following excluded span.
+        post: u32,
+    },
+    /// Matches the spans of a span query ending at most at a given position.
+    First {
+        /// Span query matched.
+        query: Box<SpanQuery>,
+        /// Position the spans must end before, i.e. the number of leading positions searched.
+        end: u32,
+    },
+}
+
+impl SpanQuery {
+    /// Creates a span query matching the positions of a term.
+    pub fn term(term: Term) -> SpanQuery {
+        SpanQuery::Term(term)
+    }
+
+    /// Creates a span query matching a span of each of the clauses,
+    /// with at most `slop` positions between them.
+    pub fn near(clauses: Vec<SpanQuery>, slop: u32, in_order: bool) -> SpanQuery {
+        SpanQuery::Near {
+            clauses,
+            slop,
+            in_order,
+        }
+    }
+
+    /// Creates a span query matching the spans of any of the clauses.
+    pub fn or(clauses: Vec<SpanQuery>) -> SpanQuery {
+        SpanQuery::Or(clauses)
+    }
+
+    /// Creates a span query matching the spans of `include` not overlapping
+    /// the spans of `exclude`.
+    pub fn not(include: SpanQuery, exclude: SpanQuery) -> SpanQuery {
+        SpanQuery::not_within(include, exclude, 0, 0)
+    }
+
+    /// Creates a span query matching the spans of `include` that do not
+    /// follow a span of `exclude` by less than `pre` positions, nor precede
+    /// it by less than `post` positions.
+    pub fn not_within(include: SpanQuery, exclude: SpanQuery, pre: u32, post: u32) -> SpanQuery {
+        SpanQuery::Not {
+            include: Box::new(include),
+            exclude: Box::new(exclude),
+            pre,
+            post,
+        }
+    }
+
+    /// Creates a span query matching the spans of `query` within
+    /// the first `end` positions.
+    pub fn first(query: SpanQuery, end: u32) -> SpanQuery {
+        SpanQuery::First {
+            query: Box::new(query),
+            end,
+        }
+    }
+
+    /// Appends the terms whose positions are matched, i.e. all of the terms
+    /// but the ones of the `exclude` clauses.
+    fn matched_terms(&self, terms: &mut Vec<Term>) {
+        match *self {
+            SpanQuery::Term(ref term) => terms.push(term.clone()),
+            SpanQuery::Near { ref clauses, .. } | SpanQuery::Or(ref clauses) => {
+                for clause in clauses {
+                    clause.matched_terms(terms);
+                }
+            }
+            SpanQuery::Not { ref include, .. } => include.matched_terms(terms),
+            SpanQuery::First { ref query, .. } => query.matched_terms(terms),
+        }
+    }
+
+    /// Appends all of the terms of the span query.
+    fn all_terms(&self, terms: &mut Vec<Term>) {
+        match *self {
+            SpanQuery::Not {
+                ref include,
+                ref exclude,
+                ..
+            } => {
+                include.all_terms(terms);
+                exclude.all_terms(terms);
+            }
+            SpanQuery::Near { ref clauses, .. } | SpanQuery::Or(ref clauses) => {
+                for clause in clauses {
+                    clause.all_terms(terms);
+                }
+            }
+            SpanQuery::First { ref query, .. } => query.all_terms(terms),
+            SpanQuery::Term(ref term) => terms.push(term.clone()),
+        }
+    }
+
+    /// Returns the spans of the segment, or `None` if
+    /// the span query cannot match any document.
+    pub(crate) fn spans(&self, reader: &SegmentReader) -> Option<Box<Spans>> {
+        match *self {
+            SpanQuery::Term(ref term) => {
+                let inverted_index = reader.inverted_index(term.field());
+                let postings_opt = if reader.has_deletes() {
+                    inverted_index.read_postings(term, IndexRecordOption::WithFreqsAndPositions)
+                } else {
+                    inverted_index
+                        .read_postings_no_deletes(term, IndexRecordOption::WithFreqsAndPositions)
+                };
+                postings_opt.map(|postings| Box::new(TermSpans::new(postings)) as Box<Spans>)
+            }
+            SpanQuery::Near {
+                ref clauses,
+                slop,
+                in_order,
+            } => {
+                if clauses.is_empty() {
+                    return None;
+                }
+                let clause_spans = clauses
+                    .iter()
+                    .map(|clause| clause.spans(reader))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Box::new(NearSpans::new(clause_spans, slop, in_order)))
+            }
+            SpanQuery::Or(ref clauses) => {
+                let clause_spans = clauses
+                    .iter()
+                    .filter_map(|clause| clause.spans(reader))
+                    .collect::<Vec<_>>();
+                if clause_spans.is_empty() {
+                    None
+                } else {
+                    Some(Box::new(OrSpans::new(clause_spans)))
+                }
+            }
+            SpanQuery::Not {
+                ref include,
+                ref exclude,
+                pre,
+                post,
+            } => {
+                let include_spans = include.spans(reader)?;
+                if let Some(exclude_spans) = exclude.spans(reader) {
+                    Some(Box::new(NotSpans::new(
+                        include_spans,
+                        exclude_spans,
+                        pre,
+                        post,
+                    )))
+                } else {
+                    Some(include_spans)
+                }
+            }
+            SpanQuery::First { ref query, end } => {
+                let spans = query.spans(reader)?;
+                Some(Box::new(FirstSpans::new(spans, end)))
+            }
+        }
+    }
+}
+
+impl Query for SpanQuery {
+    /// Create the weight associated to a query.
+    ///
+    /// See [`Weight`](./trait.Weight.html).
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        let mut terms = Vec::new();
+        self.all_terms(&mut terms);
+        let field: Field = if let Some(term) = terms.first() {
+            term.field()
+        } else {
+            return Ok(Box::new(EmptyWeight));
+        };
+        if terms.iter().any(|term| term.field() != field) {
+            return Err(TantivyError::InvalidArgument(
+                "All of the terms of a span query must belong to the same field".to_string(),
+            ));
+        }
+        let schema = searcher.schema();
+        let field_entry = schema.get_field_entry(field);
+        let has_positions = field_entry
+            .field_type()
+            .get_index_record_option()
+            .map(IndexRecordOption::has_positions)
+            .unwrap_or(false);
+        if !has_positions {
+            let field_name = field_entry.name();
+            return Err(TantivyError::SchemaError(format!(
+                "Applied span query on field {:?}, which does not have positions indexed",
+                field_name
+            )));
+        }
+        let mut matched_terms = Vec::new();
+        self.matched_terms(&mut matched_terms);
+        let similarity_weight = if scoring_enabled {
+            similarity::weight_for_terms(searcher, &matched_terms)?
+        } else {
+            similarity::disabled_weight()
+        };
+        Ok(Box::new(SpanWeight::new(
+            self.clone(),
+            field,
+            similarity_weight,
+        )))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        let mut terms = Vec::new();
+        self.matched_terms(&mut terms);
+        term_set.extend(terms);
+    }
+}
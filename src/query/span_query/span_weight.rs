@@ -0,0 +1,135 @@
+use super::spans::Spans;
+use super::SpanQuery;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use fieldnorm::FieldNormReader;
+use query::EmptyScorer;
+use query::SimilarityWeight;
+use query::{does_not_match, Explanation};
+use query::{Scorer, Weight};
+use schema::Field;
+use std::sync::Arc;
+use DocId;
+use Result;
+use Score;
+
+/// Sum of the sloppy frequencies of the spans, a span
+/// matched with a slop of `n` counting for `1 / (1 + n)`.
+fn span_freq(spans: &mut Spans) -> f32 {
+    spans
+        .spans()
+        .iter()
+        .map(|span| 1f32 / (1f32 + span.slop as f32))
+        .sum()
+}
+
+pub struct SpanWeight {
+    query: SpanQuery,
+    field: Field,
+    similarity_weight: Arc<SimilarityWeight>,
+}
+
+impl SpanWeight {
+    /// Creates a new span weight.
+    pub fn new(
+        query: SpanQuery,
+        field: Field,
+        similarity_weight: Arc<SimilarityWeight>,
+    ) -> SpanWeight {
+        SpanWeight {
+            query,
+            field,
+            similarity_weight,
+        }
+    }
+
+    fn span_scorer(&self, reader: &SegmentReader) -> Option<SpanScorer> {
+        let spans = self.query.spans(reader)?;
+        Some(SpanScorer {
+            spans,
+            fieldnorm_reader: reader.get_fieldnorms_reader(self.field),
+            similarity_weight: self.similarity_weight.clone(),
+            started: false,
+        })
+    }
+}
+
+impl Weight for SpanWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        if let Some(scorer) = self.span_scorer(reader) {
+            Ok(Box::new(scorer))
+        } else {
+            Ok(Box::new(EmptyScorer))
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut scorer = self
+            .span_scorer(reader)
+            .ok_or_else(|| does_not_match(doc))?;
+        if scorer.skip_next(doc) != SkipResult::Reached {
+            return Err(does_not_match(doc));
+        }
+        let similarity_explanation = scorer.explain();
+        let mut explanation = Explanation::new("SpanQuery", similarity_explanation.value());
+        explanation.add_detail(similarity_explanation);
+        Ok(explanation)
+    }
+}
+
+/// Scores the documents matching a `SpanQuery` with BM25,
+/// using the sloppy frequency of their spans as term frequency.
+pub struct SpanScorer {
+    spans: Box<Spans>,
+    fieldnorm_reader: FieldNormReader,
+    similarity_weight: Arc<SimilarityWeight>,
+    started: bool,
+}
+
+impl SpanScorer {
+    fn explain(&mut self) -> Explanation {
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(self.doc());
+        let freq = span_freq(self.spans.as_mut());
+        self.similarity_weight.explain(fieldnorm_id, freq)
+    }
+}
+
+impl DocSet for SpanScorer {
+    fn advance(&mut self) -> bool {
+        let target = if self.started { self.doc() + 1 } else { 0 };
+        self.started = true;
+        self.spans.seek(target)
+    }
+
+    fn skip_next(&mut self, target: DocId) -> SkipResult {
+        let target = if self.started {
+            target.max(self.doc() + 1)
+        } else {
+            target
+        };
+        self.started = true;
+        if !self.spans.seek(target) {
+            SkipResult::End
+        } else if self.doc() == target {
+            SkipResult::Reached
+        } else {
+            SkipResult::OverStep
+        }
+    }
+
+    fn doc(&self) -> DocId {
+        self.spans.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.spans.size_hint()
+    }
+}
+
+impl Scorer for SpanScorer {
+    fn score(&mut self) -> Score {
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(self.doc());
+        let freq = span_freq(self.spans.as_mut());
+        self.similarity_weight.score_with_freq(fieldnorm_id, freq)
+    }
+}
@@ -0,0 +1,552 @@
+use docset::{DocSet, SkipResult};
+use postings::{Postings, SegmentPostings};
+use DocId;
+
+/// A span of token positions, matched by a `SpanQuery` within a document.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Span {
+    /// Position of the first token of the span.
+    pub start: u32,
+    /// Position following the last token of the span.
+    pub end: u32,
+    /// Number of tokens skipped within the span to match it,
+    /// e.g. `0` for a term or an exact sequence.
+    pub slop: u32,
+}
+
+impl Span {
+    fn len(&self) -> u32 {
+        self.end - self.start
+    }
+}
+
+/// Iterates over the documents having at least one span matching
+/// a `SpanQuery`, giving access to those spans.
+pub trait Spans {
+    /// Positions the spans on the first matching document greater than or
+    /// equal to `target`, and returns false if there is no such document.
+    ///
+    /// Unlike `DocSet::skip_next`, this does not move if the spans are
+    /// already positioned on a document greater than or equal to `target`.
+    fn seek(&mut self, target: DocId) -> bool;
+
+    /// Returns the current document.
+    fn doc(&self) -> DocId;
+
+    /// Returns the spans of the current document, sorted by position.
+    ///
+    /// There is always at least one span.
+    fn spans(&mut self) -> &[Span];
+
+    /// Returns a best-effort hint of the number of documents matching.
+    fn size_hint(&self) -> u32;
+}
+
+/// Spans of the positions of a term.
+pub struct TermSpans {
+    postings: SegmentPostings,
+    started: bool,
+    ended: bool,
+    positions: Vec<u32>,
+    spans: Vec<Span>,
+    spans_doc: Option<DocId>,
+}
+
+impl TermSpans {
+    pub fn new(postings: SegmentPostings) -> TermSpans {
+        TermSpans {
+            postings,
+            started: false,
+            ended: false,
+            positions: Vec::new(),
+            spans: Vec::new(),
+            spans_doc: None,
+        }
+    }
+}
+
+impl Spans for TermSpans {
+    fn seek(&mut self, target: DocId) -> bool {
+        if self.ended {
+            return false;
+        }
+        if !self.started {
+            self.started = true;
+            if !self.postings.advance() {
+                self.ended = true;
+                return false;
+            }
+        }
+        if self.postings.doc() < target && self.postings.skip_next(target) == SkipResult::End {
+            self.ended = true;
+            return false;
+        }
+        true
+    }
+
+    fn doc(&self) -> DocId {
+        self.postings.doc()
+    }
+
+    fn spans(&mut self) -> &[Span] {
+        // Positions are only decoded when the spans of a document are needed.
+        let doc = self.postings.doc();
+        if self.spans_doc != Some(doc) {
+            self.postings.positions(&mut self.positions);
+            self.spans.clear();
+            self.spans
+                .extend(self.positions.iter().map(|&position| Span {
+                    start: position,
+                    end: position + 1,
+                    slop: 0,
+                }));
+            self.spans_doc = Some(doc);
+        }
+        &self.spans[..]
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings.size_hint()
+    }
+}
+
+/// Pushes to `output` the spans made of a span of each of `clauses`,
+/// in order and not overlapping, with at most `slop` positions between them.
+fn ordered_near_spans(clauses: &[Vec<Span>], slop: u32, output: &mut Vec<Span>) {
+    for first in &clauses[0] {
+        let mut end = first.end;
+        let mut len = first.len();
+        let mut inner_slop = first.slop;
+        let mut complete = true;
+        for clause in &clauses[1..] {
+            // The earliest following span leaves the least room between the spans.
+            if let Some(next) = clause.iter().find(|span| span.start >= end) {
+                end = next.end;
+                len += next.len();
+                inner_slop += next.slop;
+            } else {
+                complete = false;
+                break;
+            }
+        }
+        if !complete {
+            continue;
+        }
+        let gaps = end - first.start - len;
+        if gaps <= slop {
+            output.push(Span {
+                start: first.start,
+                end,
+                slop: gaps + inner_slop,
+            });
+        }
+    }
+}
+
+/// Pushes to `output` the spans made of a span of each of `clauses`,
+/// in any order and not overlapping, with at most `slop` positions between them.
+fn unordered_near_spans(clauses: &[Vec<Span>], slop: u32, output: &mut Vec<Span>) {
+    let mut cursors = vec![0usize; clauses.len()];
+    let mut window: Vec<Span> = Vec::with_capacity(clauses.len());
+    loop {
+        window.clear();
+        window.extend(
+            clauses
+                .iter()
+                .zip(cursors.iter())
+                .map(|(clause, &cursor)| clause[cursor]),
+        );
+        window.sort();
+        let overlaps = window
+            .windows(2)
+            .any(|consecutive| consecutive[1].start < consecutive[0].end);
+        if !overlaps {
+            let start = window[0].start;
+            let end = window.iter().map(|span| span.end).max().unwrap_or(start);
+            let len: u32 = window.iter().map(Span::len).sum();
+            let gaps = end - start - len;
+            if gaps <= slop {
+                let inner_slop: u32 = window.iter().map(|span| span.slop).sum();
+                output.push(Span {
+                    start,
+                    end,
+                    slop: gaps + inner_slop,
+                });
+            }
+        }
+        // Moves the span starting first forward.
+        let (min_ord, _) = clauses
+            .iter()
+            .zip(cursors.iter())
+            .enumerate()
+            .min_by_key(|&(_, (clause, &cursor))| clause[cursor])
+            .expect("Near spans require at least one clause");
+        cursors[min_ord] += 1;
+        if cursors[min_ord] == clauses[min_ord].len() {
+            return;
+        }
+    }
+}
+
+/// Spans made of a span of each of its clauses, close to one another.
+pub struct NearSpans {
+    clauses: Vec<Box<Spans>>,
+    slop: u32,
+    in_order: bool,
+    positioned: bool,
+    doc: DocId,
+    clause_spans: Vec<Vec<Span>>,
+    spans: Vec<Span>,
+}
+
+impl NearSpans {
+    pub fn new(clauses: Vec<Box<Spans>>, slop: u32, in_order: bool) -> NearSpans {
+        assert!(
+            !clauses.is_empty(),
+            "Near spans require at least one clause"
+        );
+        let clause_spans = clauses.iter().map(|_| Vec::new()).collect();
+        NearSpans {
+            clauses,
+            slop,
+            in_order,
+            positioned: false,
+            doc: 0,
+            clause_spans,
+            spans: Vec::new(),
+        }
+    }
+
+    /// Positions all of the clauses on the first document greater than
+    /// or equal to `target` they all match.
+    fn align(&mut self, target: DocId) -> bool {
+        let mut candidate = target;
+        'align: loop {
+            for clause in &mut self.clauses {
+                if !clause.seek(candidate) {
+                    return false;
+                }
+                if clause.doc() > candidate {
+                    candidate = clause.doc();
+                    continue 'align;
+                }
+            }
+            self.doc = candidate;
+            return true;
+        }
+    }
+
+    fn compute_spans(&mut self) {
+        for (clause, clause_spans) in self.clauses.iter_mut().zip(self.clause_spans.iter_mut()) {
+            clause_spans.clear();
+            clause_spans.extend_from_slice(clause.spans());
+        }
+        self.spans.clear();
+        if self.in_order {
+            ordered_near_spans(&self.clause_spans, self.slop, &mut self.spans);
+        } else {
+            unordered_near_spans(&self.clause_spans, self.slop, &mut self.spans);
+        }
+        self.spans.sort();
+        self.spans.dedup();
+    }
+}
+
+impl Spans for NearSpans {
+    fn seek(&mut self, target: DocId) -> bool {
+        if self.positioned && self.doc >= target {
+            return true;
+        }
+        let mut candidate = target;
+        loop {
+            if !self.align(candidate) {
+                self.positioned = false;
+                return false;
+            }
+            self.compute_spans();
+            if !self.spans.is_empty() {
+                self.positioned = true;
+                return true;
+            }
+            candidate = self.doc + 1;
+        }
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn spans(&mut self) -> &[Span] {
+        &self.spans[..]
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.clauses
+            .iter()
+            .map(|clause| clause.size_hint())
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+/// Spans of any of its clauses.
+pub struct OrSpans {
+    clauses: Vec<Box<Spans>>,
+    positioned: bool,
+    doc: DocId,
+    spans: Vec<Span>,
+}
+
+impl OrSpans {
+    pub fn new(clauses: Vec<Box<Spans>>) -> OrSpans {
+        OrSpans {
+            clauses,
+            positioned: false,
+            doc: 0,
+            spans: Vec::new(),
+        }
+    }
+}
+
+impl Spans for OrSpans {
+    fn seek(&mut self, target: DocId) -> bool {
+        if self.positioned && self.doc >= target {
+            return true;
+        }
+        let mut ord = 0;
+        while ord < self.clauses.len() {
+            if self.clauses[ord].seek(target) {
+                ord += 1;
+            } else {
+                self.clauses.swap_remove(ord);
+            }
+        }
+        let doc_opt = self.clauses.iter().map(|clause| clause.doc()).min();
+        if let Some(doc) = doc_opt {
+            self.doc = doc;
+            self.spans.clear();
+            for clause in &mut self.clauses {
+                if clause.doc() == doc {
+                    self.spans.extend_from_slice(clause.spans());
+                }
+            }
+            self.spans.sort();
+            self.spans.dedup();
+            self.positioned = true;
+            true
+        } else {
+            self.positioned = false;
+            false
+        }
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn spans(&mut self) -> &[Span] {
+        &self.spans[..]
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.clauses
+            .iter()
+            .map(|clause| clause.size_hint())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Spans of a clause not overlapping the spans of another one.
+pub struct NotSpans {
+    include: Box<Spans>,
+    exclude: Box<Spans>,
+    pre: u32,
+    post: u32,
+    positioned: bool,
+    spans: Vec<Span>,
+    exclude_spans: Vec<Span>,
+}
+
+impl NotSpans {
+    pub fn new(include: Box<Spans>, exclude: Box<Spans>, pre: u32, post: u32) -> NotSpans {
+        NotSpans {
+            include,
+            exclude,
+            pre,
+            post,
+            positioned: false,
+            spans: Vec::new(),
+            exclude_spans: Vec::new(),
+        }
+    }
+}
+
+impl Spans for NotSpans {
+    fn seek(&mut self, target: DocId) -> bool {
+        if self.positioned && self.include.doc() >= target {
+            return true;
+        }
+        let mut candidate = target;
+        loop {
+            if !self.include.seek(candidate) {
+                self.positioned = false;
+                return false;
+            }
+            let doc = self.include.doc();
+            self.spans.clear();
+            self.spans.extend_from_slice(self.include.spans());
+            if self.exclude.seek(doc) && self.exclude.doc() == doc {
+                self.exclude_spans.clear();
+                self.exclude_spans.extend_from_slice(self.exclude.spans());
+                let (pre, post) = (self.pre, self.post);
+                let exclude_spans = &self.exclude_spans;
+                self.spans.retain(|span| {
+                    !exclude_spans.iter().any(|excluded| {
+                        excluded.start < span.end + post && span.start < excluded.end + pre
+                    })
+                });
+            }
+            if !self.spans.is_empty() {
+                self.positioned = true;
+                return true;
+            }
+            candidate = doc + 1;
+        }
+    }
+
+    fn doc(&self) -> DocId {
+        self.include.doc()
+    }
+
+    fn spans(&mut self) -> &[Span] {
+        &self.spans[..]
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.include.size_hint()
+    }
+}
+
+/// Spans of a clause ending before a given position.
+pub struct FirstSpans {
+    spans: Box<Spans>,
+    end: u32,
+    positioned: bool,
+    first_spans: Vec<Span>,
+}
+
+impl FirstSpans {
+    pub fn new(spans: Box<Spans>, end: u32) -> FirstSpans {
+        FirstSpans {
+            spans,
+            end,
+            positioned: false,
+            first_spans: Vec::new(),
+        }
+    }
+}
+
+impl Spans for FirstSpans {
+    fn seek(&mut self, target: DocId) -> bool {
+        if self.positioned && self.spans.doc() >= target {
+            return true;
+        }
+        let mut candidate = target;
+        loop {
+            if !self.spans.seek(candidate) {
+                self.positioned = false;
+                return false;
+            }
+            let end = self.end;
+            self.first_spans.clear();
+            self.first_spans.extend(
+                self.spans
+                    .spans()
+                    .iter()
+                    .cloned()
+                    .filter(|span| span.end <= end),
+            );
+            if !self.first_spans.is_empty() {
+                self.positioned = true;
+                return true;
+            }
+            candidate = self.spans.doc() + 1;
+        }
+    }
+
+    fn doc(&self) -> DocId {
+        self.spans.doc()
+    }
+
+    fn spans(&mut self) -> &[Span] {
+        &self.first_spans[..]
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.spans.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{ordered_near_spans, unordered_near_spans, Span};
+
+    fn term_spans(positions: &[u32]) -> Vec<Span> {
+        positions
+            .iter()
+            .map(|&position| Span {
+                start: position,
+                end: position + 1,
+                slop: 0,
+            })
+            .collect()
+    }
+
+    fn near_spans(clauses: &[&[u32]], slop: u32, in_order: bool) -> Vec<(u32, u32, u32)> {
+        let clauses: Vec<Vec<Span>> = clauses
+            .iter()
+            .map(|positions| term_spans(positions))
+            .collect();
+        let mut spans = Vec::new();
+        if in_order {
+            ordered_near_spans(&clauses, slop, &mut spans);
+        } else {
+            unordered_near_spans(&clauses, slop, &mut spans);
+        }
+        spans.sort();
+        spans.dedup();
+        spans
+            .into_iter()
+            .map(|span| (span.start, span.end, span.slop))
+            .collect()
+    }
+
+    #[test]
+    fn test_ordered_near_spans() {
+        assert_eq!(near_spans(&[&[1], &[2]], 0, true), vec![(1, 3, 0)]);
+        assert_eq!(near_spans(&[&[1], &[4]], 1, true), vec![]);
+        assert_eq!(near_spans(&[&[1], &[4]], 2, true), vec![(1, 5, 2)]);
+        assert_eq!(near_spans(&[&[4], &[1]], 5, true), vec![]);
+        assert_eq!(near_spans(&[&[1], &[1, 2]], 0, true), vec![(1, 3, 0)]);
+        assert_eq!(
+            near_spans(&[&[1, 7], &[2, 9], &[3, 10]], 1, true),
+            vec![(1, 4, 0), (7, 11, 1)]
+        );
+    }
+
+    #[test]
+    fn test_unordered_near_spans() {
+        assert_eq!(near_spans(&[&[1], &[2]], 0, false), vec![(1, 3, 0)]);
+        assert_eq!(near_spans(&[&[2], &[1]], 0, false), vec![(1, 3, 0)]);
+        assert_eq!(near_spans(&[&[5], &[1]], 2, false), vec![]);
+        assert_eq!(near_spans(&[&[5], &[1]], 3, false), vec![(1, 6, 3)]);
+        // the same token can not match two clauses
+        assert_eq!(near_spans(&[&[1], &[1]], 3, false), vec![]);
+        assert_eq!(
+            near_spans(&[&[1, 10], &[3, 12]], 1, false),
+            vec![(1, 4, 1), (10, 13, 1)]
+        );
+    }
+}
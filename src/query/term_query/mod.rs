@@ -14,6 +14,7 @@ mod tests {
     use query::{Query, QueryParser, Scorer, TermQuery};
     use schema::{IndexRecordOption, Schema, STRING, TEXT};
     use tests::assert_nearly_equals;
+    use DocAddress;
     use Index;
     use Term;
 
@@ -114,4 +115,44 @@ mod tests {
         let reader = index.reader().unwrap();
         assert_eq!(term_query.count(&*reader.searcher()).unwrap(), 1);
     }
+
+    #[test]
+    fn test_term_query_explain() {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_with_num_threads(1, 5_000_000).unwrap();
+        index_writer.add_document(doc!(text_field=>"a b a"));
+        index_writer.add_document(doc!(text_field=>"c"));
+        index_writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let term_query = TermQuery::new(
+            Term::from_field_text(text_field, "a"),
+            IndexRecordOption::WithFreqs,
+        );
+        let top_docs = searcher
+            .search(&term_query, &TopDocs::with_limit(1))
+            .unwrap();
+        let (score, doc_address) = top_docs[0];
+        let explanation = searcher.explain(&term_query, doc_address).unwrap();
+        assert_nearly_equals(score, explanation.value());
+        let bm25_explanation = &explanation.details()[0];
+        assert_eq!(bm25_explanation.details().len(), 3);
+        let idf_explanation = &bm25_explanation.details()[1];
+        assert!(idf_explanation.description().starts_with("idf"));
+        assert_eq!(idf_explanation.details()[0].value(), 1f32);
+        assert_eq!(idf_explanation.details()[1].value(), 2f32);
+        let tf_explanation = &bm25_explanation.details()[2];
+        assert!(tf_explanation.description().starts_with("tf"));
+        assert_eq!(tf_explanation.details()[0].value(), 2f32);
+        assert_eq!(tf_explanation.details()[3].value(), 3f32);
+        assert_nearly_equals(
+            score,
+            bm25_explanation.details()[0].value()
+                * idf_explanation.value()
+                * tf_explanation.value(),
+        );
+        assert!(searcher.explain(&term_query, DocAddress(0, 1)).is_err());
+    }
 }
@@ -1,5 +1,5 @@
 use super::term_weight::TermWeight;
-use query::bm25::BM25Weight;
+use query::similarity;
 use query::Query;
 use query::Weight;
 use schema::IndexRecordOption;
@@ -81,26 +81,44 @@ impl TermQuery {
         &self.term
     }
 
+    /// The postings information this query reads when scoring is enabled.
+    pub fn index_record_option(&self) -> IndexRecordOption {
+        self.index_record_option
+    }
+
     /// Returns a weight object.
     ///
     /// While `.weight(...)` returns a boxed trait object,
     /// this method return a specific implementation.
     /// This is useful for optimization purpose.
-    pub fn specialized_weight(&self, searcher: &Searcher, scoring_enabled: bool) -> TermWeight {
-        let term = self.term.clone();
-        let bm25_weight = BM25Weight::for_terms(searcher, &[term]);
-        let index_record_option = if scoring_enabled {
-            self.index_record_option
+    pub fn specialized_weight(
+        &self,
+        searcher: &Searcher,
+        scoring_enabled: bool,
+    ) -> Result<TermWeight> {
+        if scoring_enabled {
+            let term = self.term.clone();
+            let similarity_weight = similarity::weight_for_terms(searcher, &[term])?;
+            Ok(TermWeight::new(
+                self.term.clone(),
+                self.index_record_option,
+                similarity_weight,
+            ))
         } else {
-            IndexRecordOption::Basic
-        };
-        TermWeight::new(self.term.clone(), index_record_option, bm25_weight)
+            Ok(TermWeight::new(
+                self.term.clone(),
+                IndexRecordOption::Basic,
+                similarity::disabled_weight(),
+            ))
+        }
     }
 }
 
 impl Query for TermQuery {
     fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
-        Ok(Box::new(self.specialized_weight(searcher, scoring_enabled)))
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
     }
     fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
         term_set.insert(self.term.clone());
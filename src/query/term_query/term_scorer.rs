@@ -1,24 +1,26 @@
 use docset::{DocSet, SkipResult};
 use query::Scorer;
+use std::sync::Arc;
 use DocId;
 use Score;
 
 use fieldnorm::FieldNormReader;
 use postings::Postings;
 use postings::SegmentPostings;
-use query::bm25::BM25Weight;
+use query::Explanation;
+use query::SimilarityWeight;
 
 pub struct TermScorer {
     postings: SegmentPostings,
     fieldnorm_reader: FieldNormReader,
-    similarity_weight: BM25Weight,
+    similarity_weight: Arc<SimilarityWeight>,
 }
 
 impl TermScorer {
     pub fn new(
         postings: SegmentPostings,
         fieldnorm_reader: FieldNormReader,
-        similarity_weight: BM25Weight,
+        similarity_weight: Arc<SimilarityWeight>,
     ) -> TermScorer {
         TermScorer {
             postings,
@@ -26,6 +28,23 @@ impl TermScorer {
             similarity_weight,
         }
     }
+
+    /// Returns, for each block of the postings that has not been read yet,
+    /// its last document and an upper bound of the score of its documents.
+    pub(crate) fn block_max_scores(&self) -> Vec<(DocId, Score)> {
+        let similarity_weight = &self.similarity_weight;
+        self.postings
+            .block_max_scores(&self.fieldnorm_reader, |fieldnorm_id, term_freq| {
+                similarity_weight.score(fieldnorm_id, term_freq)
+            })
+    }
+
+    /// Explains the score of the current document.
+    pub fn explain(&self) -> Explanation {
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(self.doc());
+        let term_freq = self.postings.term_freq() as f32;
+        self.similarity_weight.explain(fieldnorm_id, term_freq)
+    }
 }
 
 impl DocSet for TermScorer {
@@ -1,41 +1,40 @@
 use super::term_scorer::TermScorer;
 use core::SegmentReader;
-use docset::DocSet;
+use docset::{DocSet, SkipResult};
 use postings::SegmentPostings;
-use query::bm25::BM25Weight;
 use query::Scorer;
+use query::SimilarityWeight;
 use query::Weight;
+use query::{does_not_match, Explanation};
 use schema::IndexRecordOption;
+use std::sync::Arc;
+use DocId;
 use Result;
 use Term;
 
 pub struct TermWeight {
     term: Term,
     index_record_option: IndexRecordOption,
-    similarity_weight: BM25Weight,
+    similarity_weight: Arc<SimilarityWeight>,
 }
 
 impl Weight for TermWeight {
     fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
-        let field = self.term.field();
-        let inverted_index = reader.inverted_index(field);
-        let fieldnorm_reader = reader.get_fieldnorms_reader(field);
-        let similarity_weight = self.similarity_weight.clone();
-        let postings_opt: Option<SegmentPostings> =
-            inverted_index.read_postings(&self.term, self.index_record_option);
-        if let Some(segment_postings) = postings_opt {
-            Ok(Box::new(TermScorer::new(
-                segment_postings,
-                fieldnorm_reader,
-                similarity_weight,
-            )))
-        } else {
-            Ok(Box::new(TermScorer::new(
-                SegmentPostings::empty(),
-                fieldnorm_reader,
-                similarity_weight,
-            )))
+        Ok(Box::new(self.specialized_scorer(reader)))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut scorer = self.specialized_scorer(reader);
+        if scorer.skip_next(doc) != SkipResult::Reached {
+            return Err(does_not_match(doc));
         }
+        let similarity_explanation = scorer.explain();
+        let mut explanation = Explanation::new(
+            format!("TermQuery({:?})", self.term),
+            similarity_explanation.value(),
+        );
+        explanation.add_detail(similarity_explanation);
+        Ok(explanation)
     }
 
     fn count(&self, reader: &SegmentReader) -> Result<u32> {
@@ -56,7 +55,7 @@ impl TermWeight {
     pub fn new(
         term: Term,
         index_record_option: IndexRecordOption,
-        similarity_weight: BM25Weight,
+        similarity_weight: Arc<SimilarityWeight>,
     ) -> TermWeight {
         TermWeight {
             term,
@@ -64,4 +63,22 @@ impl TermWeight {
             similarity_weight,
         }
     }
+
+    fn specialized_scorer(&self, reader: &SegmentReader) -> TermScorer {
+        let field = self.term.field();
+        let inverted_index = reader.inverted_index(field);
+        let fieldnorm_reader = reader.get_fieldnorms_reader(field);
+        let similarity_weight = self.similarity_weight.clone();
+        let postings_opt: Option<SegmentPostings> =
+            inverted_index.read_postings(&self.term, self.index_record_option);
+        if let Some(segment_postings) = postings_opt {
+            TermScorer::new(segment_postings, fieldnorm_reader, similarity_weight)
+        } else {
+            TermScorer::new(
+                SegmentPostings::empty(),
+                fieldnorm_reader,
+                similarity_weight,
+            )
+        }
+    }
 }
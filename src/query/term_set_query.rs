@@ -0,0 +1,333 @@
+use common::BitSet;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use query::BitSetDocSet;
+use query::ConstScorer;
+use query::{does_not_match, Explanation};
+use query::{Query, QueryCacheKey, Scorer, Weight};
+use schema::{Field, IndexRecordOption, Term};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use tantivy_fst::Automaton;
+use DocId;
+use Result;
+use Searcher;
+
+/// A Term Set Query matches all of the documents
+/// containing any of the given terms.
+///
+/// It is equivalent to `BooleanQuery::new_multiterms_query`, but all of the
+/// documents get the score `1f32`. In exchange, it is much cheaper than a
+/// `BooleanQuery` when searching for thousands of terms, e.g. to filter the
+/// documents belonging to one of many users.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::collector::Count;
+/// use tantivy::query::TermSetQuery;
+/// use tantivy::schema::{Schema, STRING};
+/// use tantivy::{Index, Result, Term};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = Schema::builder();
+///     let room_id = schema_builder.add_text_field("room_id", STRING);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(room_id => "!a:matrix.org"));
+///         index_writer.add_document(doc!(room_id => "!b:matrix.org"));
+///         index_writer.add_document(doc!(room_id => "!c:matrix.org"));
+///         index_writer.commit()?;
+///     }
+///     let searcher = index.reader()?.searcher();
+///
+///     let terms = vec![
+///         Term::from_field_text(room_id, "!a:matrix.org"),
+///         Term::from_field_text(room_id, "!c:matrix.org"),
+///         Term::from_field_text(room_id, "!d:matrix.org"),
+///     ];
+///     let query = TermSetQuery::new(terms);
+///     assert_eq!(searcher.search(&query, &Count)?, 2);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TermSetQuery {
+    terms_map: BTreeMap<Field, Vec<Term>>,
+}
+
+impl TermSetQuery {
+    /// Creates a new `TermSetQuery` matching the documents containing any of the terms.
+    ///
+    /// The terms may belong to different fields.
+    pub fn new<T: IntoIterator<Item = Term>>(terms: T) -> TermSetQuery {
+        let mut terms_map: BTreeMap<Field, Vec<Term>> = BTreeMap::new();
+        for term in terms {
+            terms_map
+                .entry(term.field())
+                .or_insert_with(Vec::new)
+                .push(term);
+        }
+        for terms in terms_map.values_mut() {
+            terms.sort();
+            terms.dedup();
+        }
+        TermSetQuery { terms_map }
+    }
+
+    /// Returns the terms searched, sorted by field and value.
+    pub fn terms(&self) -> Vec<&Term> {
+        self.terms_map
+            .values()
+            .flat_map(|terms| terms.iter())
+            .collect()
+    }
+}
+
+impl Query for TermSetQuery {
+    fn weight(&self, _searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        let automatons = self
+            .terms_map
+            .iter()
+            .map(|(&field, terms)| (field, TermSetAutomaton::new(terms)))
+            .collect();
+        Ok(Box::new(TermSetWeight { automatons }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for terms in self.terms_map.values() {
+            term_set.extend(terms.iter().cloned());
+        }
+    }
+
+    fn cache_key(&self) -> Option<QueryCacheKey> {
+        let mut cache_key = QueryCacheKey::new("term_set");
+        for terms in self.terms_map.values() {
+            for term in terms {
+                cache_key.push(term.as_slice());
+            }
+        }
+        Some(cache_key)
+    }
+}
+
+/// Automaton matching a set of byte strings.
+///
+/// Searching the term dictionary with it walks through the dictionary once,
+/// only following the paths leading to the terms of the set.
+struct TermSetAutomaton {
+    /// Sorted and deduplicated values of the terms.
+    values: Vec<Vec<u8>>,
+}
+
+impl TermSetAutomaton {
+    /// Creates an automaton matching the values of sorted and deduplicated terms.
+    fn new(terms: &[Term]) -> TermSetAutomaton {
+        TermSetAutomaton {
+            values: terms
+                .iter()
+                .map(|term| term.value_bytes().to_vec())
+                .collect(),
+        }
+    }
+}
+
+impl Automaton for TermSetAutomaton {
+    /// Range of the values starting with the bytes accepted so far, with the number
+    /// of accepted bytes, or `None` if no value starts with them.
+    type State = Option<(usize, usize, usize)>;
+
+    fn start(&self) -> Option<(usize, usize, usize)> {
+        if self.values.is_empty() {
+            None
+        } else {
+            Some((0, self.values.len(), 0))
+        }
+    }
+
+    fn is_match(&self, state: &Option<(usize, usize, usize)>) -> bool {
+        match *state {
+            Some((start, _, len)) => self.values[start].len() == len,
+            None => false,
+        }
+    }
+
+    fn can_match(&self, state: &Option<(usize, usize, usize)>) -> bool {
+        state.is_some()
+    }
+
+    fn accept(
+        &self,
+        state: &Option<(usize, usize, usize)>,
+        byte: u8,
+    ) -> Option<(usize, usize, usize)> {
+        let (start, end, len) = (*state)?;
+        let values = &self.values[start..end];
+        // The values share their first `len` bytes, so they are sorted by their
+        // byte at `len`, the value without such a byte coming first.
+        let first = values
+            .binary_search_by(|value| {
+                if value.get(len).cloned() < Some(byte) {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                }
+            })
+            .unwrap_err();
+        let last = values
+            .binary_search_by(|value| {
+                if value.get(len).cloned() <= Some(byte) {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                }
+            })
+            .unwrap_err();
+        if first < last {
+            Some((start + first, start + last, len + 1))
+        } else {
+            None
+        }
+    }
+}
+
+struct TermSetWeight {
+    automatons: BTreeMap<Field, TermSetAutomaton>,
+}
+
+impl Weight for TermSetWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let max_doc = reader.max_doc();
+        let mut doc_bitset = BitSet::with_max_value(max_doc);
+        for (&field, automaton) in &self.automatons {
+            let inverted_index = reader.inverted_index(field);
+            let mut term_stream = inverted_index.terms().search(automaton).into_stream();
+            while term_stream.advance() {
+                let mut block_segment_postings = inverted_index.read_block_postings_from_terminfo(
+                    term_stream.value(),
+                    IndexRecordOption::Basic,
+                );
+                while block_segment_postings.advance() {
+                    for &doc in block_segment_postings.docs() {
+                        doc_bitset.insert(doc);
+                    }
+                }
+            }
+        }
+        let doc_bitset = BitSetDocSet::from(doc_bitset);
+        Ok(Box::new(ConstScorer::new(doc_bitset)))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut scorer = self.scorer(reader)?;
+        if scorer.skip_next(doc) == SkipResult::Reached {
+            Ok(Explanation::new("TermSetQuery", 1f32))
+        } else {
+            Err(does_not_match(doc))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{TermSetAutomaton, TermSetQuery};
+    use collector::{Count, TopDocs};
+    use query::{BooleanQuery, Query};
+    use schema::{Field, Schema, STRING, TEXT};
+    use std::collections::BTreeSet;
+    use tantivy_fst::Automaton;
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use Index;
+    use Term;
+
+    #[test]
+    fn test_term_set_query() {
+        let mut schema_builder = Schema::builder();
+        let room_id = schema_builder.add_text_field("room_id", STRING);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            for i in 0..1_000u32 {
+                let room = format!("room{}", i % 100);
+                index_writer.add_document(doc!(room_id=>room, body=>"hello happy"));
+            }
+            index_writer.add_document(doc!(body=>"room1"));
+            index_writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let room_terms: Vec<Term> = (0..5_000u32)
+            .rev()
+            .filter(|i| i % 3 == 0)
+            .map(|i| Term::from_field_text(room_id, &format!("room{}", i)))
+            .collect();
+        let term_set_query = TermSetQuery::new(room_terms.clone());
+        let boolean_query = BooleanQuery::new_multiterms_query(room_terms);
+        assert_eq!(searcher.search(&term_set_query, &Count).unwrap(), 340);
+        assert_eq!(
+            searcher.search(&term_set_query, &Count).unwrap(),
+            searcher.search(&boolean_query, &Count).unwrap()
+        );
+        let top_docs = searcher
+            .search(&term_set_query, &TopDocs::with_limit(1))
+            .unwrap();
+        assert_nearly_equals(top_docs[0].0, 1f32);
+
+        let mixed_fields_query = TermSetQuery::new(vec![
+            Term::from_field_text(room_id, "room1"),
+            Term::from_field_text(body, "room1"),
+            Term::from_field_text(room_id, "room1"),
+        ]);
+        assert_eq!(mixed_fields_query.terms().len(), 2);
+        assert_eq!(searcher.search(&mixed_fields_query, &Count).unwrap(), 11);
+        let mut terms = BTreeSet::new();
+        mixed_fields_query.query_terms(&mut terms);
+        assert_eq!(terms.len(), 2);
+        assert!(searcher
+            .explain(&mixed_fields_query, DocAddress(0, 1_000))
+            .is_ok());
+        assert!(searcher
+            .explain(&mixed_fields_query, DocAddress(0, 2))
+            .is_err());
+
+        assert_eq!(
+            searcher
+                .search(&TermSetQuery::new(Vec::new()), &Count)
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_term_set_automaton() {
+        let field = Field(0);
+        let mut terms: Vec<Term> = ["ab", "abc", "b", "ba", ""]
+            .iter()
+            .map(|text| Term::from_field_text(field, text))
+            .collect();
+        terms.sort();
+        let automaton = TermSetAutomaton::new(&terms);
+        let is_match = |text: &str| {
+            let mut state = automaton.start();
+            for &byte in text.as_bytes() {
+                state = automaton.accept(&state, byte);
+            }
+            automaton.is_match(&state)
+        };
+        for text in &["ab", "abc", "b", "ba", ""] {
+            assert!(is_match(text), "{:?} should match", text);
+        }
+        for text in &["a", "abcd", "abd", "bb", "c", "bab"] {
+            assert!(!is_match(text), "{:?} should not match", text);
+        }
+        assert!(!automaton.can_match(&automaton.accept(&automaton.start(), b'c')));
+
+        let empty_automaton = TermSetAutomaton::new(&[]);
+        assert!(!empty_automaton.can_match(&empty_automaton.start()));
+    }
+}
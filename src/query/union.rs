@@ -14,7 +14,7 @@ const HORIZON: u32 = 64u32 * HORIZON_NUM_TINYBITSETS as u32;
 // it does not keep the original vector ordering.
 //
 // Also, it does not "yield" any elements.
-fn unordered_drain_filter<T, P>(v: &mut Vec<T>, mut predicate: P)
+pub(crate) fn unordered_drain_filter<T, P>(v: &mut Vec<T>, mut predicate: P)
 where
     P: FnMut(&mut T) -> bool,
 {
@@ -45,6 +45,24 @@ where
     TScorer: Scorer,
 {
     fn from(docsets: Vec<TScorer>) -> Union<TScorer, TScoreCombiner> {
+        Union::with_score_combiner(docsets, TScoreCombiner::default())
+    }
+}
+
+impl<TScorer, TScoreCombiner> Union<TScorer, TScoreCombiner>
+where
+    TScoreCombiner: ScoreCombiner,
+    TScorer: Scorer,
+{
+    /// Creates a `Union` of the docsets, combining the scores
+    /// of each document with a copy of `score_combiner`.
+    ///
+    /// This is useful for score combiners that are configured,
+    /// while `Union::from` uses `TScoreCombiner::default()`.
+    pub fn with_score_combiner(
+        docsets: Vec<TScorer>,
+        score_combiner: TScoreCombiner,
+    ) -> Union<TScorer, TScoreCombiner> {
         let non_empty_docsets: Vec<TScorer> = docsets
             .into_iter()
             .flat_map(
@@ -60,7 +78,7 @@ where
         Union {
             docsets: non_empty_docsets,
             bitsets: Box::new([TinySet::empty(); HORIZON_NUM_TINYBITSETS]),
-            scores: Box::new([TScoreCombiner::default(); HORIZON as usize]),
+            scores: Box::new([score_combiner; HORIZON as usize]),
             cursor: HORIZON_NUM_TINYBITSETS,
             offset: 0,
             doc: 0,
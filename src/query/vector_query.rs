@@ -0,0 +1,524 @@
+use core::SegmentReader;
+use docset::DocSet;
+use error::TantivyError;
+use query::cached_query::{alive_doc_bitset, CachedWeight};
+use query::{does_not_match, Explanation};
+use query::{Query, Scorer, Weight};
+use schema::{Field, FieldType};
+use std::num::Wrapping;
+use vector::VectorSimilarity;
+use DocId;
+use Result;
+use Score;
+use Searcher;
+
+/// Default number of candidates collected in each segment.
+pub(crate) const DEFAULT_NUM_CANDIDATES: usize = 100;
+
+/// A Vector Query matches the `k` documents of each segment whose vectors
+/// are the most similar to a given vector, scored by their similarity.
+///
+/// As the documents of each segment are ranked by the same similarity,
+/// collecting the top `k` documents of a `VectorQuery` returns the `k`
+/// approximate nearest neighbours of the vector over the whole index.
+///
+/// The nearest neighbours are searched in the HNSW graph of each segment:
+/// `num_candidates` documents are considered, and the best `k` of them are kept.
+/// The higher the number of candidates, the more accurate and the slower the search.
+///
+/// The search can be restricted to the documents matching a filter query
+/// with `.set_filter(...)`: the `k` nearest documents matching the filter are
+/// then searched in the graph, and are found even if the filter is selective.
+///
+/// A `VectorQuery` can also be combined with filters inside a `BooleanQuery`.
+/// These filters apply to the `k` nearest documents of each segment instead,
+/// so that fewer than `k` documents may match.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::collector::TopDocs;
+/// use tantivy::query::VectorQuery;
+/// use tantivy::schema::{Schema, Vector, STORED, TEXT};
+/// use tantivy::{DocAddress, Index, Result};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = Schema::builder();
+///     let title = schema_builder.add_text_field("title", TEXT | STORED);
+///     let embedding = schema_builder.add_vector_field("embedding", 3);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(
+///             title => "The Name of the Wind",
+///             embedding => Vector::from(vec![0.9f32, 0.1, 0.0])
+///         ));
+///         index_writer.add_document(doc!(
+///             title => "The Diary of Muadib",
+///             embedding => Vector::from(vec![0.0f32, 0.8, 0.6])
+///         ));
+///         index_writer.add_document(doc!(
+///             title => "A Dairy Cow",
+///             embedding => Vector::from(vec![0.5f32, 0.5, 0.0])
+///         ));
+///         index_writer.commit()?;
+///     }
+///     let searcher = index.reader()?.searcher();
+///
+///     let query = VectorQuery::new(embedding, vec![0.0, 1.0, 0.5], 2);
+///     let top_docs = searcher.search(&query, &TopDocs::with_limit(2))?;
+///     assert_eq!(top_docs[0].1, DocAddress(0, 1));
+///     assert_eq!(top_docs[1].1, DocAddress(0, 2));
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct VectorQuery {
+    field: Field,
+    vector: Vec<f32>,
+    k: usize,
+    similarity: VectorSimilarity,
+    num_candidates: usize,
+    filter: Option<Box<Query>>,
+}
+
+impl Clone for VectorQuery {
+    fn clone(&self) -> Self {
+        VectorQuery {
+            field: self.field,
+            vector: self.vector.clone(),
+            k: self.k,
+            similarity: self.similarity,
+            num_candidates: self.num_candidates,
+            filter: self.filter.as_ref().map(|filter| filter.box_clone()),
+        }
+    }
+}
+
+impl VectorQuery {
+    /// Creates a new `VectorQuery` matching the `k` documents whose vectors for `field`
+    /// are the most similar to `vector`, according to the cosine similarity.
+    pub fn new(field: Field, vector: Vec<f32>, k: usize) -> VectorQuery {
+        VectorQuery {
+            field,
+            vector,
+            k,
+            similarity: VectorSimilarity::default(),
+            num_candidates: DEFAULT_NUM_CANDIDATES,
+            filter: None,
+        }
+    }
+
+    /// The `Field` this `VectorQuery` is targeting.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// The vector whose nearest neighbours are searched.
+    pub fn vector(&self) -> &[f32] {
+        &self.vector[..]
+    }
+
+    /// The number of nearest documents matched in each segment.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Sets the similarity used to rank and score the documents.
+    pub fn set_similarity(&mut self, similarity: VectorSimilarity) {
+        self.similarity = similarity;
+    }
+
+    /// The similarity used to rank and score the documents.
+    pub fn similarity(&self) -> VectorSimilarity {
+        self.similarity
+    }
+
+    /// Sets the number of candidates collected in each segment,
+    /// among which the `k` nearest documents are selected.
+    ///
+    /// It is at least `k`, and defaults to `100`.
+    pub fn set_num_candidates(&mut self, num_candidates: usize) {
+        self.num_candidates = num_candidates;
+    }
+
+    /// The number of candidates collected in each segment.
+    pub fn num_candidates(&self) -> usize {
+        self.num_candidates
+    }
+
+    /// Restricts the search to the documents matching `filter`.
+    ///
+    /// The filter does not contribute to the score. Its documents are cached
+    /// in the `FilterCache` of the searcher if `filter.cache_key()` returns a key.
+    pub fn set_filter(&mut self, filter: Box<Query>) {
+        self.filter = Some(filter);
+    }
+
+    /// The query the search is restricted to, if any.
+    pub fn filter(&self) -> Option<&Query> {
+        self.filter.as_ref().map(|filter| filter.as_ref())
+    }
+}
+
+impl Query for VectorQuery {
+    fn weight(&self, searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        let field_entry = searcher.schema().get_field_entry(self.field);
+        match *field_entry.field_type() {
+            FieldType::Vector(dim) => {
+                if dim != self.vector.len() {
+                    return Err(TantivyError::InvalidArgument(format!(
+                        "The vector has dimension {}, but field {:?} has dimension {}",
+                        self.vector.len(),
+                        field_entry.name(),
+                        dim
+                    )));
+                }
+            }
+            _ => {
+                return Err(TantivyError::SchemaError(format!(
+                    "Field {:?} is not a vector field",
+                    field_entry.name()
+                )));
+            }
+        }
+        let filter_weight = match self.filter {
+            Some(ref filter) => Some(match filter.cache_key() {
+                Some(cache_key) => {
+                    Box::new(CachedWeight::new(filter.as_ref(), cache_key, searcher)?)
+                }
+                None => filter.weight(searcher, false)?,
+            }),
+            None => None,
+        };
+        Ok(Box::new(VectorWeight {
+            field: self.field,
+            vector: self.vector.clone(),
+            k: self.k,
+            similarity: self.similarity,
+            num_candidates: self.num_candidates,
+            filter_weight,
+        }))
+    }
+}
+
+struct VectorWeight {
+    field: Field,
+    vector: Vec<f32>,
+    k: usize,
+    similarity: VectorSimilarity,
+    num_candidates: usize,
+    filter_weight: Option<Box<Weight>>,
+}
+
+impl VectorWeight {
+    /// Returns the nearest documents of the segment, with their similarity,
+    /// sorted by doc id.
+    fn top_docs(&self, reader: &SegmentReader) -> Result<Vec<(DocId, Score)>> {
+        let vector_reader = reader.vector_reader(self.field)?;
+        let search = |accept: &Fn(DocId) -> bool| {
+            vector_reader.search(
+                &self.vector,
+                self.k,
+                self.num_candidates,
+                self.similarity,
+                accept,
+            )
+        };
+        // the filtered and deleted documents are skipped while searching the graph,
+        // so that they do not take the place of the nearest accepted documents.
+        let mut top_docs = if let Some(ref filter_weight) = self.filter_weight {
+            let filtered_docs = alive_doc_bitset(filter_weight.as_ref(), reader)?;
+            search(&|doc| filtered_docs.contains(doc))
+        } else if let Some(delete_bitset) = reader.delete_bitset() {
+            search(&|doc| delete_bitset.is_alive(doc))
+        } else {
+            search(&|_| true)
+        };
+        top_docs.sort_by_key(|&(doc, _)| doc);
+        Ok(top_docs)
+    }
+}
+
+impl Weight for VectorWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        Ok(Box::new(VectorScorer::from(self.top_docs(reader)?)))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let top_docs = self.top_docs(reader)?;
+        let ord = top_docs
+            .binary_search_by_key(&doc, |&(top_doc, _)| top_doc)
+            .map_err(|_| does_not_match(doc))?;
+        let description = match self.similarity {
+            VectorSimilarity::Cosine => "VectorQuery, cosine similarity",
+            VectorSimilarity::DotProduct => "VectorQuery, dot product",
+        };
+        let mut explanation = Explanation::new(description, top_docs[ord].1);
+        explanation.add_const(
+            "k, number of nearest documents matched in the segment",
+            self.k as f32,
+        );
+        Ok(explanation)
+    }
+
+    fn count(&self, reader: &SegmentReader) -> Result<u32> {
+        Ok(self.top_docs(reader)?.len() as u32)
+    }
+}
+
+/// Scorer over the nearest documents of a segment, sorted by doc id.
+struct VectorScorer {
+    top_docs: Vec<(DocId, Score)>,
+    cursor: Wrapping<usize>,
+}
+
+impl From<Vec<(DocId, Score)>> for VectorScorer {
+    fn from(top_docs: Vec<(DocId, Score)>) -> VectorScorer {
+        VectorScorer {
+            top_docs,
+            cursor: Wrapping(usize::max_value()),
+        }
+    }
+}
+
+impl DocSet for VectorScorer {
+    fn advance(&mut self) -> bool {
+        self.cursor += Wrapping(1);
+        self.top_docs.len() > self.cursor.0
+    }
+
+    fn doc(&self) -> DocId {
+        self.top_docs[self.cursor.0].0
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.top_docs.len() as u32
+    }
+}
+
+impl Scorer for VectorScorer {
+    fn score(&mut self) -> Score {
+        self.top_docs[self.cursor.0].1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::VectorQuery;
+    use collector::{Count, TopDocs};
+    use error::TantivyError;
+    use futures::Future;
+    use query::{BooleanQuery, ExistsQuery, Occur, Query, RangeQuery};
+    use schema::{Schema, Vector, INDEXED};
+    use tests::assert_nearly_equals;
+    use vector::VectorSimilarity;
+    use DocAddress;
+    use Index;
+    use Searcher;
+    use Term;
+
+    #[test]
+    fn test_vector_query() {
+        let mut schema_builder = Schema::builder();
+        let embedding = schema_builder.add_vector_field("embedding", 2);
+        let year = schema_builder.add_u64_field("year", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let vector = |x: f32, y: f32| Vector::from(vec![x, y]);
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(embedding=>vector(1f32, 0.1f32), year=>2017u64));
+            index_writer.add_document(doc!(embedding=>vector(10f32, 10f32), year=>2018u64));
+            index_writer.add_document(doc!(embedding=>vector(0f32, 1f32), year=>2018u64));
+            index_writer.add_document(doc!(year=>2019u64));
+            index_writer.add_document(doc!(embedding=>vector(1f32, 0f32), year=>2019u64));
+            index_writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+
+        let query = VectorQuery::new(embedding, vec![2f32, 0f32], 2);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(3)).unwrap();
+        assert_eq!(top_docs.len(), 2);
+        assert_eq!(top_docs[0].1, DocAddress(0, 4));
+        assert_nearly_equals(1f32, top_docs[0].0);
+        assert_eq!(top_docs[1].1, DocAddress(0, 0));
+        assert_eq!(searcher.search(&query, &Count).unwrap(), 2);
+        let explanation = searcher.explain(&query, DocAddress(0, 0)).unwrap();
+        assert_nearly_equals(top_docs[1].0, explanation.value());
+        assert!(searcher.explain(&query, DocAddress(0, 2)).is_err());
+
+        let mut dot_product_query = VectorQuery::new(embedding, vec![2f32, 0f32], 2);
+        dot_product_query.set_similarity(VectorSimilarity::DotProduct);
+        let top_docs = searcher
+            .search(&dot_product_query, &TopDocs::with_limit(3))
+            .unwrap();
+        assert_eq!(top_docs[0].1, DocAddress(0, 1));
+        assert_nearly_equals(20f32, top_docs[0].0);
+
+        // filters apply to the nearest documents.
+        let filtered_query = BooleanQuery::from(vec![
+            (Occur::Must, Box::new(query.clone()) as Box<Query>),
+            (
+                Occur::Filter,
+                Box::new(RangeQuery::new_u64(year, 2018..2020)),
+            ),
+        ]);
+        let top_docs = searcher
+            .search(&filtered_query, &TopDocs::with_limit(3))
+            .unwrap();
+        assert_eq!(top_docs.len(), 1);
+        assert_eq!(top_docs[0].1, DocAddress(0, 4));
+
+        assert_eq!(
+            searcher
+                .search(&ExistsQuery::new(embedding), &Count)
+                .unwrap(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_vector_query_errors() {
+        let mut schema_builder = Schema::builder();
+        let embedding = schema_builder.add_vector_field("embedding", 2);
+        let year = schema_builder.add_u64_field("year", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let searcher = index.reader().unwrap().searcher();
+        match searcher.search(
+            &VectorQuery::new(embedding, vec![1f32, 2f32, 3f32], 1),
+            &Count,
+        ) {
+            Err(TantivyError::InvalidArgument(_)) => {}
+            _ => panic!("Expected an invalid argument error"),
+        }
+        match searcher.search(&VectorQuery::new(year, vec![1f32, 2f32], 1), &Count) {
+            Err(TantivyError::SchemaError(_)) => {}
+            _ => panic!("Expected a schema error"),
+        }
+    }
+
+    #[test]
+    fn test_vector_query_merge_and_deletes() {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_u64_field("id", INDEXED);
+        let embedding = schema_builder.add_vector_field("embedding", 2);
+        let index = Index::create_in_ram(schema_builder.build());
+        let vector = |i: u64| {
+            let angle = i as f32 / 100f32;
+            vec![angle.cos(), angle.sin()]
+        };
+        let mut index_writer = index.writer_with_num_threads(1, 30_000_000).unwrap();
+        for i in 0..300u64 {
+            if i % 10 == 0 {
+                index_writer.add_document(doc!(id=>i));
+            } else {
+                index_writer.add_document(doc!(id=>i, embedding=>Vector::from(vector(i))));
+            }
+            if i % 100 == 99 {
+                index_writer.commit().unwrap();
+            }
+        }
+        for i in (0..300u64).filter(|i| i % 3 == 0) {
+            index_writer.delete_term(Term::from_field_u64(id, i));
+        }
+        index_writer.commit().unwrap();
+        let reader = index.reader().unwrap();
+        // the ids of the nearest documents, recovered from their vectors.
+        let nearest_ids = |searcher: &Searcher, query: &VectorQuery| -> Vec<u64> {
+            let mut ids: Vec<u64> = searcher
+                .search(query, &TopDocs::with_limit(query.k()))
+                .unwrap()
+                .into_iter()
+                .map(|(_, doc_address)| {
+                    let DocAddress(segment_ord, doc) = doc_address;
+                    let vector_reader = searcher
+                        .segment_reader(segment_ord)
+                        .vector_reader(embedding)
+                        .unwrap();
+                    let vector = vector_reader.vector(doc).unwrap();
+                    (vector[1].atan2(vector[0]) * 100f32).round() as u64
+                })
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        let mut query = VectorQuery::new(embedding, vector(150), 4);
+        query.set_num_candidates(20);
+        // 147, 150 and 153 are deleted.
+        let expected: Vec<u64> = vec![148, 149, 151, 152];
+        assert_eq!(nearest_ids(&reader.searcher(), &query), expected);
+
+        let segment_ids = index.searchable_segment_ids().unwrap();
+        index_writer
+            .merge(&segment_ids)
+            .expect("Failed to initiate merge")
+            .wait()
+            .expect("Merging failed");
+        index_writer.wait_merging_threads().unwrap();
+        reader.reload().unwrap();
+        let searcher = reader.searcher();
+        assert_eq!(searcher.segment_readers().len(), 1);
+        let vector_reader = searcher.segment_reader(0).vector_reader(embedding).unwrap();
+        // 200 alive documents, 20 of them without a vector.
+        assert_eq!(vector_reader.num_vectors(), 180);
+        assert_eq!(nearest_ids(&searcher, &query), expected);
+        assert_eq!(
+            searcher
+                .search(&ExistsQuery::new(embedding), &Count)
+                .unwrap(),
+            180
+        );
+    }
+
+    #[test]
+    fn test_vector_query_selective_filter() {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_u64_field("id", INDEXED);
+        let embedding = schema_builder.add_vector_field("embedding", 2);
+        let index = Index::create_in_ram(schema_builder.build());
+        let vector = |i: u64| {
+            let angle = i as f32 / 100f32;
+            vec![angle.cos(), angle.sin()]
+        };
+        let mut index_writer = index.writer_with_num_threads(1, 30_000_000).unwrap();
+        for i in 0..300u64 {
+            index_writer.add_document(doc!(id=>i, embedding=>Vector::from(vector(i))));
+        }
+        index_writer.commit().unwrap();
+        index_writer.delete_term(Term::from_field_u64(id, 49));
+        index_writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        assert_eq!(searcher.segment_readers().len(), 1);
+        let vector_reader = searcher.segment_reader(0).vector_reader(embedding).unwrap();
+
+        let mut query = VectorQuery::new(embedding, vector(150), 4);
+        query.set_num_candidates(20);
+        query.set_filter(Box::new(RangeQuery::new_u64(id, 0..50)));
+        let mut ids: Vec<u64> = searcher
+            .search(&query, &TopDocs::with_limit(10))
+            .unwrap()
+            .into_iter()
+            .map(|(_, DocAddress(_, doc))| {
+                let vector = vector_reader.vector(doc).unwrap();
+                (vector[1].atan2(vector[0]) * 100f32).round() as u64
+            })
+            .collect();
+        ids.sort();
+        // the filter removes all of the nearest documents, and 49 is deleted.
+        assert_eq!(ids, vec![45, 46, 47, 48]);
+        assert_eq!(searcher.search(&query, &Count).unwrap(), 4);
+
+        // filters of a boolean query only apply to the nearest documents.
+        let mut unfiltered_query = query.clone();
+        unfiltered_query.filter = None;
+        let boolean_query = BooleanQuery::from(vec![
+            (Occur::Must, Box::new(unfiltered_query) as Box<Query>),
+            (Occur::Filter, Box::new(RangeQuery::new_u64(id, 0..50))),
+        ]);
+        assert_eq!(searcher.search(&boolean_query, &Count).unwrap(), 0);
+    }
+}
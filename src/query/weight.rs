@@ -1,6 +1,10 @@
 use super::Scorer;
 use core::SegmentReader;
+use query::Explanation;
+use DocId;
 use Result;
+use Score;
+use TantivyError;
 
 /// A Weight is the specialization of a Query
 /// for a given set of segments.
@@ -11,6 +15,17 @@ pub trait Weight: Send + Sync + 'static {
     /// See [`Query`](./trait.Query.html).
     fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>>;
 
+    /// Returns an `Explanation` for the score of the document `doc`
+    /// within the given `SegmentReader`.
+    ///
+    /// Returns an error if the document does not match the query,
+    /// or if the weight does not support explanations.
+    fn explain(&self, _reader: &SegmentReader, _doc: DocId) -> Result<Explanation> {
+        Err(TantivyError::InvalidArgument(
+            "explain is not supported by this weight".to_string(),
+        ))
+    }
+
     /// Returns the number documents within the given `SegmentReader`.
     fn count(&self, reader: &SegmentReader) -> Result<u32> {
         let mut scorer = self.scorer(reader)?;
@@ -20,4 +35,22 @@ pub trait Weight: Send + Sync + 'static {
             Ok(scorer.count_including_deleted())
         }
     }
+
+    /// Pushes the documents of the given `SegmentReader` with a score
+    /// strictly greater than `threshold` to the callback, which returns
+    /// the new threshold.
+    ///
+    /// This is used by top-k collectors: as the threshold increases,
+    /// the documents that cannot make it to the top k can be skipped.
+    /// See [`Scorer::for_each_pruning`](./trait.Scorer.html#method.for_each_pruning).
+    fn for_each_pruning(
+        &self,
+        threshold: Score,
+        reader: &SegmentReader,
+        callback: &mut FnMut(DocId, Score) -> Score,
+    ) -> Result<()> {
+        let mut scorer = self.scorer(reader)?;
+        scorer.for_each_pruning(threshold, callback);
+        Ok(())
+    }
 }
@@ -0,0 +1,175 @@
+use error::TantivyError;
+use query::{AutomatonWeight, Query, Weight};
+use regex;
+use schema::Field;
+use tantivy_fst::Regex;
+use Result;
+use Searcher;
+
+/// Translates a wildcard pattern into an equivalent regular expression.
+fn wildcard_to_regex(pattern: &str) -> String {
+    let mut regex_pattern = String::with_capacity(pattern.len() * 2);
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex_pattern.push_str(".*"),
+            '?' => regex_pattern.push('.'),
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    regex_pattern.push_str(&regex::escape(&escaped.to_string()));
+                }
+            }
+            _ => regex_pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex_pattern
+}
+
+/// A Wildcard Query matches all of the documents
+/// containing a term matching a wildcard pattern.
+///
+/// In the pattern, `*` matches any sequence of characters,
+/// possibly empty, and `?` matches exactly one character.
+/// A backslash makes the character following it match literally, e.g. `\*`.
+/// All of the matching documents get the score `1`.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{Index, Result};
+/// use tantivy::collector::Count;
+/// use tantivy::query::WildcardQuery;
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = Schema::builder();
+///     let title = schema_builder.add_text_field("title", TEXT);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(
+///             title => "The Name of the Wind",
+///         ));
+///         index_writer.add_document(doc!(
+///             title => "The Diary of Muadib",
+///         ));
+///         index_writer.add_document(doc!(
+///             title => "A Dairy Cow",
+///         ));
+///         index_writer.commit().unwrap();
+///     }
+///
+///     let reader = index.reader()?;
+///     let searcher = reader.searcher();
+///
+///     let query = WildcardQuery::new("d??ry".to_string(), title);
+///     let count = searcher.search(&query, &Count)?;
+///     assert_eq!(count, 2);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WildcardQuery {
+    pattern: String,
+    field: Field,
+    max_expansions: Option<usize>,
+}
+
+impl WildcardQuery {
+    /// Creates a new Wildcard Query
+    pub fn new(pattern: String, field: Field) -> WildcardQuery {
+        WildcardQuery {
+            pattern,
+            field,
+            max_expansions: None,
+        }
+    }
+
+    /// The wildcard pattern searched by this query.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// The field searched by this query.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// Limits the number of terms the pattern may expand to in each segment.
+    ///
+    /// Only the documents containing one of the first `max_expansions`
+    /// matching terms, in lexicographic order, are matched.
+    pub fn set_max_expansions(&mut self, max_expansions: usize) {
+        self.max_expansions = Some(max_expansions);
+    }
+
+    /// The maximum number of terms this query may expand to, if limited.
+    pub fn max_expansions(&self) -> Option<usize> {
+        self.max_expansions
+    }
+
+    fn specialized_weight(&self) -> Result<AutomatonWeight<Regex>> {
+        let automaton = Regex::new(&wildcard_to_regex(&self.pattern))
+            .map_err(|_| TantivyError::InvalidArgument(self.pattern.clone()))?;
+        let mut weight = AutomatonWeight::new(self.field, automaton);
+        if let Some(max_expansions) = self.max_expansions {
+            weight.set_max_expansions(max_expansions);
+        }
+        Ok(weight)
+    }
+}
+
+impl Query for WildcardQuery {
+    fn weight(&self, _searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(self.specialized_weight()?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{wildcard_to_regex, WildcardQuery};
+    use collector::Count;
+    use schema::Schema;
+    use schema::TEXT;
+    use Index;
+
+    #[test]
+    pub fn test_wildcard_to_regex() {
+        assert_eq!(wildcard_to_regex("r?st*"), "r.st.*");
+        assert_eq!(wildcard_to_regex("a.b+"), "a\\.b\\+");
+        assert_eq!(wildcard_to_regex("a\\*b\\\\"), "a\\*b\\\\");
+    }
+
+    #[test]
+    pub fn test_wildcard_query() {
+        let mut schema_builder = Schema::builder();
+        let country_field = schema_builder.add_text_field("country", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 10_000_000).unwrap();
+            index_writer.add_document(doc!(country_field => "japan"));
+            index_writer.add_document(doc!(country_field => "jamaica"));
+            index_writer.add_document(doc!(country_field => "korea"));
+            index_writer.commit().unwrap();
+        }
+        let reader = index.reader().unwrap();
+        let searcher = reader.searcher();
+        let count = |pattern: &str, max_expansions: Option<usize>| {
+            let mut query = WildcardQuery::new(pattern.to_string(), country_field);
+            if let Some(max_expansions) = max_expansions {
+                query.set_max_expansions(max_expansions);
+            }
+            searcher.search(&query, &Count).unwrap()
+        };
+        assert_eq!(count("ja*", None), 2);
+        assert_eq!(count("ja?an", None), 1);
+        assert_eq!(count("*a", None), 2);
+        assert_eq!(count("j?", None), 0);
+        assert_eq!(count("*", None), 3);
+        assert_eq!(count("*", Some(1)), 1);
+        assert_eq!(count("ja.an", None), 0);
+    }
+}
@@ -5,10 +5,12 @@ use core::Segment;
 use directory::Directory;
 use directory::WatchHandle;
 use directory::META_LOCK;
+use query::{FilterCache, DEFAULT_FILTER_CACHE_MEMORY_BUDGET};
 use std::sync::Arc;
 use Index;
 use Result;
 use Searcher;
+use SegmentId;
 use SegmentReader;
 
 /// Defines when a new version of the index should be reloaded.
@@ -39,10 +41,14 @@ pub enum ReloadPolicy {
 /// - `reload_policy` (by default `ReloadPolicy::OnCommit`):
 ///
 ///   See [`ReloadPolicy`](./enum.ReloadPolicy.html) for more details.
+/// - `filter_cache_memory_budget` (by default 32MB):
+///
+///   See [`FilterCache`](../query/struct.FilterCache.html) for more details.
 #[derive(Clone)]
 pub struct IndexReaderBuilder {
     num_searchers: usize,
     reload_policy: ReloadPolicy,
+    filter_cache_memory_budget: usize,
     index: Index,
 }
 
@@ -51,6 +57,7 @@ impl IndexReaderBuilder {
         IndexReaderBuilder {
             num_searchers: num_cpus::get(),
             reload_policy: ReloadPolicy::OnCommit,
+            filter_cache_memory_budget: DEFAULT_FILTER_CACHE_MEMORY_BUDGET,
             index,
         }
     }
@@ -66,6 +73,9 @@ impl IndexReaderBuilder {
             index: self.index,
             num_searchers: self.num_searchers,
             searcher_pool: Pool::new(),
+            filter_cache: Arc::new(FilterCache::with_memory_budget(
+                self.filter_cache_memory_budget,
+            )),
         };
         inner_reader.reload()?;
         let inner_reader_arc = Arc::new(inner_reader);
@@ -108,12 +118,21 @@ impl IndexReaderBuilder {
         self.num_searchers = num_searchers;
         self
     }
+
+    /// Sets the memory budget, in bytes, of the `FilterCache` shared by the searchers.
+    ///
+    /// A budget of `0` disables the cache.
+    pub fn filter_cache_memory_budget(mut self, memory_budget: usize) -> IndexReaderBuilder {
+        self.filter_cache_memory_budget = memory_budget;
+        self
+    }
 }
 
 struct InnerIndexReader {
     num_searchers: usize,
     searcher_pool: Pool<Searcher>,
     index: Index,
+    filter_cache: Arc<FilterCache>,
 }
 
 impl InnerIndexReader {
@@ -126,9 +145,21 @@ impl InnerIndexReader {
                 .map(SegmentReader::open)
                 .collect::<Result<_>>()?
         };
+        let segment_ids: Vec<SegmentId> = segment_readers
+            .iter()
+            .map(SegmentReader::segment_id)
+            .collect();
+        self.filter_cache.retain_segments(&segment_ids);
         let schema = self.index.schema();
         let searchers = (0..self.num_searchers)
-            .map(|_| Searcher::new(schema.clone(), self.index.clone(), segment_readers.clone()))
+            .map(|_| {
+                Searcher::new(
+                    schema.clone(),
+                    self.index.clone(),
+                    segment_readers.clone(),
+                    self.filter_cache.clone(),
+                )
+            })
             .collect();
         self.searcher_pool.publish_new_generation(searchers);
         Ok(())
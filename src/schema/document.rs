@@ -98,6 +98,11 @@ impl Document {
         self.add(FieldValue::new(field, Value::Bytes(value)))
     }
 
+    /// Add a vector field
+    pub fn add_vector(&mut self, field: Field, value: Vec<f32>) {
+        self.add(FieldValue::new(field, Value::Vector(Vector::from(value))))
+    }
+
     /// Add a field value
     pub fn add(&mut self, field_value: FieldValue) {
         self.field_values.push(field_value);
@@ -73,6 +73,14 @@ impl FieldEntry {
         }
     }
 
+    /// Creates a field entry for a vector field of the given dimension.
+    pub fn new_vector(field_name: String, dim: usize) -> FieldEntry {
+        FieldEntry {
+            name: field_name,
+            field_type: FieldType::Vector(dim),
+        }
+    }
+
     /// Returns the name of the field
     pub fn name(&self) -> &str {
         &self.name
@@ -91,14 +99,16 @@ impl FieldEntry {
             | FieldType::I64(ref options)
             | FieldType::Date(ref options) => options.is_indexed(),
             FieldType::HierarchicalFacet => true,
-            FieldType::Bytes => false,
+            FieldType::Bytes | FieldType::Vector(_) => false,
         }
     }
 
-    /// Returns true iff the field is a int (signed or unsigned) fast field
+    /// Returns true iff the field is a int (signed or unsigned) or date fast field
     pub fn is_int_fast(&self) -> bool {
         match self.field_type {
-            FieldType::U64(ref options) | FieldType::I64(ref options) => options.is_fast(),
+            FieldType::U64(ref options)
+            | FieldType::I64(ref options)
+            | FieldType::Date(ref options) => options.is_fast(),
             _ => false,
         }
     }
@@ -112,11 +122,17 @@ impl FieldEntry {
             FieldType::Str(ref options) => options.is_stored(),
             // TODO make stored hierarchical facet optional
             FieldType::HierarchicalFacet => true,
-            FieldType::Bytes => false,
+            FieldType::Bytes | FieldType::Vector(_) => false,
         }
     }
 }
 
+/// Options of a vector field, as serialized in the schema.
+#[derive(Serialize, Deserialize)]
+struct VectorOptions {
+    dim: usize,
+}
+
 impl Serialize for FieldEntry {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -148,6 +164,10 @@ impl Serialize for FieldEntry {
             FieldType::Bytes => {
                 s.serialize_field("type", "bytes")?;
             }
+            FieldType::Vector(dim) => {
+                s.serialize_field("type", "vector")?;
+                s.serialize_field("options", &VectorOptions { dim })?;
+            }
         }
 
         s.end()
@@ -205,7 +225,7 @@ impl<'de> Deserialize<'de> for FieldEntry {
                                 "bytes" => {
                                     field_type = Some(FieldType::Bytes);
                                 }
-                                "text" | "u64" | "i64" | "date" => {
+                                "text" | "u64" | "i64" | "date" | "vector" => {
                                     // These types require additional options to create a field_type
                                 }
                                 _ => panic!("unhandled type"),
@@ -223,6 +243,10 @@ impl<'de> Deserialize<'de> for FieldEntry {
                                 "u64" => field_type = Some(FieldType::U64(map.next_value()?)),
                                 "i64" => field_type = Some(FieldType::I64(map.next_value()?)),
                                 "date" => field_type = Some(FieldType::Date(map.next_value()?)),
+                                "vector" => {
+                                    let options: VectorOptions = map.next_value()?;
+                                    field_type = Some(FieldType::Vector(options.dim));
+                                }
                                 _ => {
                                     let msg = format!("Unrecognised type {}", ty);
                                     return Err(de::Error::custom(msg));
@@ -278,4 +302,13 @@ mod tests {
             _ => panic!("expected FieldType::Str"),
         }
     }
+
+    #[test]
+    fn test_vector_json_serialization() {
+        let field_entry = FieldEntry::new_vector(String::from("embedding"), 3);
+        let expected = r#"{"name":"embedding","type":"vector","options":{"dim":3}}"#;
+        assert_eq!(serde_json::to_string(&field_entry).unwrap(), expected);
+        let deserialized: FieldEntry = serde_json::from_str(expected).unwrap();
+        assert_eq!(deserialized, field_entry);
+    }
 }
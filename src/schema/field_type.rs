@@ -6,6 +6,7 @@ use schema::Facet;
 use schema::IndexRecordOption;
 use schema::TextFieldIndexing;
 use schema::Value;
+use schema::Vector;
 use serde_json::Value as JsonValue;
 
 /// Possible error that may occur while parsing a field value
@@ -41,6 +42,8 @@ pub enum Type {
     HierarchicalFacet,
     /// `Vec<u8>`
     Bytes,
+    /// `tantivy::schema::Vector`. Passed as an array of numbers in JSON.
+    Vector,
 }
 
 /// A `FieldType` describes the type (text, u64) of a field as well as
@@ -59,6 +62,8 @@ pub enum FieldType {
     HierarchicalFacet,
     /// Bytes (one per document)
     Bytes,
+    /// Dense vector of `f32` of the given dimension (one per document)
+    Vector(usize),
 }
 
 impl FieldType {
@@ -71,6 +76,7 @@ impl FieldType {
             FieldType::Date(_) => Type::Date,
             FieldType::HierarchicalFacet => Type::HierarchicalFacet,
             FieldType::Bytes => Type::Bytes,
+            FieldType::Vector(_) => Type::Vector,
         }
     }
 
@@ -83,7 +89,7 @@ impl FieldType {
             }
             FieldType::Date(ref date_options) => date_options.is_indexed(),
             FieldType::HierarchicalFacet => true,
-            FieldType::Bytes => false,
+            FieldType::Bytes | FieldType::Vector(_) => false,
         }
     }
 
@@ -106,7 +112,7 @@ impl FieldType {
                 }
             }
             FieldType::HierarchicalFacet => Some(IndexRecordOption::Basic),
-            FieldType::Bytes => None,
+            FieldType::Bytes | FieldType::Vector(_) => None,
         }
     }
 
@@ -122,6 +128,10 @@ impl FieldType {
                 FieldType::U64(_) | FieldType::I64(_) | FieldType::Date(_) => Err(
                     ValueParsingError::TypeError(format!("Expected an integer, got {:?}", json)),
                 ),
+                FieldType::Vector(_) => Err(ValueParsingError::TypeError(format!(
+                    "Expected an array of numbers, got {:?}",
+                    json
+                ))),
                 FieldType::HierarchicalFacet => Ok(Value::Facet(Facet::from(field_text))),
                 FieldType::Bytes => decode(field_text).map(Value::Bytes).map_err(|_| {
                     ValueParsingError::InvalidBase64(format!(
@@ -151,6 +161,31 @@ impl FieldType {
                     let msg = format!("Expected a string, got {:?}", json);
                     Err(ValueParsingError::TypeError(msg))
                 }
+                FieldType::Vector(_) => {
+                    let msg = format!("Expected an array of numbers, got {:?}", json);
+                    Err(ValueParsingError::TypeError(msg))
+                }
+            },
+            JsonValue::Array(ref json_items) => match *self {
+                FieldType::Vector(dim) => {
+                    let values = json_items
+                        .iter()
+                        .map(|json_item| json_item.as_f64().map(|value| value as f32))
+                        .collect::<Option<Vec<f32>>>()
+                        .ok_or_else(|| {
+                            let msg = format!("Expected an array of numbers, got {:?}", json);
+                            ValueParsingError::TypeError(msg)
+                        })?;
+                    if values.len() != dim {
+                        let msg = format!("Expected a vector of dimension {}, got {:?}", dim, json);
+                        return Err(ValueParsingError::TypeError(msg));
+                    }
+                    Ok(Value::Vector(Vector::from(values)))
+                }
+                _ => {
+                    let msg = format!("Expected a single value, got {:?}", json);
+                    Err(ValueParsingError::TypeError(msg))
+                }
             },
             _ => {
                 let msg = format!(
@@ -167,7 +202,7 @@ impl FieldType {
 mod tests {
     use super::FieldType;
     use schema::field_type::ValueParsingError;
-    use schema::Value;
+    use schema::{Value, Vector};
 
     #[test]
     fn test_bytes_value_from_json() {
@@ -188,4 +223,25 @@ mod tests {
             _ => panic!("Expected parse failure for invalid base64"),
         }
     }
+
+    #[test]
+    fn test_vector_value_from_json() {
+        let result = FieldType::Vector(3)
+            .value_from_json(&json!([1, 0.5, -2.0]))
+            .unwrap();
+        assert_eq!(
+            result,
+            Value::Vector(Vector::from(vec![1f32, 0.5f32, -2f32]))
+        );
+
+        match FieldType::Vector(2).value_from_json(&json!([1, 0.5, -2.0])) {
+            Err(ValueParsingError::TypeError(_)) => {}
+            _ => panic!("Expected parse failure for wrong dimension"),
+        }
+
+        match FieldType::Vector(2).value_from_json(&json!([1, "a"])) {
+            Err(ValueParsingError::TypeError(_)) => {}
+            _ => panic!("Expected parse failure for wrong type"),
+        }
+    }
 }
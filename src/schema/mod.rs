@@ -117,6 +117,7 @@ mod int_options;
 mod named_field_document;
 mod text_options;
 mod value;
+mod vector;
 
 mod flags;
 
@@ -124,6 +125,7 @@ pub use self::named_field_document::NamedFieldDocument;
 pub use self::schema::DocParsingError;
 pub use self::schema::{Schema, SchemaBuilder};
 pub use self::value::Value;
+pub use self::vector::Vector;
 
 pub use self::facet::Facet;
 pub(crate) use self::facet::FACET_SEP_BYTE;
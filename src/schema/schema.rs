@@ -145,6 +145,17 @@ impl SchemaBuilder {
         self.add_field(field_entry)
     }
 
+    /// Adds a vector field to the schema.
+    ///
+    /// Each document holds at most one vector of dimension `dim`
+    /// in this field. Vector fields are neither indexed nor stored:
+    /// they are only used to search for the nearest neighbours of a
+    /// vector, with a [`VectorQuery`](../query/struct.VectorQuery.html).
+    pub fn add_vector_field(&mut self, field_name: &str, dim: usize) -> Field {
+        let field_entry = FieldEntry::new_vector(field_name.to_string(), dim);
+        self.add_field(field_entry)
+    }
+
     /// Adds a field entry to the schema in build.
     fn add_field(&mut self, field_entry: FieldEntry) -> Field {
         let field = Field(self.fields.len() as u32);
@@ -267,7 +278,10 @@ impl Schema {
                     let field_entry = self.get_field_entry(field);
                     let field_type = field_entry.field_type();
                     match *json_value {
-                        JsonValue::Array(ref json_items) => {
+                        // the value of a vector field is itself an array of numbers.
+                        JsonValue::Array(ref json_items)
+                            if !is_vector_json(field_type, json_items) =>
+                        {
                             for json_item in json_items {
                                 let value = field_type.value_from_json(json_item).map_err(|e| {
                                     DocParsingError::ValueError(field_name.clone(), e)
@@ -290,6 +304,16 @@ impl Schema {
     }
 }
 
+/// Returns true iff the json array is a single vector of a vector field,
+/// rather than several values.
+fn is_vector_json(field_type: &FieldType, json_items: &[JsonValue]) -> bool {
+    if let FieldType::Vector(_) = *field_type {
+        json_items.iter().all(JsonValue::is_number)
+    } else {
+        false
+    }
+}
+
 impl Serialize for Schema {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -456,6 +480,23 @@ mod tests {
         assert_eq!(doc, doc_serdeser);
     }
 
+    #[test]
+    pub fn test_parse_vector_document() {
+        let mut schema_builder = Schema::builder();
+        let embedding = schema_builder.add_vector_field("embedding", 2);
+        let schema = schema_builder.build();
+        let doc = schema.parse_document(r#"{"embedding": [0.5, 1]}"#).unwrap();
+        assert_eq!(
+            doc.get_first(embedding).and_then(Value::vector_value),
+            Some(&Vector::from(vec![0.5f32, 1f32]))
+        );
+        let doc_serdeser = schema.parse_document(&schema.to_json(&doc)).unwrap();
+        assert_eq!(doc, doc_serdeser);
+        assert!(schema
+            .parse_document(r#"{"embedding": [0.5, 1, 2]}"#)
+            .is_err());
+    }
+
     #[test]
     pub fn test_parse_document() {
         let mut schema_builder = Schema::builder();
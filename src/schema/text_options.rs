@@ -50,10 +50,24 @@ impl Default for TextOptions {
 /// - the amount of information that should be stored about the presence of a term in a document.
 /// Essentially, should we store the term frequency and/or the positions (See [`IndexRecordOption`](./enum.IndexRecordOption.html)).
 /// - the name of the `Tokenizer` that should be used to process the field.
+/// - the name of the `Similarity` that should be used to score the field.
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct TextFieldIndexing {
     record: IndexRecordOption,
     tokenizer: Cow<'static, str>,
+    #[serde(
+        default = "default_similarity",
+        skip_serializing_if = "is_default_similarity"
+    )]
+    similarity: Cow<'static, str>,
+}
+
+fn default_similarity() -> Cow<'static, str> {
+    Cow::Borrowed("default")
+}
+
+fn is_default_similarity(similarity: &str) -> bool {
+    similarity == "default"
 }
 
 impl Default for TextFieldIndexing {
@@ -61,6 +75,7 @@ impl Default for TextFieldIndexing {
         TextFieldIndexing {
             tokenizer: Cow::Borrowed("default"),
             record: IndexRecordOption::Basic,
+            similarity: Cow::Borrowed("default"),
         }
     }
 }
@@ -77,6 +92,20 @@ impl TextFieldIndexing {
         &self.tokenizer
     }
 
+    /// Sets the similarity to be used to score the terms of a given field.
+    ///
+    /// The similarity is looked up by name in the
+    /// [`SimilarityManager`](../query/struct.SimilarityManager.html) of the index.
+    pub fn set_similarity(mut self, similarity_name: &str) -> TextFieldIndexing {
+        self.similarity = Cow::Owned(similarity_name.to_string());
+        self
+    }
+
+    /// Returns the similarity that will be used to score this field.
+    pub fn similarity(&self) -> &str {
+        &self.similarity
+    }
+
     /// Sets which information should be indexed with the tokens.
     ///
     /// See [IndexRecordOption](./enum.IndexRecordOption.html) for more detail.
@@ -98,6 +127,7 @@ pub const STRING: TextOptions = TextOptions {
     indexing: Some(TextFieldIndexing {
         tokenizer: Cow::Borrowed("raw"),
         record: IndexRecordOption::Basic,
+        similarity: Cow::Borrowed("default"),
     }),
     stored: false,
 };
@@ -107,6 +137,7 @@ pub const TEXT: TextOptions = TextOptions {
     indexing: Some(TextFieldIndexing {
         tokenizer: Cow::Borrowed("default"),
         record: IndexRecordOption::WithFreqsAndPositions,
+        similarity: Cow::Borrowed("default"),
     }),
     stored: false,
 };
@@ -152,6 +183,7 @@ where
 #[cfg(test)]
 mod tests {
     use schema::*;
+    use serde_json;
 
     #[test]
     fn test_field_options() {
@@ -181,6 +213,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_similarity_serialization() {
+        let text_indexing = TextFieldIndexing::default();
+        assert_eq!(text_indexing.similarity(), "default");
+        let json = serde_json::to_string(&text_indexing).unwrap();
+        assert_eq!(json, r#"{"record":"basic","tokenizer":"default"}"#);
+        assert_eq!(
+            serde_json::from_str::<TextFieldIndexing>(&json).unwrap(),
+            text_indexing
+        );
+        let text_indexing = text_indexing.set_similarity("tfidf");
+        let json = serde_json::to_string(&text_indexing).unwrap();
+        assert_eq!(
+            json,
+            r#"{"record":"basic","tokenizer":"default","similarity":"tfidf"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<TextFieldIndexing>(&json)
+                .unwrap()
+                .similarity(),
+            "tfidf"
+        );
+    }
+
     #[test]
     fn test_cmp_index_record_option() {
         assert!(IndexRecordOption::WithFreqsAndPositions > IndexRecordOption::WithFreqs);
@@ -1,5 +1,6 @@
 use schema::Facet;
-use serde::de::Visitor;
+use schema::Vector;
+use serde::de::{SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use DateTime;
@@ -20,6 +21,8 @@ pub enum Value {
     Facet(Facet),
     /// Arbitrarily sized byte array
     Bytes(Vec<u8>),
+    /// Dense vector of `f32`
+    Vector(Vector),
 }
 
 impl Serialize for Value {
@@ -34,6 +37,7 @@ impl Serialize for Value {
             Value::Date(ref date) => serializer.serialize_i64(date.timestamp()),
             Value::Facet(ref facet) => facet.serialize(serializer),
             Value::Bytes(ref bytes) => serializer.serialize_bytes(bytes),
+            Value::Vector(ref vector) => vector.serialize(serializer),
         }
     }
 }
@@ -67,6 +71,17 @@ impl<'de> Deserialize<'de> for Value {
             fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
                 Ok(Value::Str(v))
             }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut values = Vec::new();
+                while let Some(value) = seq.next_element::<f32>()? {
+                    values.push(value);
+                }
+                Ok(Value::Vector(Vector::from(values)))
+            }
         }
 
         deserializer.deserialize_any(ValueVisitor)
@@ -117,6 +132,14 @@ impl Value {
             _ => panic!("This is not a date field."),
         }
     }
+
+    /// Returns the vector, provided the value is of the `Vector` type.
+    pub fn vector_value(&self) -> Option<&Vector> {
+        match *self {
+            Value::Vector(ref vector) => Some(vector),
+            _ => None,
+        }
+    }
 }
 
 impl From<String> for Value {
@@ -161,11 +184,19 @@ impl From<Vec<u8>> for Value {
     }
 }
 
+impl From<Vector> for Value {
+    fn from(vector: Vector) -> Value {
+        Value::Vector(vector)
+    }
+}
+
 mod binary_serialize {
     use super::Value;
     use chrono::{TimeZone, Utc};
     use common::BinarySerializable;
+    use common::VInt;
     use schema::Facet;
+    use schema::Vector;
     use std::io::{self, Read, Write};
 
     const TEXT_CODE: u8 = 0;
@@ -174,6 +205,7 @@ mod binary_serialize {
     const HIERARCHICAL_FACET_CODE: u8 = 3;
     const BYTES_CODE: u8 = 4;
     const DATE_CODE: u8 = 5;
+    const VECTOR_CODE: u8 = 6;
 
     impl BinarySerializable for Value {
         fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
@@ -202,6 +234,14 @@ mod binary_serialize {
                     BYTES_CODE.serialize(writer)?;
                     bytes.serialize(writer)
                 }
+                Value::Vector(ref vector) => {
+                    VECTOR_CODE.serialize(writer)?;
+                    VInt(vector.len() as u64).serialize(writer)?;
+                    for value in vector.values() {
+                        value.serialize(writer)?;
+                    }
+                    Ok(())
+                }
             }
         }
         fn deserialize<R: Read>(reader: &mut R) -> io::Result<Self> {
@@ -225,6 +265,9 @@ mod binary_serialize {
                 }
                 HIERARCHICAL_FACET_CODE => Ok(Value::Facet(Facet::deserialize(reader)?)),
                 BYTES_CODE => Ok(Value::Bytes(Vec::<u8>::deserialize(reader)?)),
+                VECTOR_CODE => Ok(Value::Vector(Vector::from(Vec::<f32>::deserialize(
+                    reader,
+                )?))),
                 _ => Err(io::Error::new(
                     io::ErrorKind::InvalidData,
                     format!("No field type is associated with code {:?}", type_code),
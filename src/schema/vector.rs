@@ -0,0 +1,104 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::ops::Deref;
+
+/// A dense vector of `f32`, the value of a vector field.
+///
+/// Vectors are compared by the bit representation of their components,
+/// so that they can be part of a `Value`. This order is total but is not
+/// the numerical order.
+#[derive(Clone, Debug, Default)]
+pub struct Vector(Vec<f32>);
+
+impl Vector {
+    /// Returns the components of the vector.
+    pub fn values(&self) -> &[f32] {
+        &self.0
+    }
+
+    /// Returns the components of the vector, consuming it.
+    pub fn into_values(self) -> Vec<f32> {
+        self.0
+    }
+
+    fn bits<'a>(&'a self) -> impl Iterator<Item = u32> + 'a {
+        self.0.iter().map(|value| value.to_bits())
+    }
+}
+
+impl From<Vec<f32>> for Vector {
+    fn from(values: Vec<f32>) -> Vector {
+        Vector(values)
+    }
+}
+
+impl<'a> From<&'a [f32]> for Vector {
+    fn from(values: &'a [f32]) -> Vector {
+        Vector(values.to_vec())
+    }
+}
+
+impl Deref for Vector {
+    type Target = [f32];
+
+    fn deref(&self) -> &[f32] {
+        &self.0
+    }
+}
+
+impl PartialEq for Vector {
+    fn eq(&self, other: &Vector) -> bool {
+        self.0.len() == other.0.len() && self.bits().eq(other.bits())
+    }
+}
+
+impl Eq for Vector {}
+
+impl PartialOrd for Vector {
+    fn partial_cmp(&self, other: &Vector) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Vector {
+    fn cmp(&self, other: &Vector) -> Ordering {
+        self.bits().cmp(other.bits())
+    }
+}
+
+impl Serialize for Vector {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Vector {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<f32>::deserialize(deserializer).map(Vector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Vector;
+    use serde_json;
+
+    #[test]
+    fn test_vector_eq_and_serialization() {
+        let vector = Vector::from(vec![1f32, -0.5f32]);
+        assert_eq!(vector, Vector::from(&[1f32, -0.5f32][..]));
+        assert_ne!(vector, Vector::from(vec![1f32]));
+        assert_eq!(vector.len(), 2);
+        let json = serde_json::to_string(&vector).unwrap();
+        assert_eq!(json, "[1.0,-0.5]");
+        let deserialized: Vector = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, vector);
+    }
+}
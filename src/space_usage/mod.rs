@@ -71,6 +71,7 @@ pub struct SegmentSpaceUsage {
     positions_idx: PerFieldSpaceUsage,
     fast_fields: PerFieldSpaceUsage,
     fieldnorms: PerFieldSpaceUsage,
+    vectors: PerFieldSpaceUsage,
 
     store: StoreSpaceUsage,
 
@@ -89,6 +90,7 @@ impl SegmentSpaceUsage {
         positions_idx: PerFieldSpaceUsage,
         fast_fields: PerFieldSpaceUsage,
         fieldnorms: PerFieldSpaceUsage,
+        vectors: PerFieldSpaceUsage,
         store: StoreSpaceUsage,
         deletes: ByteCount,
     ) -> SegmentSpaceUsage {
@@ -97,6 +99,7 @@ impl SegmentSpaceUsage {
             + positions.total()
             + fast_fields.total()
             + fieldnorms.total()
+            + vectors.total()
             + store.total()
             + deletes;
         SegmentSpaceUsage {
@@ -107,6 +110,7 @@ impl SegmentSpaceUsage {
             positions_idx,
             fast_fields,
             fieldnorms,
+            vectors,
             store,
             deletes,
             total,
@@ -129,6 +133,7 @@ impl SegmentSpaceUsage {
             TERMS => PerField(self.termdict().clone()),
             STORE => Store(self.store().clone()),
             DELETE => Basic(self.deletes()),
+            VECTORS => PerField(self.vectors().clone()),
         }
     }
 
@@ -167,6 +172,11 @@ impl SegmentSpaceUsage {
         &self.fieldnorms
     }
 
+    /// Space usage for vectors
+    pub fn vectors(&self) -> &PerFieldSpaceUsage {
+        &self.vectors
+    }
+
     /// Space usage for stored documents
     pub fn store(&self) -> &StoreSpaceUsage {
         &self.store
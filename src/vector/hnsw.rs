@@ -0,0 +1,374 @@
+use common::BinarySerializable;
+use std::cmp::{self, Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+use std::io::{self, Read, Write};
+
+/// Maximum number of neighbours of a node on the levels above the ground level.
+const MAX_NEIGHBOURS: usize = 16;
+/// Maximum number of neighbours of a node on the ground level.
+const MAX_GROUND_NEIGHBOURS: usize = 2 * MAX_NEIGHBOURS;
+/// Number of candidate neighbours considered when inserting a node.
+const EF_CONSTRUCTION: usize = 100;
+/// Serialized entry point of an empty graph.
+const NO_ENTRY_POINT: u32 = u32::max_value();
+
+/// A node of the graph, with its similarity to the vector searched.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ScoredNode {
+    pub score: f32,
+    pub ord: u32,
+}
+
+impl PartialEq for ScoredNode {
+    fn eq(&self, other: &ScoredNode) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ScoredNode {}
+
+impl PartialOrd for ScoredNode {
+    fn partial_cmp(&self, other: &ScoredNode) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScoredNode {
+    // the greatest node is the most similar one, ties being
+    // broken in favor of the lowest ordinal.
+    fn cmp(&self, other: &ScoredNode) -> Ordering {
+        self.score
+            .partial_cmp(&other.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.ord.cmp(&self.ord))
+    }
+}
+
+fn max_neighbours(level: usize) -> usize {
+    if level == 0 {
+        MAX_GROUND_NEIGHBOURS
+    } else {
+        MAX_NEIGHBOURS
+    }
+}
+
+/// Returns the highest level of a node.
+///
+/// Levels follow a geometric distribution, drawn from a hash of the ordinal
+/// of the node so that the graph of a given list of vectors is deterministic.
+fn node_level(ord: u32) -> usize {
+    // splitmix64
+    let mut hash = u64::from(ord).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    hash ^= hash >> 31;
+    // uniform in `(0, 1]`
+    let uniform = ((hash >> 11) as f64 + 1f64) / (1u64 << 53) as f64;
+    (-uniform.ln() / (MAX_NEIGHBOURS as f64).ln()) as usize
+}
+
+/// Hierarchical Navigable Small World graph over the vectors of a field of a segment.
+///
+/// Nodes are identified by the ordinal of their vector. Each node belongs
+/// to all of the levels up to its own level, and is linked on each of them to
+/// some of its nearest neighbours.
+///
+/// A search greedily walks down the levels from the entry point of the graph,
+/// the node with the highest level, and explores the ground level, which
+/// contains all of the nodes, from the closest node found.
+#[derive(Debug, Default)]
+pub(crate) struct HnswGraph {
+    entry_point: Option<u32>,
+    // `neighbours[ord][level]` are the neighbours of the node `ord` on `level`.
+    neighbours: Vec<Vec<Vec<u32>>>,
+}
+
+impl HnswGraph {
+    /// Builds the graph of `num_nodes` nodes, given the similarity between two nodes.
+    pub fn build<S: Fn(u32, u32) -> f32>(num_nodes: usize, similarity: S) -> HnswGraph {
+        let mut graph = HnswGraph {
+            entry_point: None,
+            neighbours: Vec::with_capacity(num_nodes),
+        };
+        for ord in 0..num_nodes as u32 {
+            graph.insert(ord, &similarity);
+        }
+        graph
+    }
+
+    /// Returns the number of nodes of the graph.
+    pub fn num_nodes(&self) -> usize {
+        self.neighbours.len()
+    }
+
+    fn max_level(&self, entry_point: u32) -> usize {
+        self.neighbours[entry_point as usize].len() - 1
+    }
+
+    fn insert(&mut self, ord: u32, similarity: &Fn(u32, u32) -> f32) {
+        let level = node_level(ord);
+        self.neighbours.push(vec![Vec::new(); level + 1]);
+        let entry_point = if let Some(entry_point) = self.entry_point {
+            entry_point
+        } else {
+            self.entry_point = Some(ord);
+            return;
+        };
+        let max_level = self.max_level(entry_point);
+        let score = |other: u32| similarity(ord, other);
+        let mut entry_points = vec![ScoredNode {
+            score: score(entry_point),
+            ord: entry_point,
+        }];
+        for search_level in (level + 1..=max_level).rev() {
+            entry_points = self.search_level(&entry_points, 1, search_level, &score, &|_| true);
+        }
+        for insert_level in (0..=cmp::min(level, max_level)).rev() {
+            let candidates = self.search_level(
+                &entry_points,
+                EF_CONSTRUCTION,
+                insert_level,
+                &score,
+                &|_| true,
+            );
+            let neighbours: Vec<u32> = candidates
+                .iter()
+                .take(max_neighbours(insert_level))
+                .map(|node| node.ord)
+                .collect();
+            for &neighbour in &neighbours {
+                self.connect(neighbour, ord, insert_level, similarity);
+            }
+            self.neighbours[ord as usize][insert_level] = neighbours;
+            entry_points = candidates;
+        }
+        if level > max_level {
+            self.entry_point = Some(ord);
+        }
+    }
+
+    /// Adds `new_neighbour` to the neighbours of `ord`, only keeping
+    /// its most similar neighbours.
+    fn connect(
+        &mut self,
+        ord: u32,
+        new_neighbour: u32,
+        level: usize,
+        similarity: &Fn(u32, u32) -> f32,
+    ) {
+        let max_neighbours = max_neighbours(level);
+        let neighbours = &mut self.neighbours[ord as usize][level];
+        neighbours.push(new_neighbour);
+        if neighbours.len() > max_neighbours {
+            let mut scored_neighbours: Vec<ScoredNode> = neighbours
+                .iter()
+                .map(|&neighbour| ScoredNode {
+                    score: similarity(ord, neighbour),
+                    ord: neighbour,
+                })
+                .collect();
+            scored_neighbours.sort_by(|left, right| right.cmp(left));
+            *neighbours = scored_neighbours
+                .into_iter()
+                .take(max_neighbours)
+                .map(|node| node.ord)
+                .collect();
+        }
+    }
+
+    /// Returns the `ef` accepted nodes of `level` that are the most similar
+    /// to the searched vector, from the most similar to the least similar.
+    ///
+    /// Nodes that are not accepted are still explored.
+    fn search_level(
+        &self,
+        entry_points: &[ScoredNode],
+        ef: usize,
+        level: usize,
+        score: &Fn(u32) -> f32,
+        accept: &Fn(u32) -> bool,
+    ) -> Vec<ScoredNode> {
+        let mut visited: HashSet<u32> = entry_points.iter().map(|node| node.ord).collect();
+        let mut candidates: BinaryHeap<ScoredNode> = entry_points.iter().cloned().collect();
+        // min-heap of the best nodes found so far.
+        let mut results: BinaryHeap<Reverse<ScoredNode>> = BinaryHeap::with_capacity(ef + 1);
+        for &node in entry_points {
+            if accept(node.ord) {
+                results.push(Reverse(node));
+                if results.len() > ef {
+                    results.pop();
+                }
+            }
+        }
+        while let Some(candidate) = candidates.pop() {
+            let is_full = results.len() >= ef;
+            if is_full && results.peek().map(|worst| candidate < worst.0) == Some(true) {
+                break;
+            }
+            for &neighbour in &self.neighbours[candidate.ord as usize][level] {
+                if !visited.insert(neighbour) {
+                    continue;
+                }
+                let node = ScoredNode {
+                    score: score(neighbour),
+                    ord: neighbour,
+                };
+                let is_full = results.len() >= ef;
+                if is_full && results.peek().map(|worst| node < worst.0) == Some(true) {
+                    continue;
+                }
+                candidates.push(node);
+                if accept(neighbour) {
+                    results.push(Reverse(node));
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+        results
+            .into_sorted_vec()
+            .into_iter()
+            .map(|node| node.0)
+            .collect()
+    }
+
+    /// Returns the `ef` accepted nodes that are approximately the most similar
+    /// to the searched vector, from the most similar to the least similar.
+    ///
+    /// `score` computes the similarity of a node to the searched vector.
+    pub fn search(
+        &self,
+        ef: usize,
+        score: &Fn(u32) -> f32,
+        accept: &Fn(u32) -> bool,
+    ) -> Vec<ScoredNode> {
+        let entry_point = if let Some(entry_point) = self.entry_point {
+            entry_point
+        } else {
+            return Vec::new();
+        };
+        let mut entry_points = vec![ScoredNode {
+            score: score(entry_point),
+            ord: entry_point,
+        }];
+        for level in (1..=self.max_level(entry_point)).rev() {
+            entry_points = self.search_level(&entry_points, 1, level, score, &|_| true);
+        }
+        self.search_level(&entry_points, ef, 0, score, accept)
+    }
+}
+
+impl BinarySerializable for HnswGraph {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.entry_point
+            .unwrap_or(NO_ENTRY_POINT)
+            .serialize(writer)?;
+        self.neighbours.serialize(writer)
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<HnswGraph> {
+        let entry_point = u32::deserialize(reader)?;
+        let neighbours = Vec::<Vec<Vec<u32>>>::deserialize(reader)?;
+        let entry_point = if entry_point == NO_ENTRY_POINT {
+            None
+        } else {
+            Some(entry_point)
+        };
+        Ok(HnswGraph {
+            entry_point,
+            neighbours,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{node_level, HnswGraph, ScoredNode};
+    use common::BinarySerializable;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use vector::VectorSimilarity;
+
+    fn exact_search(vectors: &[Vec<f32>], query: &[f32], k: usize) -> Vec<u32> {
+        let mut nodes: Vec<ScoredNode> = vectors
+            .iter()
+            .enumerate()
+            .map(|(ord, vector)| ScoredNode {
+                score: VectorSimilarity::Cosine.compute(query, vector),
+                ord: ord as u32,
+            })
+            .collect();
+        nodes.sort_by(|left, right| right.cmp(left));
+        nodes.into_iter().take(k).map(|node| node.ord).collect()
+    }
+
+    #[test]
+    fn test_node_level() {
+        let num_nodes_above_ground = (0..10_000u32).filter(|&ord| node_level(ord) > 0).count();
+        // one node out of 16 is expected above the ground level.
+        assert!(num_nodes_above_ground > 400);
+        assert!(num_nodes_above_ground < 900);
+    }
+
+    #[test]
+    fn test_hnsw_recall() {
+        let mut rng = StdRng::from_seed([3u8; 32]);
+        let vectors: Vec<Vec<f32>> = (0..2_000)
+            .map(|_| (0..8).map(|_| rng.gen_range(-1f32, 1f32)).collect())
+            .collect();
+        let similarity = |left: u32, right: u32| {
+            VectorSimilarity::Cosine.compute(&vectors[left as usize], &vectors[right as usize])
+        };
+        let graph = HnswGraph::build(vectors.len(), similarity);
+        assert_eq!(graph.num_nodes(), 2_000);
+
+        let mut num_found = 0;
+        for _ in 0..20 {
+            let query: Vec<f32> = (0..8).map(|_| rng.gen_range(-1f32, 1f32)).collect();
+            let score = |ord: u32| VectorSimilarity::Cosine.compute(&query, &vectors[ord as usize]);
+            let nodes = graph.search(50, &score, &|_| true);
+            assert_eq!(nodes.len(), 50);
+            assert!(nodes.windows(2).all(|pair| pair[0] >= pair[1]));
+            let approximate: Vec<u32> = nodes.iter().take(10).map(|node| node.ord).collect();
+            num_found += exact_search(&vectors, &query, 10)
+                .iter()
+                .filter(|ord| approximate.contains(ord))
+                .count();
+        }
+        // recall at 10 over 20 queries.
+        assert!(num_found >= 180, "recall too low: {}", num_found);
+
+        let query = vectors[7].clone();
+        let score = |ord: u32| VectorSimilarity::Cosine.compute(&query, &vectors[ord as usize]);
+        let nodes = graph.search(10, &score, &|ord| ord % 2 == 0);
+        assert_eq!(nodes.len(), 10);
+        assert!(nodes.iter().all(|node| node.ord % 2 == 0));
+        let nodes = graph.search(10, &score, &|ord| ord == 7);
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].ord, 7);
+    }
+
+    #[test]
+    fn test_hnsw_serialization() {
+        let vectors: Vec<Vec<f32>> = (0..100)
+            .map(|i| vec![(i as f32).cos(), (i as f32).sin()])
+            .collect();
+        let graph = HnswGraph::build(vectors.len(), |left, right| {
+            VectorSimilarity::Cosine.compute(&vectors[left as usize], &vectors[right as usize])
+        });
+        let mut buffer = Vec::new();
+        graph.serialize(&mut buffer).unwrap();
+        let deserialized = HnswGraph::deserialize(&mut &buffer[..]).unwrap();
+        assert_eq!(deserialized.entry_point, graph.entry_point);
+        assert_eq!(deserialized.neighbours, graph.neighbours);
+
+        let empty_graph = HnswGraph::build(0, |_, _| 0f32);
+        let mut buffer = Vec::new();
+        empty_graph.serialize(&mut buffer).unwrap();
+        let deserialized = HnswGraph::deserialize(&mut &buffer[..]).unwrap();
+        assert_eq!(deserialized.num_nodes(), 0);
+        assert!(deserialized.search(10, &|_| 0f32, &|_| true).is_empty());
+    }
+}
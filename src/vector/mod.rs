@@ -0,0 +1,144 @@
+//! Dense vectors associated to the documents, and their nearest neighbour search.
+//!
+//! A document holds at most one `f32` vector of a fixed dimension in each
+//! vector field (see `SchemaBuilder::add_vector_field`).
+//!
+//! The vectors of a segment are stored in the `.vec` file of the segment,
+//! together with a [Hierarchical Navigable Small World](https://arxiv.org/abs/1603.09320)
+//! graph built when the segment is serialized, and rebuilt when segments are merged.
+//!
+//! The graph makes it possible to find the approximate nearest neighbours of a vector
+//! without computing its similarity with all of the vectors of the segment.
+//! It is built for the cosine similarity, but can be searched with any
+//! [`VectorSimilarity`](./enum.VectorSimilarity.html).
+mod hnsw;
+mod reader;
+mod serializer;
+mod writer;
+
+pub use self::reader::VectorReader;
+pub use self::serializer::VectorsSerializer;
+pub use self::writer::VectorsWriter;
+
+use schema::{Schema, Type};
+use Score;
+
+/// Similarity between two vectors, used to rank the nearest neighbours of a vector.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VectorSimilarity {
+    /// Cosine of the angle between the vectors, in `[-1, 1]`.
+    ///
+    /// The similarity with a null vector is `0`.
+    Cosine,
+    /// Dot product of the vectors.
+    ///
+    /// It is the same as the cosine similarity for normalized vectors.
+    DotProduct,
+}
+
+impl VectorSimilarity {
+    /// Computes the similarity between two vectors of the same dimension.
+    pub fn compute(self, left: &[f32], right: &[f32]) -> Score {
+        match self {
+            VectorSimilarity::Cosine => cosine(dot(left, right), norm(left), norm(right)),
+            VectorSimilarity::DotProduct => dot(left, right),
+        }
+    }
+}
+
+impl Default for VectorSimilarity {
+    fn default() -> Self {
+        VectorSimilarity::Cosine
+    }
+}
+
+/// Returns true if the schema has vector fields, i.e. if its segments have a vectors file.
+pub(crate) fn has_vector_fields(schema: &Schema) -> bool {
+    schema
+        .fields()
+        .iter()
+        .any(|field_entry| field_entry.field_type().value_type() == Type::Vector)
+}
+
+fn dot(left: &[f32], right: &[f32]) -> f32 {
+    assert_eq!(left.len(), right.len());
+    left.iter().zip(right).map(|(l, r)| l * r).sum()
+}
+
+fn norm(vector: &[f32]) -> f32 {
+    dot(vector, vector).sqrt()
+}
+
+fn cosine(dot: f32, left_norm: f32, right_norm: f32) -> f32 {
+    if left_norm == 0f32 || right_norm == 0f32 {
+        0f32
+    } else {
+        dot / (left_norm * right_norm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::VectorSimilarity;
+    use directory::Directory;
+    use schema::{Schema, Vector, TEXT};
+    use tests::assert_nearly_equals;
+    use Index;
+    use SegmentComponent;
+
+    #[test]
+    fn test_vector_similarity() {
+        let left = [1f32, 0f32];
+        let right = [3f32, 4f32];
+        assert_nearly_equals(0.6, VectorSimilarity::Cosine.compute(&left, &right));
+        assert_nearly_equals(3f32, VectorSimilarity::DotProduct.compute(&left, &right));
+        assert_eq!(VectorSimilarity::Cosine.compute(&[0f32, 0f32], &right), 0f32);
+    }
+
+    fn has_vectors_file(index: &Index) -> bool {
+        let segment_metas = index.searchable_segment_metas().unwrap();
+        assert_eq!(segment_metas.len(), 1);
+        let vectors_path = segment_metas[0].relative_path(SegmentComponent::VECTORS);
+        index.directory().exists(&vectors_path)
+    }
+
+    #[test]
+    fn test_vectors_file_only_for_vector_fields() {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(text=>"a"));
+            index_writer.commit().unwrap();
+        }
+        assert!(!has_vectors_file(&index));
+        let reader = index.reader().unwrap();
+        let searcher = reader.searcher();
+        assert_eq!(searcher.num_docs(), 1);
+        assert!(searcher.segment_reader(0).vector_reader(text).is_err());
+
+        let mut schema_builder = Schema::builder();
+        let embedding = schema_builder.add_vector_field("embedding", 2);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(embedding=>Vector::from(vec![1f32, 0f32])));
+            index_writer.commit().unwrap();
+        }
+        assert!(has_vectors_file(&index));
+        {
+            let searcher = index.reader().unwrap().searcher();
+            let vector_reader = searcher.segment_reader(0).vector_reader(embedding).unwrap();
+            assert_eq!(vector_reader.num_vectors(), 1);
+        }
+
+        // a missing vectors file is an error, not an empty vector index.
+        let segment_metas = index.searchable_segment_metas().unwrap();
+        let vectors_path = segment_metas[0].relative_path(SegmentComponent::VECTORS);
+        index.directory().delete(&vectors_path).unwrap();
+        assert!(index.reader().is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use super::hnsw::{HnswGraph, ScoredNode};
+use super::{cosine, dot, norm, VectorSimilarity};
+use common::BinarySerializable;
+use directory::ReadOnlySource;
+use std::cmp;
+use std::io;
+use DocId;
+use Score;
+
+/// Reads the vectors of a field of a segment, and searches for their nearest neighbours.
+///
+/// The vectors and the graph are decoded in memory when the reader is opened.
+pub struct VectorReader {
+    dim: usize,
+    doc_ids: Vec<DocId>,
+    values: Vec<f32>,
+    norms: Vec<f32>,
+    graph: HnswGraph,
+}
+
+impl VectorReader {
+    /// Opens a vector reader given its data source.
+    pub fn open(source: &ReadOnlySource) -> io::Result<VectorReader> {
+        let reader = &mut source.as_slice();
+        let dim = u32::deserialize(reader)? as usize;
+        let num_vectors = u32::deserialize(reader)? as usize;
+        let doc_ids = (0..num_vectors)
+            .map(|_| DocId::deserialize(reader))
+            .collect::<io::Result<Vec<DocId>>>()?;
+        let values = (0..num_vectors * dim)
+            .map(|_| f32::deserialize(reader))
+            .collect::<io::Result<Vec<f32>>>()?;
+        let graph = HnswGraph::deserialize(reader)?;
+        if graph.num_nodes() != num_vectors {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "The HNSW graph does not match the vectors.",
+            ));
+        }
+        let norms = (0..num_vectors)
+            .map(|ord| norm(&values[ord * dim..(ord + 1) * dim]))
+            .collect();
+        Ok(VectorReader {
+            dim,
+            doc_ids,
+            values,
+            norms,
+            graph,
+        })
+    }
+
+    /// Creates a reader without any vector.
+    pub fn empty(dim: usize) -> VectorReader {
+        VectorReader {
+            dim,
+            doc_ids: Vec::new(),
+            values: Vec::new(),
+            norms: Vec::new(),
+            graph: HnswGraph::default(),
+        }
+    }
+
+    /// Returns the dimension of the vectors.
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Returns the number of vectors, i.e. the number of documents having a vector.
+    pub fn num_vectors(&self) -> usize {
+        self.doc_ids.len()
+    }
+
+    /// Returns the sorted ids of the documents having a vector.
+    pub fn doc_ids(&self) -> &[DocId] {
+        &self.doc_ids
+    }
+
+    /// Returns the vector of a document, if it has one.
+    pub fn vector(&self, doc: DocId) -> Option<&[f32]> {
+        self.doc_ids
+            .binary_search(&doc)
+            .ok()
+            .map(|ord| self.vector_by_ord(ord as u32))
+    }
+
+    fn vector_by_ord(&self, ord: u32) -> &[f32] {
+        let start = ord as usize * self.dim;
+        &self.values[start..start + self.dim]
+    }
+
+    /// Returns the `k` accepted documents whose vectors are the most similar to `query`,
+    /// with their similarity, from the most similar to the least similar.
+    ///
+    /// The search is approximate: `num_candidates` documents are collected while
+    /// exploring the HNSW graph, and the best `k` of them are returned.
+    /// Increasing `num_candidates` improves the accuracy at the expense of speed.
+    /// If the segment has no more than `num_candidates` vectors, all of them are
+    /// compared to the query and the result is exact.
+    ///
+    /// # Panics
+    /// If the dimension of `query` is not the dimension of the field.
+    pub fn search(
+        &self,
+        query: &[f32],
+        k: usize,
+        num_candidates: usize,
+        similarity: VectorSimilarity,
+        accept: &Fn(DocId) -> bool,
+    ) -> Vec<(DocId, Score)> {
+        assert_eq!(
+            query.len(),
+            self.dim,
+            "The query vector does not have the dimension of the field."
+        );
+        let query_norm = norm(query);
+        let score = |ord: u32| {
+            let dot = dot(query, self.vector_by_ord(ord));
+            match similarity {
+                VectorSimilarity::Cosine => cosine(dot, query_norm, self.norms[ord as usize]),
+                VectorSimilarity::DotProduct => dot,
+            }
+        };
+        let accept_ord = |ord: u32| accept(self.doc_ids[ord as usize]);
+        let num_candidates = cmp::max(num_candidates, k);
+        let mut nodes = if self.num_vectors() <= num_candidates {
+            let mut nodes: Vec<ScoredNode> = (0..self.num_vectors() as u32)
+                .filter(|&ord| accept_ord(ord))
+                .map(|ord| ScoredNode {
+                    score: score(ord),
+                    ord,
+                })
+                .collect();
+            nodes.sort_by(|left, right| right.cmp(left));
+            nodes
+        } else {
+            self.graph.search(num_candidates, &score, &accept_ord)
+        };
+        nodes.truncate(k);
+        nodes
+            .into_iter()
+            .map(|node| (self.doc_ids[node.ord as usize], node.score))
+            .collect()
+    }
+}
@@ -0,0 +1,61 @@
+use super::hnsw::HnswGraph;
+use super::VectorSimilarity;
+use common::BinarySerializable;
+use common::CompositeWrite;
+use directory::WritePtr;
+use schema::Field;
+use std::io;
+use std::io::Write;
+use DocId;
+
+/// The vectors serializer is in charge of the serialization
+/// of the vectors of all of the vector fields, together with
+/// their HNSW graph.
+pub struct VectorsSerializer {
+    composite_write: CompositeWrite,
+}
+
+impl VectorsSerializer {
+    /// Constructor
+    pub fn from_write(write: WritePtr) -> io::Result<VectorsSerializer> {
+        let composite_write = CompositeWrite::wrap(write);
+        Ok(VectorsSerializer { composite_write })
+    }
+
+    /// Serializes the vectors of a field, building their HNSW graph.
+    ///
+    /// * dim     - the dimension of the vectors
+    /// * doc_ids - the sorted ids of the documents having a vector
+    /// * values  - the concatenated vectors of these documents
+    pub fn serialize_field(
+        &mut self,
+        field: Field,
+        dim: usize,
+        doc_ids: &[DocId],
+        values: &[f32],
+    ) -> io::Result<()> {
+        assert_eq!(doc_ids.len() * dim, values.len());
+        let vector = |ord: u32| &values[ord as usize * dim..(ord as usize + 1) * dim];
+        let graph = HnswGraph::build(doc_ids.len(), |left, right| {
+            VectorSimilarity::Cosine.compute(vector(left), vector(right))
+        });
+        let write = self.composite_write.for_field(field);
+        (dim as u32).serialize(write)?;
+        (doc_ids.len() as u32).serialize(write)?;
+        for doc_id in doc_ids {
+            doc_id.serialize(write)?;
+        }
+        for value in values {
+            value.serialize(write)?;
+        }
+        graph.serialize(write)?;
+        write.flush()?;
+        Ok(())
+    }
+
+    /// Clean up / flush / close
+    pub fn close(self) -> io::Result<()> {
+        self.composite_write.close()?;
+        Ok(())
+    }
+}
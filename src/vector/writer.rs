@@ -0,0 +1,117 @@
+use super::VectorsSerializer;
+use schema::Document;
+use schema::Field;
+use schema::FieldType;
+use schema::Schema;
+use schema::Value;
+use std::io;
+use std::mem;
+use DocId;
+
+/// Vectors of a field, in the order of their documents.
+struct FieldVectorsWriter {
+    dim: usize,
+    doc_ids: Vec<DocId>,
+    values: Vec<f32>,
+}
+
+impl FieldVectorsWriter {
+    fn new(dim: usize) -> FieldVectorsWriter {
+        FieldVectorsWriter {
+            dim,
+            doc_ids: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, doc: DocId, field: Field, vector: &[f32]) {
+        if self.doc_ids.last() == Some(&doc) {
+            warn!(
+                "Document {} has several vectors for field {:?}, only the first one is kept.",
+                doc, field
+            );
+            return;
+        }
+        if vector.len() != self.dim {
+            warn!(
+                "Ignoring the vector of dimension {} of document {} for field {:?} \
+                 of dimension {}.",
+                vector.len(),
+                doc,
+                field,
+                self.dim
+            );
+            return;
+        }
+        self.doc_ids.push(doc);
+        self.values.extend_from_slice(vector);
+    }
+
+    fn mem_usage(&self) -> usize {
+        self.doc_ids.capacity() * mem::size_of::<DocId>()
+            + self.values.capacity() * mem::size_of::<f32>()
+    }
+}
+
+/// The `VectorsWriter` is in charge of buffering the vectors of the documents
+/// of a segment, for each vector field.
+///
+/// A document has at most one vector per field: the following ones,
+/// as well as the vectors that do not have the dimension of the field,
+/// are ignored.
+pub struct VectorsWriter {
+    // indexed by field id, `None` for the fields that are not vector fields.
+    field_writers: Vec<Option<FieldVectorsWriter>>,
+}
+
+impl VectorsWriter {
+    /// Initialize with state for tracking the vector fields
+    /// specified in the schema.
+    pub fn for_schema(schema: &Schema) -> VectorsWriter {
+        let field_writers = schema
+            .fields()
+            .iter()
+            .map(|field_entry| match *field_entry.field_type() {
+                FieldType::Vector(dim) => Some(FieldVectorsWriter::new(dim)),
+                _ => None,
+            })
+            .collect();
+        VectorsWriter { field_writers }
+    }
+
+    /// Records the vectors of a document.
+    pub fn add_document(&mut self, doc: DocId, document: &Document) {
+        for field_value in document.field_values() {
+            let field = field_value.field();
+            if let Some(field_writer) = self.field_writers[field.0 as usize].as_mut() {
+                if let Value::Vector(ref vector) = *field_value.value() {
+                    field_writer.record(doc, field, vector);
+                }
+            }
+        }
+    }
+
+    /// Returns the memory used by the buffered vectors, in bytes.
+    pub fn mem_usage(&self) -> usize {
+        self.field_writers
+            .iter()
+            .flat_map(Option::as_ref)
+            .map(FieldVectorsWriter::mem_usage)
+            .sum()
+    }
+
+    /// Serializes the vectors of all of the vector fields.
+    pub fn serialize(&self, vectors_serializer: &mut VectorsSerializer) -> io::Result<()> {
+        for (field_id, field_writer_opt) in self.field_writers.iter().enumerate() {
+            if let Some(ref field_writer) = *field_writer_opt {
+                vectors_serializer.serialize_field(
+                    Field(field_id as u32),
+                    field_writer.dim,
+                    &field_writer.doc_ids,
+                    &field_writer.values,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}